@@ -8,11 +8,31 @@ use modules::hyprland::appearance::{
     apply_appearance_conf, get_current_appearance_config, get_cursor_themes, get_gtk_themes_list,
     get_hyprland_config, save_hyprland_config,
 };
+use modules::hyprland::cheatsheet::export_keybind_cheatsheet;
+use modules::hyprland::dispatchers::get_dispatchers;
 use modules::hyprland::display::{get_displays, save_displays};
-use modules::hyprland::env::{get_env_vars, save_env_vars};
-use modules::hyprland::keybinds::{get_keybinds, save_keybinds};
+use modules::hyprland::env::{
+    add_env_var, apply_env_preset, delete_env_var, get_env_presets, get_env_vars,
+    remove_env_preset, save_env_vars, update_env_var, validate_env_vars,
+};
+use modules::hyprland::keybinds::{
+    add_keybind, create_submap, delete_keybind, delete_submap, get_keybinds, get_submaps,
+    rename_submap, save_keybinds, update_keybind,
+};
+use modules::hyprland::keysyms::resolve_captured_key;
 use modules::hyprland::kitty::set_kitty_theme;
-use modules::hyprland::startup::{get_startup_commands, save_startup_commands};
+use modules::hyprland::nightlight::{
+    disable_night_light, enable_night_light, get_night_light_status, preview_night_light,
+    start_night_light_scheduler, NightLightState,
+};
+use modules::hyprland::services::{
+    convert_startup_to_unit, get_user_unit_logs, get_user_units, manage_user_unit,
+};
+use modules::hyprland::session_env::{get_effective_env, sync_env_vars};
+use modules::hyprland::startup::{
+    get_autostart_entries, get_startup_commands, get_startup_status, save_startup_commands,
+    set_autostart_enabled, set_startup_entry_enabled, test_run_startup_command,
+};
 use modules::hyprland::wallpaper::{
     get_current_wallpaper_path, get_wallpaper_base64, set_wallpaper,
 };
@@ -22,10 +42,30 @@ use modules::systems::bluetooth::{
     connect_bluetooth, get_bluetooth_devices, get_bluetooth_status, start_scan, stop_scan,
     toggle_bluetooth, BluetoothState,
 };
+use modules::systems::ethernet::{
+    add_ethernet_connection, get_ethernet_config, get_ethernet_connections, get_ethernet_devices,
+    set_ethernet_8021x, set_ethernet_autoconnect, set_ethernet_config,
+};
+use modules::systems::hotspot::{get_hotspot_qr, get_hotspot_status, start_hotspot, stop_hotspot};
+use modules::systems::proxy::{
+    get_connection_proxy, get_system_proxy, set_connection_proxy, set_system_proxy,
+};
 use modules::systems::system::{check_app_installed, get_gtk_theme, get_system_info};
-use modules::systems::vpn::{connect_vpn, disconnect_vpn, get_vpn_connections, import_vpn};
+use modules::systems::vpn::{
+    connect_vpn, delete_vpn, disconnect_vpn, export_vpn, get_vpn_auto_connect, get_vpn_connections,
+    import_vpn, rename_vpn, set_vpn_auto_connect, set_vpn_autoconnect,
+};
+use modules::systems::vpn_plugins::{create_vpn_connection, get_vpn_plugins};
 use modules::systems::wifi::{
-    connect_wifi, get_wifi_config, get_wifi_status, scan_wifi, set_wifi_config, toggle_wifi,
+    connect_wifi, connect_wifi_network, forget_wifi_network, get_active_wifi_link,
+    get_saved_networks, get_wifi_access_points, get_wifi_config, get_wifi_share_qr,
+    get_wifi_status, scan_wifi, set_wifi_autoconnect, set_wifi_config, set_wifi_mac_randomization,
+    set_wifi_metered, set_wifi_pin, set_wifi_priority, toggle_wifi,
+};
+use modules::systems::wifi_monitor::{set_wifi_page_active, start_wifi_monitor, WifiMonitorState};
+use modules::systems::wireguard::{
+    export_wireguard_config, generate_wireguard_keypair, generate_wireguard_preshared_key,
+    get_wireguard_connection, get_wireguard_public_key, save_wireguard_connection,
 };
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
@@ -34,13 +74,53 @@ pub fn run() {
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_dialog::init())
         .manage(BluetoothState::new())
+        .manage(NightLightState::new())
+        .manage(WifiMonitorState::new())
+        .setup(|app| {
+            start_night_light_scheduler(app.handle().clone());
+            start_wifi_monitor(app.handle().clone());
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             get_wifi_status,
             toggle_wifi,
             scan_wifi,
             connect_wifi,
+            connect_wifi_network,
             get_wifi_config,
             set_wifi_config,
+            get_saved_networks,
+            forget_wifi_network,
+            set_wifi_autoconnect,
+            set_wifi_priority,
+            set_wifi_metered,
+            set_wifi_mac_randomization,
+            get_wifi_share_qr,
+            get_wifi_access_points,
+            set_wifi_page_active,
+            get_active_wifi_link,
+            set_wifi_pin,
+            get_hotspot_status,
+            start_hotspot,
+            stop_hotspot,
+            get_hotspot_qr,
+            get_ethernet_devices,
+            get_ethernet_connections,
+            add_ethernet_connection,
+            get_ethernet_config,
+            set_ethernet_config,
+            set_ethernet_autoconnect,
+            set_ethernet_8021x,
+            get_system_proxy,
+            set_system_proxy,
+            get_connection_proxy,
+            set_connection_proxy,
+            generate_wireguard_keypair,
+            generate_wireguard_preshared_key,
+            get_wireguard_public_key,
+            get_wireguard_connection,
+            save_wireguard_connection,
+            export_wireguard_config,
             get_bluetooth_status,
             toggle_bluetooth,
             start_scan,
@@ -64,18 +144,58 @@ pub fn run() {
             apply_appearance_conf,
             get_current_appearance_config,
             set_kitty_theme,
+            get_night_light_status,
+            enable_night_light,
+            disable_night_light,
+            preview_night_light,
             get_hyprland_config,
             save_hyprland_config,
             get_startup_commands,
             save_startup_commands,
+            set_startup_entry_enabled,
+            get_startup_status,
+            test_run_startup_command,
+            get_user_units,
+            get_user_unit_logs,
+            manage_user_unit,
+            convert_startup_to_unit,
+            get_autostart_entries,
+            set_autostart_enabled,
             get_keybinds,
             save_keybinds,
+            add_keybind,
+            update_keybind,
+            delete_keybind,
+            get_submaps,
+            create_submap,
+            rename_submap,
+            delete_submap,
+            get_dispatchers,
+            export_keybind_cheatsheet,
+            resolve_captured_key,
             get_env_vars,
             save_env_vars,
+            add_env_var,
+            update_env_var,
+            delete_env_var,
+            validate_env_vars,
+            get_env_presets,
+            apply_env_preset,
+            remove_env_preset,
+            get_effective_env,
+            sync_env_vars,
             get_vpn_connections,
             connect_vpn,
             disconnect_vpn,
             import_vpn,
+            delete_vpn,
+            rename_vpn,
+            set_vpn_autoconnect,
+            export_vpn,
+            get_vpn_auto_connect,
+            set_vpn_auto_connect,
+            get_vpn_plugins,
+            create_vpn_connection,
             check_updates,
             update_system,
             get_installed_apps,
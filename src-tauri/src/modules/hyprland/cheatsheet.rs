@@ -0,0 +1,269 @@
+use crate::modules::hyprland::dispatchers::find_dispatcher;
+use crate::modules::hyprland::keybinds::{get_keybinds, Keybind};
+use serde::Serialize;
+use std::fs;
+
+/// Categories in the order they appear in the cheatsheet, with their headings.
+const CATEGORIES: [(&str, &str); 9] = [
+    ("apps", "Applications"),
+    ("windows", "Windows"),
+    ("focus", "Focus"),
+    ("workspaces", "Workspaces"),
+    ("monitors", "Monitors"),
+    ("groups", "Groups"),
+    ("session", "Session"),
+    ("plugins", "Plugins"),
+    ("other", "Other"),
+];
+
+/// A single cheatsheet line, also the element type of the JSON export.
+#[derive(Serialize, Debug)]
+pub struct CheatsheetEntry {
+    pub combo: String,
+    pub description: String,
+    pub category: String,
+    pub submap: String,
+    pub dispatcher: String,
+    pub args: String,
+}
+
+/// Renders a modifier set and key as e.g. `Super + Shift + Q`.
+fn readable_combo(keybind: &Keybind) -> String {
+    let mut parts: Vec<String> = keybind
+        .normalized_modifiers
+        .iter()
+        .map(|m| {
+            let mut chars = m.chars();
+            match chars.next() {
+                Some(first) => first.to_string() + &chars.as_str().to_lowercase(),
+                None => String::new(),
+            }
+        })
+        .collect();
+    parts.push(readable_key(&keybind.key));
+    parts.join(" + ")
+}
+
+/// Maps Hyprland key names to the labels printed on keyboards and mice.
+fn readable_key(key: &str) -> String {
+    let key = key.trim();
+    let label = match key.to_lowercase().as_str() {
+        "mouse:272" => "Left Click",
+        "mouse:273" => "Right Click",
+        "mouse:274" => "Middle Click",
+        "mouse_down" => "Scroll Down",
+        "mouse_up" => "Scroll Up",
+        "return" => "Enter",
+        "escape" => "Esc",
+        "space" => "Space",
+        "tab" => "Tab",
+        "backspace" => "Backspace",
+        "delete" => "Delete",
+        "print" => "Print",
+        "left" => "←",
+        "right" => "→",
+        "up" => "↑",
+        "down" => "↓",
+        _ => "",
+    };
+
+    if !label.is_empty() {
+        label.to_string()
+    } else if let Some(media) = key.strip_prefix("XF86") {
+        media.to_string()
+    } else if key.chars().count() == 1 {
+        key.to_uppercase()
+    } else {
+        key.to_string()
+    }
+}
+
+/// Describes what a binding does, preferring its `bindd` description.
+fn action_description(keybind: &Keybind) -> String {
+    match &keybind.description {
+        Some(description) if !description.trim().is_empty() => description.trim().to_string(),
+        _ if keybind.args.is_empty() => keybind.dispatcher.clone(),
+        _ => format!("{} {}", keybind.dispatcher, keybind.args),
+    }
+}
+
+/// Resolves the catalog category of a binding's dispatcher.
+fn dispatcher_category(dispatcher: &str) -> &'static str {
+    if dispatcher.contains(':') {
+        return "plugins";
+    }
+    find_dispatcher(dispatcher)
+        .map(|d| d.category)
+        .unwrap_or("other")
+}
+
+/// Builds cheatsheet entries ordered by submap (global first), category and file order.
+fn build_entries() -> Result<Vec<CheatsheetEntry>, String> {
+    let keybinds = get_keybinds()?.keybinds;
+
+    let mut submaps: Vec<String> = vec![String::new()];
+    for kb in &keybinds {
+        if !submaps.contains(&kb.submap) {
+            submaps.push(kb.submap.clone());
+        }
+    }
+
+    let mut entries = Vec::new();
+    for submap in &submaps {
+        for (category, _) in CATEGORIES {
+            for kb in keybinds.iter().filter(|kb| {
+                &kb.submap == submap && dispatcher_category(&kb.dispatcher) == category
+            }) {
+                entries.push(CheatsheetEntry {
+                    combo: readable_combo(kb),
+                    description: action_description(kb),
+                    category: category.to_string(),
+                    submap: kb.submap.clone(),
+                    dispatcher: kb.dispatcher.clone(),
+                    args: kb.args.clone(),
+                });
+            }
+        }
+    }
+    Ok(entries)
+}
+
+/// Returns the heading for a category ID.
+fn category_label(category: &str) -> &'static str {
+    CATEGORIES
+        .iter()
+        .find(|(id, _)| *id == category)
+        .map(|(_, label)| *label)
+        .unwrap_or("Other")
+}
+
+/// Returns the heading for a submap section.
+fn submap_label(submap: &str) -> String {
+    if submap.is_empty() {
+        "Global".to_string()
+    } else {
+        format!("Submap: {}", submap)
+    }
+}
+
+/// Groups consecutive entries by submap and category, in the order `build_entries` produced.
+fn sections(entries: &[CheatsheetEntry]) -> Vec<(&str, &str, Vec<&CheatsheetEntry>)> {
+    let mut sections: Vec<(&str, &str, Vec<&CheatsheetEntry>)> = Vec::new();
+    for entry in entries {
+        match sections.last_mut() {
+            Some((submap, category, items))
+                if *submap == entry.submap && *category == entry.category =>
+            {
+                items.push(entry)
+            }
+            _ => sections.push((&entry.submap, &entry.category, vec![entry])),
+        }
+    }
+    sections
+}
+
+/// Renders the cheatsheet as Markdown tables.
+fn render_markdown(entries: &[CheatsheetEntry]) -> String {
+    let escape = |text: &str| text.replace('|', "\\|");
+    let mut out = String::from("# Keybinds\n");
+    let mut current_submap: Option<&str> = None;
+
+    for (submap, category, items) in sections(entries) {
+        if current_submap != Some(submap) {
+            out.push_str(&format!("\n## {}\n", submap_label(submap)));
+            current_submap = Some(submap);
+        }
+        out.push_str(&format!("\n### {}\n\n", category_label(category)));
+        out.push_str("| Shortcut | Action |\n|---|---|\n");
+        for entry in items {
+            out.push_str(&format!(
+                "| `{}` | {} |\n",
+                escape(&entry.combo),
+                escape(&entry.description)
+            ));
+        }
+    }
+    out
+}
+
+/// Escapes text for inclusion in HTML.
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Renders the cheatsheet as a standalone, printable HTML page.
+fn render_html(entries: &[CheatsheetEntry]) -> String {
+    let mut body = String::new();
+    let mut current_submap: Option<&str> = None;
+
+    for (submap, category, items) in sections(entries) {
+        if current_submap != Some(submap) {
+            body.push_str(&format!(
+                "<h2>{}</h2>\n",
+                escape_html(&submap_label(submap))
+            ));
+            current_submap = Some(submap);
+        }
+        body.push_str(&format!("<h3>{}</h3>\n<table>\n", category_label(category)));
+        for entry in items {
+            let keys: Vec<String> = entry
+                .combo
+                .split(" + ")
+                .map(|k| format!("<kbd>{}</kbd>", escape_html(k)))
+                .collect();
+            body.push_str(&format!(
+                "<tr><td>{}</td><td>{}</td></tr>\n",
+                keys.join(" + "),
+                escape_html(&entry.description)
+            ));
+        }
+        body.push_str("</table>\n");
+    }
+
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>Keybinds</title>
+<style>
+body {{ font-family: sans-serif; margin: 2rem; color: #222; }}
+h2 {{ border-bottom: 2px solid #ccc; padding-bottom: .25rem; }}
+table {{ border-collapse: collapse; width: 100%; margin-bottom: 1rem; }}
+td {{ padding: .3rem .6rem; border-bottom: 1px solid #eee; }}
+td:first-child {{ white-space: nowrap; width: 30%; }}
+kbd {{ background: #f4f4f4; border: 1px solid #ccc; border-radius: 4px; padding: .1rem .4rem; font-size: .9em; }}
+@media print {{ body {{ margin: 0; }} h2 {{ break-before: auto; }} }}
+</style>
+</head>
+<body>
+<h1>Keybinds</h1>
+{}</body>
+</html>
+"#,
+        body
+    )
+}
+
+/// Exports the keybind cheatsheet as `markdown`, `html` or `json`.
+/// Writes it to `path` when given and returns the rendered content.
+#[tauri::command]
+pub fn export_keybind_cheatsheet(format: String, path: Option<String>) -> Result<String, String> {
+    let entries = build_entries()?;
+
+    let content = match format.as_str() {
+        "markdown" | "md" => render_markdown(&entries),
+        "html" => render_html(&entries),
+        "json" => serde_json::to_string_pretty(&entries).map_err(|e| e.to_string())?,
+        other => return Err(format!("Unsupported cheatsheet format '{}'", other)),
+    };
+
+    if let Some(path) = path {
+        fs::write(&path, &content).map_err(|e| format!("Failed to write {}: {}", path, e))?;
+    }
+
+    Ok(content)
+}
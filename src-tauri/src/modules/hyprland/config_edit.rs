@@ -0,0 +1,105 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+
+/// Hashes text with 64-bit FNV-1a, which is stable across builds and platforms.
+fn fnv1a(text: &str) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in text.bytes() {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+/// Builds an identifier from an entry's identifying content.
+/// Entries with identical content are told apart by an occurrence suffix, tracked in `seen`.
+pub fn stable_id(prefix: &str, content: &str, seen: &mut HashMap<String, usize>) -> String {
+    let base = format!("{}-{:016x}", prefix, fnv1a(content));
+    let count = seen.entry(base.clone()).or_insert(0);
+    *count += 1;
+
+    if *count == 1 {
+        base
+    } else {
+        format!("{}-{}", base, count)
+    }
+}
+
+/// Reads a config file into lines. A missing file yields no lines;
+/// any other read error is returned so callers never write over a file they could not read.
+pub fn read_lines(path: &Path) -> Result<Vec<String>, String> {
+    match fs::read_to_string(path) {
+        Ok(content) => Ok(content.lines().map(String::from).collect()),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(format!("Failed to read {}: {}", path.display(), e)),
+    }
+}
+
+/// Writes lines back to a config file atomically via a temporary file.
+/// Symlinked configs are resolved first so the link is kept and its target is updated.
+pub fn write_lines(path: &Path, lines: &[String]) -> Result<(), String> {
+    let path = &fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+
+    let mut content = lines.join("\n");
+    content.push('\n');
+
+    let temp_path = path.with_extension("tmp");
+    fs::write(&temp_path, content).map_err(|e| format!("Failed to write temp config: {}", e))?;
+    fs::rename(&temp_path, path).map_err(|e| format!("Failed to finalize config: {}", e))
+}
+
+/// Returns the leading whitespace of a line so edits keep the original indentation.
+pub fn indentation(line: &str) -> &str {
+    &line[..line.len() - line.trim_start().len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+    use std::os::unix::fs::symlink;
+    use std::path::PathBuf;
+
+    /// Creates an empty scratch directory unique to the test.
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("config-edit-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn reads_missing_files_as_empty() {
+        let dir = scratch_dir("missing");
+        assert_eq!(read_lines(&dir.join("absent.conf")), Ok(Vec::new()));
+
+        // Anything other than a missing file is an error, not an empty config
+        assert!(read_lines(&dir).is_err());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn writes_through_symlinks() {
+        let dir = scratch_dir("symlink");
+        let target = dir.join("dotfiles.conf");
+        let link = dir.join("keybinds.conf");
+        fs::write(&target, "bind = SUPER, Q, killactive\n").unwrap();
+        symlink(&target, &link).unwrap();
+
+        let mut lines = read_lines(&link).unwrap();
+        lines.push("bind = SUPER, E, exec, nautilus".to_string());
+        write_lines(&link, &lines).unwrap();
+
+        assert!(fs::symlink_metadata(&link)
+            .unwrap()
+            .file_type()
+            .is_symlink());
+        assert_eq!(read_lines(&target).unwrap(), lines);
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
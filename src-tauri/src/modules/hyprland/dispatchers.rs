@@ -0,0 +1,686 @@
+use serde::Serialize;
+
+/// The kind of value a dispatcher argument accepts.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ArgKind {
+    /// A shell command line.
+    Command,
+    /// A workspace selector: ID, relative (`+1`, `r-1`, `m+1`, `e+1`), `name:`, `special`, `previous`, `empty`.
+    Workspace,
+    /// One of `l`, `r`, `u`, `d`.
+    Direction,
+    /// A pixel or percentage delta pair such as `10 -10`, `20%` or `exact 800 600`.
+    PixelDelta,
+    /// A window selector such as `class:^kitty$`, `address:0x...` or `pid:1234`.
+    Window,
+    /// A monitor selector: name, ID, direction or relative offset.
+    Monitor,
+    /// A whole number.
+    Integer,
+    /// A decimal number, optionally prefixed with `exact`.
+    Float,
+    /// One of `on`, `off`, `toggle` (or `lock`/`unlock` for group locks).
+    Toggle,
+    /// Free-form text that is not validated.
+    Text,
+}
+
+impl ArgKind {
+    /// Human-readable name used in validation messages.
+    fn label(self) -> &'static str {
+        match self {
+            Self::Command => "command",
+            Self::Workspace => "workspace",
+            Self::Direction => "direction",
+            Self::PixelDelta => "pixel delta",
+            Self::Window => "window",
+            Self::Monitor => "monitor",
+            Self::Integer => "integer",
+            Self::Float => "number",
+            Self::Toggle => "on/off/toggle",
+            Self::Text => "text",
+        }
+    }
+}
+
+/// A single argument of a dispatcher.
+#[derive(Serialize, Debug)]
+pub struct DispatcherArg {
+    pub name: &'static str,
+    pub kind: ArgKind,
+    pub optional: bool,
+}
+
+/// A Hyprland dispatcher and the arguments it expects.
+#[derive(Serialize, Debug)]
+pub struct DispatcherInfo {
+    pub name: &'static str,
+    pub category: &'static str,
+    pub description: &'static str,
+    /// Comma-separated arguments, in order.
+    pub args: &'static [DispatcherArg],
+}
+
+const fn arg(name: &'static str, kind: ArgKind) -> DispatcherArg {
+    DispatcherArg {
+        name,
+        kind,
+        optional: false,
+    }
+}
+
+const fn opt(name: &'static str, kind: ArgKind) -> DispatcherArg {
+    DispatcherArg {
+        name,
+        kind,
+        optional: true,
+    }
+}
+
+const fn dispatcher(
+    name: &'static str,
+    category: &'static str,
+    description: &'static str,
+    args: &'static [DispatcherArg],
+) -> DispatcherInfo {
+    DispatcherInfo {
+        name,
+        category,
+        description,
+        args,
+    }
+}
+
+/// Built-in catalog of Hyprland dispatchers.
+static DISPATCHERS: &[DispatcherInfo] = &[
+    // Applications
+    dispatcher(
+        "exec",
+        "apps",
+        "Execute a shell command",
+        &[arg("command", ArgKind::Command)],
+    ),
+    dispatcher(
+        "execr",
+        "apps",
+        "Execute a raw shell command",
+        &[arg("command", ArgKind::Command)],
+    ),
+    dispatcher(
+        "pass",
+        "apps",
+        "Pass the key to a window",
+        &[arg("window", ArgKind::Window)],
+    ),
+    dispatcher(
+        "sendshortcut",
+        "apps",
+        "Send a key combination to a window",
+        &[
+            arg("modifiers", ArgKind::Text),
+            arg("key", ArgKind::Text),
+            opt("window", ArgKind::Window),
+        ],
+    ),
+    dispatcher(
+        "global",
+        "apps",
+        "Trigger a global shortcut",
+        &[arg("name", ArgKind::Text)],
+    ),
+    // Windows
+    dispatcher("killactive", "windows", "Close the active window", &[]),
+    dispatcher(
+        "forcekillactive",
+        "windows",
+        "Kill the active window's process",
+        &[],
+    ),
+    dispatcher(
+        "closewindow",
+        "windows",
+        "Close a window",
+        &[arg("window", ArgKind::Window)],
+    ),
+    dispatcher(
+        "killwindow",
+        "windows",
+        "Kill a window's process",
+        &[arg("window", ArgKind::Window)],
+    ),
+    dispatcher(
+        "signal",
+        "windows",
+        "Send a signal to the active window",
+        &[arg("signal", ArgKind::Integer)],
+    ),
+    dispatcher(
+        "togglefloating",
+        "windows",
+        "Toggle floating mode",
+        &[opt("window", ArgKind::Window)],
+    ),
+    dispatcher(
+        "setfloating",
+        "windows",
+        "Make a window floating",
+        &[opt("window", ArgKind::Window)],
+    ),
+    dispatcher(
+        "settiled",
+        "windows",
+        "Make a window tiled",
+        &[opt("window", ArgKind::Window)],
+    ),
+    dispatcher(
+        "fullscreen",
+        "windows",
+        "Toggle fullscreen (0 full, 1 maximize)",
+        &[opt("mode", ArgKind::Integer)],
+    ),
+    dispatcher(
+        "fullscreenstate",
+        "windows",
+        "Set internal and client fullscreen state",
+        &[arg("state", ArgKind::Text)],
+    ),
+    dispatcher(
+        "pseudo",
+        "windows",
+        "Toggle pseudotiling",
+        &[opt("window", ArgKind::Window)],
+    ),
+    dispatcher(
+        "pin",
+        "windows",
+        "Pin a floating window to all workspaces",
+        &[opt("window", ArgKind::Window)],
+    ),
+    dispatcher(
+        "centerwindow",
+        "windows",
+        "Center the active floating window",
+        &[opt("respect_reserved", ArgKind::Integer)],
+    ),
+    dispatcher(
+        "resizeactive",
+        "windows",
+        "Resize the active window",
+        &[arg("delta", ArgKind::PixelDelta)],
+    ),
+    dispatcher(
+        "moveactive",
+        "windows",
+        "Move the active window",
+        &[arg("delta", ArgKind::PixelDelta)],
+    ),
+    dispatcher(
+        "resizewindowpixel",
+        "windows",
+        "Resize a window",
+        &[
+            arg("delta", ArgKind::PixelDelta),
+            arg("window", ArgKind::Window),
+        ],
+    ),
+    dispatcher(
+        "movewindowpixel",
+        "windows",
+        "Move a window",
+        &[
+            arg("delta", ArgKind::PixelDelta),
+            arg("window", ArgKind::Window),
+        ],
+    ),
+    dispatcher(
+        "movewindow",
+        "windows",
+        "Move the active window in a direction or to a monitor; drags it in mouse binds",
+        &[opt("target", ArgKind::Text)],
+    ),
+    dispatcher(
+        "resizewindow",
+        "windows",
+        "Resize the active window by dragging (mouse binds only)",
+        &[opt("mode", ArgKind::Integer)],
+    ),
+    dispatcher(
+        "togglesplit",
+        "windows",
+        "Toggle the split direction (dwindle)",
+        &[],
+    ),
+    dispatcher(
+        "swapsplit",
+        "windows",
+        "Swap the two halves of the split (dwindle)",
+        &[],
+    ),
+    dispatcher(
+        "swapwindow",
+        "windows",
+        "Swap the active window with a neighbour",
+        &[arg("direction", ArgKind::Direction)],
+    ),
+    dispatcher(
+        "splitratio",
+        "windows",
+        "Change the split ratio",
+        &[arg("ratio", ArgKind::Float)],
+    ),
+    dispatcher(
+        "toggleswallow",
+        "windows",
+        "Toggle swallowing of the active window",
+        &[],
+    ),
+    dispatcher(
+        "bringactivetotop",
+        "windows",
+        "Bring the active window to the top",
+        &[],
+    ),
+    dispatcher(
+        "alterzorder",
+        "windows",
+        "Change a window's stacking order",
+        &[arg("order", ArgKind::Text)],
+    ),
+    dispatcher(
+        "setprop",
+        "windows",
+        "Set a window property",
+        &[arg("property", ArgKind::Text)],
+    ),
+    // Focus
+    dispatcher(
+        "movefocus",
+        "focus",
+        "Move focus in a direction",
+        &[arg("direction", ArgKind::Direction)],
+    ),
+    dispatcher(
+        "focuswindow",
+        "focus",
+        "Focus a window",
+        &[arg("window", ArgKind::Window)],
+    ),
+    dispatcher(
+        "cyclenext",
+        "focus",
+        "Focus the next window",
+        &[opt("filter", ArgKind::Text)],
+    ),
+    dispatcher(
+        "swapnext",
+        "focus",
+        "Swap with the next window",
+        &[opt("filter", ArgKind::Text)],
+    ),
+    dispatcher(
+        "focusurgentorlast",
+        "focus",
+        "Focus the urgent or last window",
+        &[],
+    ),
+    dispatcher(
+        "focuscurrentorlast",
+        "focus",
+        "Switch between the current and last window",
+        &[],
+    ),
+    // Workspaces
+    dispatcher(
+        "workspace",
+        "workspaces",
+        "Switch to a workspace",
+        &[arg("workspace", ArgKind::Workspace)],
+    ),
+    dispatcher(
+        "movetoworkspace",
+        "workspaces",
+        "Move a window to a workspace and follow it",
+        &[
+            arg("workspace", ArgKind::Workspace),
+            opt("window", ArgKind::Window),
+        ],
+    ),
+    dispatcher(
+        "movetoworkspacesilent",
+        "workspaces",
+        "Move a window to a workspace",
+        &[
+            arg("workspace", ArgKind::Workspace),
+            opt("window", ArgKind::Window),
+        ],
+    ),
+    dispatcher(
+        "togglespecialworkspace",
+        "workspaces",
+        "Toggle a special workspace",
+        &[opt("name", ArgKind::Text)],
+    ),
+    dispatcher(
+        "renameworkspace",
+        "workspaces",
+        "Rename a workspace (`id name`)",
+        &[arg("workspace", ArgKind::Text)],
+    ),
+    dispatcher(
+        "focusworkspaceoncurrentmonitor",
+        "workspaces",
+        "Show a workspace on the current monitor",
+        &[arg("workspace", ArgKind::Workspace)],
+    ),
+    // Monitors
+    dispatcher(
+        "focusmonitor",
+        "monitors",
+        "Focus a monitor",
+        &[arg("monitor", ArgKind::Monitor)],
+    ),
+    dispatcher(
+        "movecurrentworkspacetomonitor",
+        "monitors",
+        "Move the current workspace to a monitor",
+        &[arg("monitor", ArgKind::Monitor)],
+    ),
+    dispatcher(
+        "moveworkspacetomonitor",
+        "monitors",
+        "Move a workspace to a monitor (`workspace monitor`)",
+        &[arg("target", ArgKind::Text)],
+    ),
+    dispatcher(
+        "swapactiveworkspaces",
+        "monitors",
+        "Swap the active workspaces of two monitors (`monitor monitor`)",
+        &[arg("monitors", ArgKind::Text)],
+    ),
+    dispatcher(
+        "dpms",
+        "monitors",
+        "Turn monitors on or off",
+        &[arg("state", ArgKind::Toggle)],
+    ),
+    // Groups
+    dispatcher(
+        "togglegroup",
+        "groups",
+        "Toggle the active window's group",
+        &[],
+    ),
+    dispatcher(
+        "changegroupactive",
+        "groups",
+        "Switch the active window in a group (`b`, `f` or index)",
+        &[arg("target", ArgKind::Text)],
+    ),
+    dispatcher(
+        "lockgroups",
+        "groups",
+        "Lock or unlock all groups",
+        &[arg("state", ArgKind::Toggle)],
+    ),
+    dispatcher(
+        "lockactivegroup",
+        "groups",
+        "Lock or unlock the active group",
+        &[arg("state", ArgKind::Toggle)],
+    ),
+    dispatcher(
+        "moveintogroup",
+        "groups",
+        "Move the active window into a neighbouring group",
+        &[arg("direction", ArgKind::Direction)],
+    ),
+    dispatcher(
+        "moveoutofgroup",
+        "groups",
+        "Move a window out of its group",
+        &[opt("window", ArgKind::Window)],
+    ),
+    dispatcher(
+        "movewindoworgroup",
+        "groups",
+        "Move a window or group in a direction",
+        &[arg("direction", ArgKind::Direction)],
+    ),
+    dispatcher(
+        "movegroupwindow",
+        "groups",
+        "Swap the active window within its group (`b` or `f`)",
+        &[arg("target", ArgKind::Text)],
+    ),
+    dispatcher(
+        "denywindowfromgroup",
+        "groups",
+        "Prevent windows from joining the group",
+        &[arg("state", ArgKind::Toggle)],
+    ),
+    dispatcher(
+        "setignoregrouplock",
+        "groups",
+        "Ignore group locks",
+        &[arg("state", ArgKind::Toggle)],
+    ),
+    // Session
+    dispatcher("exit", "session", "Exit Hyprland", &[]),
+    dispatcher(
+        "forcerendererreload",
+        "session",
+        "Force a renderer reload",
+        &[],
+    ),
+    dispatcher(
+        "submap",
+        "session",
+        "Enter a submap (`reset` to leave)",
+        &[arg("name", ArgKind::Text)],
+    ),
+    dispatcher(
+        "movecursortocorner",
+        "session",
+        "Move the cursor to a corner of the active window (0-3)",
+        &[arg("corner", ArgKind::Integer)],
+    ),
+    dispatcher(
+        "movecursor",
+        "session",
+        "Move the cursor to a position (`x y`)",
+        &[arg("position", ArgKind::Text)],
+    ),
+    dispatcher(
+        "layoutmsg",
+        "session",
+        "Send a message to the current layout",
+        &[arg("message", ArgKind::Text)],
+    ),
+    dispatcher(
+        "event",
+        "session",
+        "Emit a custom IPC event",
+        &[arg("data", ArgKind::Text)],
+    ),
+];
+
+/// Looks up a dispatcher by name.
+pub fn find_dispatcher(name: &str) -> Option<&'static DispatcherInfo> {
+    DISPATCHERS.iter().find(|d| d.name == name)
+}
+
+/// Returns true if the value is a number with an optional sign.
+fn is_signed_number(value: &str) -> bool {
+    let digits = value.strip_prefix(['+', '-']).unwrap_or(value);
+    !digits.is_empty() && digits.parse::<f64>().is_ok()
+}
+
+/// Validates a workspace selector.
+fn is_workspace(value: &str) -> bool {
+    const KEYWORDS: [&str; 3] = ["previous", "previous_per_monitor", "special"];
+
+    if KEYWORDS.contains(&value) || value.starts_with("name:") || value.starts_with("special:") {
+        return true;
+    }
+
+    // `empty` optionally followed by the `n` (next) and `m` (on monitor) flags
+    if let Some(flags) = value.strip_prefix("empty") {
+        return matches!(flags, "" | "n" | "m" | "nm" | "mn");
+    }
+
+    // Absolute IDs, relative offsets (`+1`, `r-1`, `m+1`, `e+1`) and
+    // relative positions by index (`r~1`, `m~1`, `e~1`)
+    if let Some(relative) = value.strip_prefix(['r', 'm', 'e']) {
+        return relative
+            .strip_prefix(['+', '-', '~'])
+            .is_some_and(|n| n.parse::<u32>().is_ok());
+    }
+    if let Some(offset) = value.strip_prefix(['+', '-']) {
+        return offset.parse::<u32>().is_ok();
+    }
+    value.parse::<u32>().is_ok()
+}
+
+/// Validates a `resizeactive`-style delta: `X Y`, optionally prefixed with `exact`.
+fn is_pixel_delta(value: &str) -> bool {
+    let value = value.strip_prefix("exact").unwrap_or(value).trim();
+    let parts: Vec<&str> = value.split_whitespace().collect();
+    !parts.is_empty()
+        && parts.len() <= 2
+        && parts
+            .iter()
+            .all(|p| is_signed_number(p.strip_suffix('%').unwrap_or(p)))
+}
+
+/// Checks a single argument value against its kind.
+fn validate_arg(kind: ArgKind, value: &str) -> Result<(), String> {
+    let valid = match kind {
+        ArgKind::Command | ArgKind::Window | ArgKind::Monitor | ArgKind::Text => !value.is_empty(),
+        ArgKind::Workspace => is_workspace(value),
+        ArgKind::Direction => matches!(value, "l" | "r" | "u" | "d" | "t" | "b"),
+        ArgKind::PixelDelta => is_pixel_delta(value),
+        ArgKind::Integer => value.parse::<i64>().is_ok(),
+        ArgKind::Float => is_signed_number(value.strip_prefix("exact").unwrap_or(value).trim()),
+        // dpms also accepts a monitor name after the state
+        ArgKind::Toggle => matches!(
+            value.split_whitespace().next(),
+            Some("on" | "off" | "toggle" | "lock" | "unlock")
+        ),
+    };
+
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("'{}' is not a valid {} value", value, kind.label()))
+    }
+}
+
+/// Returns true if the dispatcher is in the catalog or namespaced by a plugin
+/// (e.g. `hyprexpo:expo`), which cannot be checked.
+pub fn is_known_dispatcher(name: &str) -> bool {
+    let name = name.trim();
+    name.contains(':') || find_dispatcher(name).is_some()
+}
+
+/// Validates a dispatcher name and its argument string.
+/// Dispatchers missing from the catalog are accepted with their arguments unchecked;
+/// callers use `is_known_dispatcher` to warn about them.
+pub fn validate_dispatcher(name: &str, args: &str) -> Result<(), String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Dispatcher is required".to_string());
+    }
+
+    let Some(info) = find_dispatcher(name) else {
+        return Ok(());
+    };
+    let args = args.trim();
+
+    if info.args.is_empty() {
+        return if args.is_empty() {
+            Ok(())
+        } else {
+            Err(format!("'{}' takes no arguments", name))
+        };
+    }
+
+    // Only the last argument may contain commas (e.g. a command line)
+    let values: Vec<&str> = if args.is_empty() {
+        Vec::new()
+    } else {
+        args.splitn(info.args.len(), ',').map(str::trim).collect()
+    };
+
+    for (index, spec) in info.args.iter().enumerate() {
+        match values.get(index) {
+            Some(value) if !value.is_empty() => validate_arg(spec.kind, value)
+                .map_err(|e| format!("Argument '{}': {}", spec.name, e))?,
+            _ if spec.optional => {}
+            _ => return Err(format!("Missing argument '{}' for '{}'", spec.name, name)),
+        }
+    }
+
+    Ok(())
+}
+
+/// Lists all known dispatchers so the UI can offer pickers.
+#[tauri::command]
+pub fn get_dispatchers() -> &'static [DispatcherInfo] {
+    DISPATCHERS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_workspace_forms() {
+        for value in [
+            "1",
+            "10",
+            "+1",
+            "-2",
+            "r+1",
+            "r-1",
+            "m+1",
+            "e-3",
+            "r~1",
+            "m~2",
+            "e~1",
+            "empty",
+            "emptyn",
+            "emptym",
+            "emptynm",
+            "emptymn",
+            "name:web",
+            "special",
+            "special:scratch",
+            "previous",
+            "previous_per_monitor",
+        ] {
+            assert!(is_workspace(value), "{} should be accepted", value);
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_workspaces() {
+        for value in [
+            "",
+            "emptyfoo",
+            "emptynn",
+            "empty n",
+            "~1",
+            "r~",
+            "r~-1",
+            "x+1",
+            "m",
+            "+",
+            "1.5",
+            "-x",
+            "previousfoo",
+        ] {
+            assert!(!is_workspace(value), "{} should be rejected", value);
+        }
+    }
+}
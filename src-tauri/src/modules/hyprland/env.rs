@@ -1,7 +1,9 @@
+use crate::modules::hyprland::config_edit::{indentation, read_lines, stable_id, write_lines};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::env;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 /// Represents an environment variable entry.
 #[derive(Debug, Serialize, Deserialize)]
@@ -11,35 +13,130 @@ pub struct EnvVar {
     pub value: String,
 }
 
+impl EnvVar {
+    /// Serializes the variable as an `env = KEY,VALUE` line.
+    fn to_line(&self) -> String {
+        format!("env = {},{}", self.key.trim(), self.value.trim())
+    }
+}
+
+/// A problem found while validating environment variables.
+#[derive(Debug, Serialize)]
+pub struct EnvIssue {
+    pub id: String,
+    pub key: String,
+    pub severity: String, // "error" blocks saving, "warning" does not
+    pub message: String,
+}
+
+/// A curated group of variables for a common Hyprland setup.
+#[derive(Debug, Serialize)]
+pub struct EnvPreset {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub description: &'static str,
+    pub vars: &'static [(&'static str, &'static str)],
+}
+
+/// A preset together with how much of it is present in `env.conf`.
+#[derive(Debug, Serialize)]
+pub struct EnvPresetStatus {
+    #[serde(flatten)]
+    pub preset: &'static EnvPreset,
+    pub applied: bool, // every variable is set to the preset value
+    pub partial: bool, // some variables of the preset are set
+}
+
+/// Built-in presets for common Hyprland setups.
+static ENV_PRESETS: &[EnvPreset] = &[
+    EnvPreset {
+        id: "nvidia",
+        name: "NVIDIA",
+        description: "Proprietary NVIDIA driver with hardware video acceleration",
+        vars: &[
+            ("LIBVA_DRIVER_NAME", "nvidia"),
+            ("__GLX_VENDOR_LIBRARY_NAME", "nvidia"),
+            ("GBM_BACKEND", "nvidia-drm"),
+            ("NVD_BACKEND", "direct"),
+        ],
+    },
+    EnvPreset {
+        id: "toolkits-wayland",
+        name: "Qt/GTK Wayland",
+        description: "Run Qt and GTK applications natively on Wayland with X11 fallback",
+        vars: &[
+            ("QT_QPA_PLATFORM", "wayland;xcb"),
+            ("QT_WAYLAND_DISABLE_WINDOWDECORATION", "1"),
+            ("GDK_BACKEND", "wayland,x11,*"),
+            ("SDL_VIDEODRIVER", "wayland"),
+            ("CLUTTER_BACKEND", "wayland"),
+        ],
+    },
+    EnvPreset {
+        id: "cursor",
+        name: "Cursor",
+        description: "Cursor theme and size for XCursor and Hyprcursor",
+        vars: &[
+            ("XCURSOR_THEME", "Adwaita"),
+            ("XCURSOR_SIZE", "24"),
+            ("HYPRCURSOR_THEME", "Adwaita"),
+            ("HYPRCURSOR_SIZE", "24"),
+        ],
+    },
+    EnvPreset {
+        id: "scaling",
+        name: "Toolkit scaling",
+        description: "Let Qt follow monitor scaling and keep GTK at integer scale",
+        vars: &[("QT_AUTO_SCREEN_SCALE_FACTOR", "1"), ("GDK_SCALE", "1")],
+    },
+    EnvPreset {
+        id: "electron",
+        name: "Electron Ozone",
+        description: "Run Electron and Chromium apps natively on Wayland",
+        vars: &[
+            ("ELECTRON_OZONE_PLATFORM_HINT", "auto"),
+            ("OZONE_PLATFORM", "wayland"),
+        ],
+    },
+];
+
 /// Retrieves the user's home directory.
 fn get_home_dir() -> String {
     env::var("HOME").unwrap_or_else(|_| "/home/i4104".to_string())
 }
 
-/// Parses environment variables from the Hyprland configuration file.
-#[tauri::command]
-pub fn get_env_vars() -> Vec<EnvVar> {
-    let home = get_home_dir();
-    let config_path = Path::new(&home).join(".config/hypr/configs/env.conf");
+/// Returns the path of the Hyprland env config file.
+fn get_env_path() -> PathBuf {
+    Path::new(&get_home_dir()).join(".config/hypr/configs/env.conf")
+}
+
+/// Parses `env = KEY,VALUE` lines, returning each variable with its line index.
+/// IDs are derived from the key so they survive edits elsewhere in the file.
+fn parse_env_vars(lines: &[String]) -> Vec<(usize, EnvVar)> {
     let mut vars = Vec::new();
+    let mut seen = HashMap::new();
+
+    for (index, line) in lines.iter().enumerate() {
+        let trim = line.trim();
+        if trim.is_empty() || trim.starts_with('#') {
+            continue;
+        }
 
-    if let Ok(content) = fs::read_to_string(config_path) {
-        for (index, line) in content.lines().enumerate() {
-            let trim = line.trim();
-            if trim.is_empty() || trim.starts_with('#') {
+        // Expected format: env = KEY,VALUE
+        if let Some((keyword, rest)) = trim.split_once('=') {
+            if keyword.trim() != "env" {
                 continue;
             }
-
-            // Expected format: env = KEY,VALUE
-            if trim.starts_with("env =") {
-                let parts: Vec<&str> = trim.trim_start_matches("env =").splitn(2, ',').collect();
-                if parts.len() == 2 {
-                    vars.push(EnvVar {
-                        id: format!("env-{}", index),
-                        key: parts[0].trim().to_string(),
-                        value: parts[1].trim().to_string(),
-                    });
-                }
+            if let Some((key, value)) = rest.split_once(',') {
+                let key = key.trim().to_string();
+                vars.push((
+                    index,
+                    EnvVar {
+                        id: stable_id("env", &key, &mut seen),
+                        key,
+                        value: value.trim().to_string(),
+                    },
+                ));
             }
         }
     }
@@ -47,11 +144,156 @@ pub fn get_env_vars() -> Vec<EnvVar> {
     vars
 }
 
+/// Finds the line index of the variable with the given ID.
+fn find_env_var(lines: &[String], id: &str) -> Result<usize, String> {
+    parse_env_vars(lines)
+        .into_iter()
+        .find(|(_, var)| var.id == id)
+        .map(|(index, _)| index)
+        .ok_or_else(|| {
+            format!(
+                "Environment variable '{}' no longer exists; reload and retry",
+                id
+            )
+        })
+}
+
+/// Re-reads the variable written at `index` so the caller receives its current ID.
+fn env_var_at(lines: &[String], index: usize) -> Result<EnvVar, String> {
+    parse_env_vars(lines)
+        .into_iter()
+        .find(|(i, _)| *i == index)
+        .map(|(_, var)| var)
+        .ok_or_else(|| "Failed to re-read the edited variable".to_string())
+}
+
+/// Returns true if the name is a legal environment variable name.
+fn is_valid_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Checks names, duplicates and quoting of a set of variables.
+fn find_env_issues(vars: &[EnvVar]) -> Vec<EnvIssue> {
+    let mut issues = Vec::new();
+    let mut seen: HashMap<&str, usize> = HashMap::new();
+
+    for var in vars {
+        let key = var.key.trim();
+        let value = var.value.trim();
+        let mut issue = |severity: &str, message: String| {
+            issues.push(EnvIssue {
+                id: var.id.clone(),
+                key: key.to_string(),
+                severity: severity.to_string(),
+                message,
+            })
+        };
+
+        if key.is_empty() {
+            continue;
+        }
+        if !is_valid_name(key) {
+            issue(
+                "error",
+                format!(
+                    "'{}' is not a valid variable name (use letters, digits and _)",
+                    key
+                ),
+            );
+        }
+
+        let count = seen.entry(key).or_insert(0);
+        *count += 1;
+        if *count == 2 {
+            issue("error", format!("'{}' is defined more than once", key));
+        }
+
+        // Hyprland keeps quotes literally, unlike a shell
+        if value.contains('"') || value.contains('\'') {
+            issue(
+                "error",
+                "Quotes are passed through literally; remove them".to_string(),
+            );
+        }
+        if value.contains('\n') || value.contains('#') {
+            issue(
+                "error",
+                "Values cannot contain newlines or '#' (starts a comment)".to_string(),
+            );
+        }
+        if value.contains(',') {
+            issue(
+                "warning",
+                "Everything after the first comma is part of the value".to_string(),
+            );
+        }
+        if value.is_empty() {
+            issue("warning", format!("'{}' is set to an empty value", key));
+        }
+    }
+
+    issues
+}
+
+/// Validates variables without saving them.
+#[tauri::command]
+pub fn validate_env_vars(vars: Vec<EnvVar>) -> Vec<EnvIssue> {
+    find_env_issues(&vars)
+}
+
+/// Returns an error listing blocking issues, if any.
+fn ensure_valid(vars: &[EnvVar]) -> Result<(), String> {
+    let errors: Vec<String> = find_env_issues(vars)
+        .into_iter()
+        .filter(|i| i.severity == "error")
+        .map(|i| format!("{}: {}", i.key, i.message))
+        .collect();
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors.join("; "))
+    }
+}
+
+/// Validates the variables written at `indices` of an edited env file.
+/// Problems in other entries predate the edit; `validate_env_vars` reports them,
+/// and they do not block changes to unrelated variables.
+fn ensure_valid_entries(lines: &[String], indices: &[usize]) -> Result<(), String> {
+    let (written, others): (Vec<_>, Vec<_>) = parse_env_vars(lines)
+        .into_iter()
+        .partition(|(index, _)| indices.contains(index));
+    let written: Vec<EnvVar> = written.into_iter().map(|(_, v)| v).collect();
+    ensure_valid(&written)?;
+
+    match written
+        .iter()
+        .find(|var| others.iter().any(|(_, other)| other.key == var.key))
+    {
+        Some(var) => Err(format!(
+            "{}: '{}' is defined more than once",
+            var.key, var.key
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Parses environment variables from the Hyprland configuration file.
+#[tauri::command]
+pub fn get_env_vars() -> Result<Vec<EnvVar>, String> {
+    Ok(parse_env_vars(&read_lines(&get_env_path())?)
+        .into_iter()
+        .map(|(_, var)| var)
+        .collect())
+}
+
 /// Saves environment variables to the Hyprland configuration file.
 #[tauri::command]
 pub fn save_env_vars(vars: Vec<EnvVar>) -> Result<(), String> {
-    let home = get_home_dir();
-    let config_path = Path::new(&home).join(".config/hypr/configs/env.conf");
+    ensure_valid(&vars)?;
+    let config_path = get_env_path();
 
     if let Some(parent) = config_path.parent() {
         let _ = fs::create_dir_all(parent);
@@ -63,9 +305,207 @@ pub fn save_env_vars(vars: Vec<EnvVar>) -> Result<(), String> {
 
     for var in vars {
         if !var.key.trim().is_empty() {
-            content.push_str(&format!("env = {},{}\n", var.key.trim(), var.value.trim()));
+            content.push_str(&var.to_line());
+            content.push('\n');
         }
     }
 
     fs::write(config_path, content).map_err(|e| e.to_string())
 }
+
+/// Appends a single environment variable to the env config file.
+#[tauri::command]
+pub fn add_env_var(var: EnvVar) -> Result<EnvVar, String> {
+    if var.key.trim().is_empty() {
+        return Err("Variable name is required".to_string());
+    }
+
+    let config_path = get_env_path();
+    let mut lines = read_lines(&config_path)?;
+    lines.push(var.to_line());
+    ensure_valid_entries(&lines, &[lines.len() - 1])?;
+    write_lines(&config_path, &lines)?;
+    env_var_at(&lines, lines.len() - 1)
+}
+
+/// Replaces a single environment variable in place, leaving the rest of the file untouched.
+#[tauri::command]
+pub fn update_env_var(id: String, var: EnvVar) -> Result<EnvVar, String> {
+    if var.key.trim().is_empty() {
+        return Err("Variable name is required".to_string());
+    }
+
+    let config_path = get_env_path();
+    let mut lines = read_lines(&config_path)?;
+    let index = find_env_var(&lines, &id)?;
+
+    lines[index] = format!("{}{}", indentation(&lines[index]), var.to_line());
+    ensure_valid_entries(&lines, &[index])?;
+    write_lines(&config_path, &lines)?;
+    env_var_at(&lines, index)
+}
+
+/// Removes a single environment variable from the env config file.
+#[tauri::command]
+pub fn delete_env_var(id: String) -> Result<(), String> {
+    let config_path = get_env_path();
+    let mut lines = read_lines(&config_path)?;
+    let index = find_env_var(&lines, &id)?;
+
+    lines.remove(index);
+    write_lines(&config_path, &lines)
+}
+
+/// Lists the built-in presets and whether each is present in `env.conf`.
+#[tauri::command]
+pub fn get_env_presets() -> Result<Vec<EnvPresetStatus>, String> {
+    let vars = get_env_vars()?;
+
+    Ok(ENV_PRESETS
+        .iter()
+        .map(|preset| {
+            let matching = preset
+                .vars
+                .iter()
+                .filter(|(key, value)| vars.iter().any(|v| v.key == *key && v.value == *value))
+                .count();
+            let present = preset
+                .vars
+                .iter()
+                .filter(|(key, _)| vars.iter().any(|v| v.key == *key))
+                .count();
+
+            EnvPresetStatus {
+                preset,
+                applied: matching == preset.vars.len(),
+                partial: present > 0 && matching < preset.vars.len(),
+            }
+        })
+        .collect())
+}
+
+/// Finds a preset by ID.
+fn find_preset(id: &str) -> Result<&'static EnvPreset, String> {
+    ENV_PRESETS
+        .iter()
+        .find(|p| p.id == id)
+        .ok_or_else(|| format!("Unknown preset '{}'", id))
+}
+
+/// Applies a preset: existing variables are updated in place, missing ones appended.
+/// `overrides` replaces preset values (e.g. a different cursor theme).
+#[tauri::command]
+pub fn apply_env_preset(
+    id: String,
+    overrides: Option<HashMap<String, String>>,
+) -> Result<(), String> {
+    let preset = find_preset(&id)?;
+    let overrides = overrides.unwrap_or_default();
+    let config_path = get_env_path();
+    let mut lines = read_lines(&config_path)?;
+    let mut written = Vec::new();
+
+    for (key, default) in preset.vars {
+        let var = EnvVar {
+            id: String::new(),
+            key: key.to_string(),
+            value: overrides
+                .get(*key)
+                .cloned()
+                .unwrap_or_else(|| default.to_string()),
+        };
+
+        let existing = parse_env_vars(&lines)
+            .into_iter()
+            .find(|(_, v)| v.key == *key)
+            .map(|(index, _)| index);
+
+        match existing {
+            Some(index) => {
+                lines[index] = format!("{}{}", indentation(&lines[index]), var.to_line());
+                written.push(index);
+            }
+            None => {
+                lines.push(var.to_line());
+                written.push(lines.len() - 1);
+            }
+        }
+    }
+
+    ensure_valid_entries(&lines, &written)?;
+    write_lines(&config_path, &lines)
+}
+
+/// Returns the line indices of the variables that still hold a preset's values.
+/// Variables the user has since changed are not part of the preset anymore.
+fn preset_entries(lines: &[String], preset: &EnvPreset) -> Vec<usize> {
+    parse_env_vars(lines)
+        .into_iter()
+        .filter(|(_, v)| {
+            preset
+                .vars
+                .iter()
+                .any(|(key, value)| v.key == *key && v.value == *value)
+        })
+        .map(|(index, _)| index)
+        .collect()
+}
+
+/// Removes the variables of a preset from `env.conf`, keeping any the user customised.
+#[tauri::command]
+pub fn remove_env_preset(id: String) -> Result<(), String> {
+    let preset = find_preset(&id)?;
+    let config_path = get_env_path();
+    let lines = read_lines(&config_path)?;
+    let remove = preset_entries(&lines, preset);
+
+    let kept: Vec<String> = lines
+        .into_iter()
+        .enumerate()
+        .filter(|(index, _)| !remove.contains(index))
+        .map(|(_, line)| line)
+        .collect();
+
+    write_lines(&config_path, &kept)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(text: &str) -> Vec<String> {
+        text.lines().map(String::from).collect()
+    }
+
+    #[test]
+    fn ignores_existing_problems_when_writing_other_entries() {
+        let file = lines(
+            "env = QUOTED,\"value\"\n\
+             env = DUP,1\n\
+             env = DUP,2\n\
+             env = http_proxy,http://proxy:3128",
+        );
+        assert!(ensure_valid_entries(&file, &[3]).is_ok());
+        assert!(ensure_valid_entries(&file, &[0]).is_err());
+        assert!(ensure_valid_entries(&file, &[2]).is_err());
+    }
+
+    #[test]
+    fn rejects_a_written_key_defined_elsewhere() {
+        let file = lines("env = EDITOR,vim\nenv = EDITOR,nvim");
+        let error = ensure_valid_entries(&file, &[1]).unwrap_err();
+        assert!(error.contains("more than once"));
+    }
+
+    #[test]
+    fn keeps_customised_preset_values() {
+        let preset = find_preset("cursor").unwrap();
+        let file = lines(
+            "env = XCURSOR_THEME,Bibata\n\
+             env = XCURSOR_SIZE,24\n\
+             env = EDITOR,nvim\n\
+             env = HYPRCURSOR_SIZE,24",
+        );
+        assert_eq!(preset_entries(&file, preset), [1, 3]);
+    }
+}
@@ -1,23 +1,253 @@
+use crate::modules::hyprland::config_edit::{indentation, read_lines, stable_id, write_lines};
+use crate::modules::hyprland::dispatchers::{is_known_dispatcher, validate_dispatcher};
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::env;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+
+/// A single flag appended to the `bind` keyword (e.g. the `l` and `e` in `bindle`).
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum BindFlag {
+    #[serde(rename = "l")]
+    Locked,
+    #[serde(rename = "r")]
+    Release,
+    #[serde(rename = "c")]
+    Click,
+    #[serde(rename = "g")]
+    Drag,
+    #[serde(rename = "o")]
+    LongPress,
+    #[serde(rename = "e")]
+    Repeat,
+    #[serde(rename = "n")]
+    NonConsuming,
+    #[serde(rename = "m")]
+    Mouse,
+    #[serde(rename = "t")]
+    Transparent,
+    #[serde(rename = "i")]
+    IgnoreMods,
+    #[serde(rename = "s")]
+    Separate,
+    #[serde(rename = "d")]
+    Description,
+    #[serde(rename = "p")]
+    BypassInhibit,
+}
+
+impl BindFlag {
+    /// Returns the flag for a keyword suffix character.
+    pub fn from_char(c: char) -> Option<Self> {
+        match c {
+            'l' => Some(Self::Locked),
+            'r' => Some(Self::Release),
+            'c' => Some(Self::Click),
+            'g' => Some(Self::Drag),
+            'o' => Some(Self::LongPress),
+            'e' => Some(Self::Repeat),
+            'n' => Some(Self::NonConsuming),
+            'm' => Some(Self::Mouse),
+            't' => Some(Self::Transparent),
+            'i' => Some(Self::IgnoreMods),
+            's' => Some(Self::Separate),
+            'd' => Some(Self::Description),
+            'p' => Some(Self::BypassInhibit),
+            _ => None,
+        }
+    }
+
+    /// Returns the keyword suffix character for this flag.
+    pub fn as_char(self) -> char {
+        match self {
+            Self::Locked => 'l',
+            Self::Release => 'r',
+            Self::Click => 'c',
+            Self::Drag => 'g',
+            Self::LongPress => 'o',
+            Self::Repeat => 'e',
+            Self::NonConsuming => 'n',
+            Self::Mouse => 'm',
+            Self::Transparent => 't',
+            Self::IgnoreMods => 'i',
+            Self::Separate => 's',
+            Self::Description => 'd',
+            Self::BypassInhibit => 'p',
+        }
+    }
+}
 
 /// Represents a Hyprland keybinding.
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Keybind {
     pub id: String,
-    pub bind_type: String, // e.g., bind, binde, bindm
+    #[serde(default)]
+    pub flags: BTreeSet<BindFlag>,
+    #[serde(default)]
+    pub description: Option<String>,
     pub modifiers: String,
     pub key: String,
     pub dispatcher: String,
     pub args: String,
+    /// Modifiers with `$variables` resolved, aliases unified and sorted (read-only).
+    #[serde(default)]
+    pub normalized_modifiers: Vec<String>,
+    /// Submap the binding belongs to; empty for global bindings.
+    #[serde(default)]
+    pub submap: String,
+}
+
+impl Keybind {
+    /// Parses a `bind[flags] = MODS, key, [description,] dispatcher[, args]` line.
+    /// Returns `None` if the line is not a keybinding.
+    pub fn parse(line: &str, id: String) -> Option<Self> {
+        let (keyword, rest) = line.trim().split_once('=')?;
+        let flags = parse_bind_keyword(keyword.trim())?;
+
+        // The description (if any) sits between the key and the dispatcher.
+        // Everything after the dispatcher belongs to the arguments, commas included.
+        let has_description = flags.contains(&BindFlag::Description);
+        let field_count = if has_description { 5 } else { 4 };
+        let parts: Vec<&str> = rest.splitn(field_count, ',').map(str::trim).collect();
+        if parts.len() < field_count - 1 {
+            return None;
+        }
+
+        let (description, dispatcher_idx) = if has_description {
+            (Some(parts[2].to_string()), 3)
+        } else {
+            (None, 2)
+        };
+
+        Some(Keybind {
+            id,
+            flags,
+            description,
+            modifiers: parts[0].to_string(),
+            key: parts[1].to_string(),
+            dispatcher: parts[dispatcher_idx].to_string(),
+            args: parts.get(dispatcher_idx + 1).unwrap_or(&"").to_string(),
+            normalized_modifiers: Vec::new(),
+            submap: String::new(),
+        })
+    }
+
+    /// Returns the bind keyword including its flags, e.g. `bindle`.
+    pub fn keyword(&self) -> String {
+        let mut flags = self.flags.clone();
+        // A description implies the `d` flag and vice versa
+        if self.description.is_some() {
+            flags.insert(BindFlag::Description);
+        } else {
+            flags.remove(&BindFlag::Description);
+        }
+
+        let mut keyword = "bind".to_string();
+        keyword.extend(flags.iter().map(|f| f.as_char()));
+        keyword
+    }
+
+    /// Content that identifies the binding independently of its position in the file.
+    fn identity(&self) -> String {
+        format!(
+            "{}|{}|{}|{}",
+            self.submap,
+            self.keyword(),
+            self.modifiers,
+            self.key
+        )
+    }
+
+    /// Serializes the keybinding back into a Hyprland config line.
+    pub fn to_line(&self) -> String {
+        let mut line = format!("{} = {}, {}", self.keyword(), self.modifiers, self.key);
+        if let Some(description) = &self.description {
+            line.push_str(&format!(", {}", description));
+        }
+        line.push_str(&format!(", {}", self.dispatcher));
+        if !self.args.is_empty() {
+            line.push_str(&format!(", {}", self.args));
+        }
+        line
+    }
+}
+
+/// Parses the flag set out of a bind keyword. Returns `None` for non-bind keywords.
+fn parse_bind_keyword(keyword: &str) -> Option<BTreeSet<BindFlag>> {
+    let suffix = keyword.strip_prefix("bind")?;
+    suffix.chars().map(BindFlag::from_char).collect()
+}
+
+/// Two or more bindings that claim the same key combination.
+#[derive(Serialize, Debug, Clone)]
+pub struct KeybindConflict {
+    pub kind: String, // "duplicate" or "shadowed"
+    pub combo: String,
+    pub submap: String,
+    pub ids: Vec<String>,
+    pub sources: Vec<String>, // "file:line" of every binding involved
+}
+
+/// A submap with the bindings that enter it.
+#[derive(Serialize, Debug)]
+pub struct Submap {
+    pub name: String,
+    pub bind_count: usize,
+    pub entered_by: Vec<String>, // IDs of bindings dispatching `submap, <name>`
+}
+
+/// Keybindings from the editable file along with detected conflicts.
+#[derive(Serialize, Debug)]
+pub struct KeybindsResult {
+    pub keybinds: Vec<Keybind>,
+    pub conflicts: Vec<KeybindConflict>,
+}
+
+/// A validation error for a single keybinding.
+#[derive(Serialize, Debug)]
+pub struct KeybindError {
+    pub id: String,
+    pub message: String,
+}
+
+/// Reasons `save_keybinds` can refuse to write the file.
+#[derive(Serialize, Debug)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SaveKeybindsError {
+    /// One or more bindings failed validation.
+    Invalid { errors: Vec<KeybindError> },
+    /// Bindings use dispatchers missing from the catalog and `force` was not set.
+    Warnings { warnings: Vec<KeybindError> },
+    /// Bindings clash and `force` was not set.
+    Conflicts { conflicts: Vec<KeybindConflict> },
+    /// The binding to change is no longer in the file (it was edited externally).
+    NotFound { id: String },
+    /// The file could not be written.
+    Io { message: String },
 }
 
 /// Arguments for the save_keybinds command.
 #[derive(Deserialize)]
 pub struct SaveKeybindsArgs {
     pub keybinds: Vec<Keybind>,
+    #[serde(default)]
+    pub force: bool,
+}
+
+/// A binding found anywhere in the Hyprland config tree.
+struct ConfigBind {
+    keybind: Keybind,
+    file: PathBuf,
+    line: usize,
+}
+
+/// Variables, submaps and bindings collected from `hyprland.conf` and every sourced file.
+#[derive(Default)]
+struct ConfigTree {
+    variables: HashMap<String, String>,
+    submaps: BTreeSet<String>,
+    binds: Vec<ConfigBind>,
 }
 
 /// Retrieves the user's home directory.
@@ -25,84 +255,1042 @@ fn get_home_dir() -> String {
     env::var("HOME").unwrap_or_else(|_| "/home/i4104".to_string())
 }
 
-/// Parses existing keybindings from the Hyprland keybinds configuration file.
-#[tauri::command]
-pub fn get_keybinds() -> Vec<Keybind> {
+/// Returns the path of the keybinds file managed by the app.
+fn get_keybinds_path() -> PathBuf {
+    Path::new(&get_home_dir()).join(".config/hypr/configs/keybinds.conf")
+}
+
+/// Expands a leading `~` or `$HOME` in a `source =` path.
+fn expand_source_path(raw: &str, base_dir: &Path) -> PathBuf {
     let home = get_home_dir();
-    let config_path = Path::new(&home).join(".config/hypr/configs/keybinds.conf");
-    let mut keybinds = Vec::new();
+    let raw = raw.trim();
+    let expanded = if let Some(rest) = raw.strip_prefix("~/") {
+        Path::new(&home).join(rest)
+    } else if let Some(rest) = raw.strip_prefix("$HOME/") {
+        Path::new(&home).join(rest)
+    } else {
+        PathBuf::from(raw)
+    };
+
+    if expanded.is_absolute() {
+        expanded
+    } else {
+        base_dir.join(expanded)
+    }
+}
+
+/// Recursively reads a config file, following `source =` lines and tracking submaps.
+fn collect_config(path: &Path, tree: &mut ConfigTree, visited: &mut HashSet<PathBuf>) {
+    let canonical = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    if !visited.insert(canonical.clone()) {
+        return;
+    }
+
+    // Only bindings from the editable file carry IDs the UI knows about
+    let keybinds_path = get_keybinds_path();
+    let is_keybinds_file = canonical == fs::canonicalize(&keybinds_path).unwrap_or(keybinds_path);
 
-    if let Ok(content) = fs::read_to_string(config_path) {
-        for (index, line) in content.lines().enumerate() {
-            let trim = line.trim();
-            if trim.is_empty() || trim.starts_with('#') {
+    let content = match fs::read_to_string(path) {
+        Ok(c) => c,
+        Err(_) => return,
+    };
+    let base_dir = path.parent().unwrap_or(Path::new("/")).to_path_buf();
+    let mut submap = String::new();
+    let mut seen = HashMap::new();
+
+    for (index, line) in content.lines().enumerate() {
+        let trim = line.trim();
+        if trim.is_empty() || trim.starts_with('#') {
+            continue;
+        }
+        let Some((keyword, value)) = trim.split_once('=') else {
+            continue;
+        };
+        let keyword = keyword.trim();
+        let value = value.trim();
+
+        if let Some(name) = keyword.strip_prefix('$') {
+            tree.variables.insert(name.to_string(), value.to_string());
+        } else if keyword == "source" {
+            collect_config(&expand_source_path(value, &base_dir), tree, visited);
+        } else if keyword == "submap" {
+            submap = parse_submap_line(value);
+            if !submap.is_empty() {
+                tree.submaps.insert(submap.clone());
+            }
+        } else {
+            let Some(mut keybind) = Keybind::parse(trim, String::new()) else {
                 continue;
+            };
+            keybind.submap = submap.clone();
+            keybind.id = if is_keybinds_file {
+                stable_id("kb", &keybind.identity(), &mut seen)
+            } else {
+                format!("{}:{}", path.display(), index + 1)
+            };
+            tree.binds.push(ConfigBind {
+                keybind,
+                file: path.to_path_buf(),
+                line: index + 1,
+            });
+        }
+    }
+}
+
+/// Returns the submap named by a `submap = <name>` line; `reset` yields the global scope.
+fn parse_submap_line(value: &str) -> String {
+    let value = value.trim();
+    if value == "reset" {
+        String::new()
+    } else {
+        value.to_string()
+    }
+}
+
+/// Loads the whole Hyprland config tree, making sure the keybinds file is included.
+fn load_config_tree() -> ConfigTree {
+    let home = get_home_dir();
+    let mut tree = ConfigTree::default();
+    let mut visited = HashSet::new();
+
+    collect_config(
+        &Path::new(&home).join(".config/hypr/hyprland.conf"),
+        &mut tree,
+        &mut visited,
+    );
+    collect_config(&get_keybinds_path(), &mut tree, &mut visited);
+    tree
+}
+
+/// Replaces `$name` references with their values, following nested variables.
+fn expand_variables(text: &str, variables: &HashMap<String, String>) -> String {
+    let mut result = text.to_string();
+    // Bounded to guard against self-referencing variables
+    for _ in 0..8 {
+        if !result.contains('$') {
+            break;
+        }
+        let mut names: Vec<&String> = variables.keys().collect();
+        // Longest names first so `$mainModShift` is not matched as `$mainMod`
+        names.sort_by(|a, b| b.len().cmp(&a.len()));
+        for name in names {
+            result = result.replace(&format!("${}", name), &variables[name]);
+        }
+    }
+    result
+}
+
+/// Maps modifier aliases onto Hyprland's canonical names.
+fn canonical_modifier(name: &str) -> String {
+    match name.to_uppercase().as_str() {
+        "WIN" | "LOGO" | "MOD4" | "META" => "SUPER".to_string(),
+        "CONTROL" => "CTRL".to_string(),
+        "MOD1" => "ALT".to_string(),
+        "LOCK" => "CAPS".to_string(),
+        other => other.to_string(),
+    }
+}
+
+/// Splits run-together modifiers such as `SUPERSHIFT`, which Hyprland accepts.
+/// Tokens that are not made up entirely of modifier names are returned unchanged.
+fn split_modifier_token(token: &str) -> Vec<String> {
+    const NAMES: [&str; 15] = [
+        "CONTROL", "SUPER", "SHIFT", "LOGO", "META", "CTRL", "CAPS", "LOCK", "MOD1", "MOD2",
+        "MOD3", "MOD4", "MOD5", "WIN", "ALT",
+    ];
+
+    let upper = token.to_uppercase();
+    let mut rest = upper.as_str();
+    let mut parts = Vec::new();
+    while !rest.is_empty() {
+        match NAMES.iter().find(|name| rest.starts_with(*name)) {
+            Some(name) => {
+                parts.push(name.to_string());
+                rest = &rest[name.len()..];
             }
+            None => return vec![upper],
+        }
+    }
+    parts
+}
+
+/// Resolves variables in a modifier string and returns a sorted, deduplicated modifier set.
+fn normalize_modifiers(modifiers: &str, variables: &HashMap<String, String>) -> Vec<String> {
+    const ORDER: [&str; 8] = [
+        "SUPER", "CTRL", "ALT", "SHIFT", "CAPS", "MOD2", "MOD3", "MOD5",
+    ];
+
+    let expanded = expand_variables(modifiers, variables);
+    let mut mods: Vec<String> = expanded
+        .split(|c: char| c.is_whitespace() || c == '_' || c == '+')
+        .filter(|m| !m.is_empty())
+        .flat_map(split_modifier_token)
+        .map(|m| canonical_modifier(&m))
+        .collect();
+
+    mods.sort_by_key(|m| ORDER.iter().position(|o| o == m).unwrap_or(ORDER.len()));
+    mods.dedup();
+    mods
+}
+
+/// Builds the comparison key for a binding: normalized modifiers plus the key name.
+fn combo_label(modifiers: &[String], key: &str, variables: &HashMap<String, String>) -> String {
+    let key = expand_variables(key, variables).trim().to_uppercase();
+    if modifiers.is_empty() {
+        key
+    } else {
+        format!("{}+{}", modifiers.join("+"), key)
+    }
+}
+
+/// Finds bindings sharing a combination within the same submap.
+/// Clashes within one file are duplicates; clashes across files are shadowed binds.
+fn find_conflicts(
+    binds: &[ConfigBind],
+    variables: &HashMap<String, String>,
+) -> Vec<KeybindConflict> {
+    let mut groups: HashMap<(String, String), Vec<&ConfigBind>> = HashMap::new();
+    let mut order: Vec<(String, String)> = Vec::new();
 
-            // Split into bind type and binding definition
-            if let Some((type_part, rest)) = trim.split_once('=') {
-                let btype = type_part.trim();
-
-                if btype.starts_with("bind") {
-                    let parts: Vec<&str> = rest.splitn(4, ',').collect();
-                    if parts.len() >= 3 {
-                        let mods = parts[0].trim().to_string();
-                        let key = parts[1].trim().to_string();
-                        let dispatcher = parts[2].trim().to_string();
-                        let args = if parts.len() > 3 {
-                            parts[3].trim().to_string()
-                        } else {
-                            "".to_string()
-                        };
-
-                        keybinds.push(Keybind {
-                            id: format!("kb-{}", index),
-                            bind_type: btype.to_string(),
-                            modifiers: mods,
-                            key,
-                            dispatcher,
-                            args,
-                        });
-                    }
-                }
+    for bind in binds {
+        let mods = normalize_modifiers(&bind.keybind.modifiers, variables);
+        let combo = combo_label(&mods, &bind.keybind.key, variables);
+        let group_key = (bind.keybind.submap.clone(), combo);
+        let entry = groups.entry(group_key.clone()).or_default();
+        if entry.is_empty() {
+            order.push(group_key);
+        }
+        entry.push(bind);
+    }
+
+    let mut conflicts = Vec::new();
+    for group_key in order {
+        let group = &groups[&group_key];
+        if group.len() < 2 {
+            continue;
+        }
+
+        let first_file = &group[0].file;
+        let kind = if group.iter().all(|b| &b.file == first_file) {
+            "duplicate"
+        } else {
+            "shadowed"
+        };
+
+        conflicts.push(KeybindConflict {
+            kind: kind.to_string(),
+            combo: group_key.1.clone(),
+            submap: group_key.0.clone(),
+            ids: group.iter().map(|b| b.keybind.id.clone()).collect(),
+            sources: group
+                .iter()
+                .map(|b| format!("{}:{}", b.file.display(), b.line))
+                .collect(),
+        });
+    }
+    conflicts
+}
+
+/// Parses the bindings of the editable keybinds file, recording the submap of each.
+/// Returns each binding with the index of the line it was read from.
+fn parse_keybinds(lines: &[String]) -> Vec<(usize, Keybind)> {
+    let mut keybinds = Vec::new();
+    let mut submap = String::new();
+    let mut seen = HashMap::new();
+
+    for (index, line) in lines.iter().enumerate() {
+        let trim = line.trim();
+        if trim.is_empty() || trim.starts_with('#') {
+            continue;
+        }
+
+        if let Some((keyword, value)) = trim.split_once('=') {
+            if keyword.trim() == "submap" {
+                submap = parse_submap_line(value);
+                continue;
             }
         }
+
+        if let Some(mut keybind) = Keybind::parse(trim, String::new()) {
+            keybind.submap = submap.clone();
+            keybind.id = stable_id("kb", &keybind.identity(), &mut seen);
+            keybinds.push((index, keybind));
+        }
+    }
+    keybinds
+}
+
+/// Reads the bindings of the editable keybinds file.
+fn read_keybinds_file(path: &Path) -> Result<Vec<Keybind>, String> {
+    Ok(parse_keybinds(&read_lines(path)?)
+        .into_iter()
+        .map(|(_, keybind)| keybind)
+        .collect())
+}
+
+/// Renders bindings as a keybinds file: global bindings first, then one block per submap.
+/// Returns the content and the line number each binding ID was written to.
+fn render_keybinds(keybinds: &[Keybind]) -> (String, HashMap<String, usize>) {
+    let mut lines = vec![
+        "# Custom keybinds".to_string(),
+        "# Auto-generated by Settings".to_string(),
+    ];
+    let mut line_numbers = HashMap::new();
+
+    let mut submaps: Vec<&str> = Vec::new();
+    for kb in keybinds {
+        if !kb.submap.is_empty() && !submaps.contains(&kb.submap.as_str()) {
+            submaps.push(&kb.submap);
+        }
+    }
+
+    for kb in keybinds.iter().filter(|kb| kb.submap.is_empty()) {
+        lines.push(kb.to_line());
+        line_numbers.insert(kb.id.clone(), lines.len());
+    }
+
+    for submap in submaps {
+        lines.push(String::new());
+        lines.push(format!("submap = {}", submap));
+        for kb in keybinds.iter().filter(|kb| kb.submap == submap) {
+            lines.push(kb.to_line());
+            line_numbers.insert(kb.id.clone(), lines.len());
+        }
+        lines.push("submap = reset".to_string());
+    }
+
+    let mut content = lines.join("\n");
+    content.push('\n');
+    (content, line_numbers)
+}
+
+/// Parses existing keybindings from the Hyprland keybinds configuration file
+/// and reports conflicts across the whole config tree.
+#[tauri::command]
+pub fn get_keybinds() -> Result<KeybindsResult, String> {
+    let tree = load_config_tree();
+    let mut keybinds = read_keybinds_file(&get_keybinds_path())?;
+
+    for keybind in keybinds.iter_mut() {
+        keybind.normalized_modifiers = normalize_modifiers(&keybind.modifiers, &tree.variables);
+    }
+
+    let conflicts = find_conflicts(&tree.binds, &tree.variables);
+    Ok(KeybindsResult {
+        keybinds,
+        conflicts,
+    })
+}
+
+/// Lists the submaps defined in the keybinds file and the bindings that enter them.
+#[tauri::command]
+pub fn get_submaps() -> Result<Vec<Submap>, String> {
+    let keybinds = read_keybinds_file(&get_keybinds_path())?;
+    let mut submaps: Vec<Submap> = Vec::new();
+
+    for kb in keybinds.iter().filter(|kb| !kb.submap.is_empty()) {
+        match submaps.iter_mut().find(|s| s.name == kb.submap) {
+            Some(submap) => submap.bind_count += 1,
+            None => submaps.push(Submap {
+                name: kb.submap.clone(),
+                bind_count: 1,
+                entered_by: Vec::new(),
+            }),
+        }
+    }
+
+    for kb in keybinds.iter().filter(|kb| kb.dispatcher == "submap") {
+        if let Some(submap) = submaps.iter_mut().find(|s| s.name == kb.args.trim()) {
+            submap.entered_by.push(kb.id.clone());
+        }
+    }
+    Ok(submaps)
+}
+
+/// Validates a submap name for use in `submap = <name>`.
+fn validate_submap_name(name: &str) -> Result<(), String> {
+    if name.is_empty() || name == "reset" || name.contains([',', '=', '#']) {
+        Err(format!("'{}' is not a valid submap name", name))
+    } else {
+        Ok(())
+    }
+}
+
+/// Returns the submap named by a `submap = <name>` line, with `reset` yielding "".
+/// Returns `None` for any other line.
+fn submap_line_name(line: &str) -> Option<String> {
+    let (keyword, value) = line.trim().split_once('=')?;
+    (keyword.trim() == "submap").then(|| parse_submap_line(value))
+}
+
+/// Builds a binding that dispatches `submap, <target>`.
+fn submap_bind(modifiers: &str, key: &str, target: &str) -> Keybind {
+    Keybind {
+        id: String::new(),
+        flags: BTreeSet::new(),
+        description: None,
+        modifiers: modifiers.to_string(),
+        key: key.to_string(),
+        dispatcher: "submap".to_string(),
+        args: target.to_string(),
+        normalized_modifiers: Vec::new(),
+        submap: String::new(),
+    }
+}
+
+/// Returns the indices of the lines that open a block for the submap.
+fn submap_block_starts(lines: &[String], name: &str) -> Vec<usize> {
+    lines
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| submap_line_name(line).as_deref() == Some(name))
+        .map(|(index, _)| index)
+        .collect()
+}
+
+/// Returns the bindings that enter the submap, with the index of their line.
+fn submap_entries(lines: &[String], name: &str) -> Vec<(usize, Keybind)> {
+    parse_keybinds(lines)
+        .into_iter()
+        .filter(|(_, kb)| kb.dispatcher == "submap" && kb.args.trim() == name)
+        .collect()
+}
+
+/// Fails unless the submap has a block or a binding entering it.
+fn ensure_submap_exists(lines: &[String], name: &str) -> Result<(), String> {
+    if submap_block_starts(lines, name).is_empty() && submap_entries(lines, name).is_empty() {
+        Err(format!("Submap '{}' does not exist", name))
+    } else {
+        Ok(())
+    }
+}
+
+/// Adds a global binding entering the submap and a new block left with Escape.
+fn add_submap_lines(
+    lines: &mut Vec<String>,
+    name: &str,
+    modifiers: &str,
+    key: &str,
+) -> Result<(), String> {
+    if !submap_block_starts(lines, name).is_empty() {
+        return Err(format!("Submap '{}' already exists", name));
+    }
+
+    let index = insertion_index(lines, "");
+    lines.insert(index, submap_bind(modifiers, key, name).to_line());
+    let index = insertion_index(lines, name);
+    lines.insert(index, submap_bind("", "escape", "reset").to_line());
+    Ok(())
+}
+
+/// Renames the submap's `submap =` lines and the bindings that enter it.
+fn rename_submap_lines(lines: &mut [String], old_name: &str, new_name: &str) -> Result<(), String> {
+    ensure_submap_exists(lines, old_name)?;
+    if !submap_block_starts(lines, new_name).is_empty() {
+        return Err(format!("Submap '{}' already exists", new_name));
+    }
+
+    for index in submap_block_starts(lines, old_name) {
+        lines[index] = format!("{}submap = {}", indentation(&lines[index]), new_name);
+    }
+    for (index, mut keybind) in submap_entries(lines, old_name) {
+        keybind.args = new_name.to_string();
+        lines[index] = format!("{}{}", indentation(&lines[index]), keybind.to_line());
+    }
+    Ok(())
+}
+
+/// Removes the submap's blocks, including their closing `submap = reset`,
+/// and the bindings that enter it.
+fn remove_submap_lines(lines: &mut Vec<String>, name: &str) -> Result<(), String> {
+    ensure_submap_exists(lines, name)?;
+
+    let mut removed: BTreeSet<usize> = submap_entries(lines, name)
+        .into_iter()
+        .map(|(index, _)| index)
+        .collect();
+    for start in submap_block_starts(lines, name) {
+        let end = lines[start + 1..]
+            .iter()
+            .position(|line| submap_line_name(line).is_some())
+            .map(|offset| start + 1 + offset);
+        let end = match end {
+            // Another block opening right away keeps its own line
+            Some(end) if submap_line_name(&lines[end]).as_deref() == Some("") => end + 1,
+            Some(end) => end,
+            None => lines.len(),
+        };
+        removed.extend(start..end);
+
+        // Drop the blank line that separated the block from the rest
+        if start > 0 && lines[start - 1].trim().is_empty() {
+            removed.insert(start - 1);
+        }
+    }
+
+    for index in removed.into_iter().rev() {
+        lines.remove(index);
     }
+    Ok(())
+}
+
+/// Creates a submap entered with the given combination and left with Escape.
+#[tauri::command]
+pub fn create_submap(name: String, modifiers: String, key: String) -> Result<(), String> {
+    let name = name.trim().to_string();
+    validate_submap_name(&name)?;
+
+    let config_path = get_keybinds_path();
+    let mut lines = read_lines(&config_path)?;
+    add_submap_lines(&mut lines, &name, &modifiers, &key)?;
+    write_lines(&config_path, &lines)
+}
+
+/// Renames a submap, updating its block and every binding that enters it.
+#[tauri::command]
+pub fn rename_submap(old_name: String, new_name: String) -> Result<(), String> {
+    let new_name = new_name.trim().to_string();
+    validate_submap_name(&new_name)?;
+
+    let config_path = get_keybinds_path();
+    let mut lines = read_lines(&config_path)?;
+    rename_submap_lines(&mut lines, &old_name, &new_name)?;
+    write_lines(&config_path, &lines)
+}
+
+/// Deletes a submap along with its bindings and the bindings that enter it.
+#[tauri::command]
+pub fn delete_submap(name: String) -> Result<(), String> {
+    let config_path = get_keybinds_path();
+    let mut lines = read_lines(&config_path)?;
+    remove_submap_lines(&mut lines, &name)?;
+    write_lines(&config_path, &lines)
+}
+
+/// Checks every binding's dispatcher and arguments against the dispatcher catalog,
+/// and that `submap` bindings point at a submap that exists.
+fn validate_keybinds(keybinds: &[Keybind], known_submaps: &BTreeSet<String>) -> Vec<KeybindError> {
+    keybinds
+        .iter()
+        .filter_map(|kb| {
+            let target = kb.args.trim();
+            let message = if kb.key.trim().is_empty() {
+                "Key is required".to_string()
+            } else if kb.description.as_deref().is_some_and(|d| d.contains(',')) {
+                // Hyprland would read the text after the comma as the dispatcher
+                "Descriptions cannot contain commas".to_string()
+            } else if !kb.submap.is_empty() && validate_submap_name(&kb.submap).is_err() {
+                format!("'{}' is not a valid submap name", kb.submap)
+            } else if kb.dispatcher == "submap"
+                && target != "reset"
+                && !known_submaps.contains(target)
+                && !keybinds.iter().any(|other| other.submap == target)
+            {
+                format!("Submap '{}' does not exist", target)
+            } else {
+                validate_dispatcher(&kb.dispatcher, &kb.args).err()?
+            };
+            Some(KeybindError {
+                id: kb.id.clone(),
+                message,
+            })
+        })
+        .collect()
+}
+
+/// Flags bindings whose dispatcher is not in the catalog. The catalog can lag behind
+/// Hyprland, so these are warnings the user may override rather than errors.
+fn dispatcher_warnings(keybinds: &[Keybind]) -> Vec<KeybindError> {
     keybinds
+        .iter()
+        .filter(|kb| !is_known_dispatcher(&kb.dispatcher))
+        .map(|kb| KeybindError {
+            id: kb.id.clone(),
+            message: format!("Unknown dispatcher '{}'", kb.dispatcher.trim()),
+        })
+        .collect()
 }
 
 /// Saves the provided list of keybindings to the Hyprland configuration file.
+/// Refuses to write invalid bindings, and ones with unknown dispatchers or
+/// conflicts unless `force` is set.
 #[tauri::command]
-pub fn save_keybinds(args: SaveKeybindsArgs) -> Result<(), String> {
-    let home = get_home_dir();
-    let config_path = Path::new(&home).join(".config/hypr/configs/keybinds.conf");
+pub fn save_keybinds(args: SaveKeybindsArgs) -> Result<(), SaveKeybindsError> {
+    let config_path = get_keybinds_path();
+    let tree = load_config_tree();
+
+    let errors = validate_keybinds(&args.keybinds, &tree.submaps);
+    if !errors.is_empty() {
+        return Err(SaveKeybindsError::Invalid { errors });
+    }
+
+    let (content, line_numbers) = render_keybinds(&args.keybinds);
+
+    if !args.force {
+        let warnings = dispatcher_warnings(&args.keybinds);
+        if !warnings.is_empty() {
+            return Err(SaveKeybindsError::Warnings { warnings });
+        }
+
+        let canonical_target = fs::canonicalize(&config_path).unwrap_or(config_path.clone());
+
+        // Compare the new bindings against everything defined outside the keybinds file
+        let mut binds: Vec<ConfigBind> = tree
+            .binds
+            .into_iter()
+            .filter(|b| fs::canonicalize(&b.file).unwrap_or(b.file.clone()) != canonical_target)
+            .collect();
+        binds.extend(args.keybinds.iter().map(|kb| ConfigBind {
+            keybind: kb.clone(),
+            file: config_path.clone(),
+            line: line_numbers.get(&kb.id).copied().unwrap_or(0),
+        }));
+
+        let conflicts = find_conflicts(&binds, &tree.variables);
+        if !conflicts.is_empty() {
+            return Err(SaveKeybindsError::Conflicts { conflicts });
+        }
+    }
 
     if let Some(parent) = config_path.parent() {
         let _ = fs::create_dir_all(parent);
     }
 
-    let mut content = String::new();
-    content.push_str("# Custom keybinds\n");
-    content.push_str("# Auto-generated by Settings\n");
+    fs::write(config_path, content).map_err(|e| SaveKeybindsError::Io {
+        message: e.to_string(),
+    })?;
 
-    for kb in args.keybinds {
-        let btype = if kb.bind_type.is_empty() {
-            "bind"
-        } else {
-            &kb.bind_type
-        };
-        let mut line = format!(
-            "{} = {}, {}, {}",
-            btype, kb.modifiers, kb.key, kb.dispatcher
+    Ok(())
+}
+
+/// Returns the index at which a binding for `submap` should be inserted,
+/// creating the submap block at the end of the file if it does not exist yet.
+fn insertion_index(lines: &mut Vec<String>, submap: &str) -> usize {
+    if submap.is_empty() {
+        return lines.len();
+    }
+
+    let is_submap_line = |line: &String| {
+        line.trim()
+            .split_once('=')
+            .is_some_and(|(keyword, _)| keyword.trim() == "submap")
+    };
+
+    let block_start = lines.iter().position(|line| {
+        is_submap_line(line) && line.split_once('=').map(|(_, v)| v.trim()) == Some(submap)
+    });
+
+    match block_start {
+        Some(start) => lines[start + 1..]
+            .iter()
+            .position(is_submap_line)
+            .map(|offset| start + 1 + offset)
+            .unwrap_or(lines.len()),
+        None => {
+            lines.push(String::new());
+            lines.push(format!("submap = {}", submap));
+            lines.push("submap = reset".to_string());
+            lines.len() - 1
+        }
+    }
+}
+
+/// Validates an edited keybinds file, checks the changed binding for unknown dispatchers
+/// and conflicts, and writes it.
+/// Returns the changed binding as re-read from the new content, with its new ID.
+fn commit_keybind_edit(
+    lines: Vec<String>,
+    changed_line: usize,
+    force: bool,
+) -> Result<Keybind, SaveKeybindsError> {
+    let config_path = get_keybinds_path();
+    let tree = load_config_tree();
+
+    let parsed = parse_keybinds(&lines);
+    let changed = parsed
+        .iter()
+        .find(|(index, _)| *index == changed_line)
+        .map(|(_, kb)| kb.clone())
+        .ok_or_else(|| SaveKeybindsError::Io {
+            message: "Failed to re-read the edited keybind".to_string(),
+        })?;
+
+    let errors = validate_keybinds(std::slice::from_ref(&changed), &tree.submaps);
+    if !errors.is_empty() {
+        return Err(SaveKeybindsError::Invalid { errors });
+    }
+
+    if !force {
+        let warnings = dispatcher_warnings(std::slice::from_ref(&changed));
+        if !warnings.is_empty() {
+            return Err(SaveKeybindsError::Warnings { warnings });
+        }
+
+        let canonical_target = fs::canonicalize(&config_path).unwrap_or(config_path.clone());
+        let mut binds: Vec<ConfigBind> = tree
+            .binds
+            .into_iter()
+            .filter(|b| fs::canonicalize(&b.file).unwrap_or(b.file.clone()) != canonical_target)
+            .collect();
+        binds.extend(parsed.into_iter().map(|(index, keybind)| ConfigBind {
+            keybind,
+            file: config_path.clone(),
+            line: index + 1,
+        }));
+
+        // Only clashes involving the changed binding block the edit
+        let conflicts: Vec<KeybindConflict> = find_conflicts(&binds, &tree.variables)
+            .into_iter()
+            .filter(|c| c.ids.contains(&changed.id))
+            .collect();
+        if !conflicts.is_empty() {
+            return Err(SaveKeybindsError::Conflicts { conflicts });
+        }
+    }
+
+    write_lines(&config_path, &lines).map_err(|message| SaveKeybindsError::Io { message })?;
+    Ok(changed)
+}
+
+/// Appends a single keybinding to the keybinds file, inside its submap block if it has one.
+#[tauri::command]
+pub fn add_keybind(keybind: Keybind, force: bool) -> Result<Keybind, SaveKeybindsError> {
+    let mut lines =
+        read_lines(&get_keybinds_path()).map_err(|message| SaveKeybindsError::Io { message })?;
+    let index = insertion_index(&mut lines, &keybind.submap);
+    lines.insert(index, keybind.to_line());
+    commit_keybind_edit(lines, index, force)
+}
+
+/// Replaces a single keybinding in place, leaving the rest of the file untouched.
+#[tauri::command]
+pub fn update_keybind(
+    id: String,
+    keybind: Keybind,
+    force: bool,
+) -> Result<Keybind, SaveKeybindsError> {
+    let mut lines =
+        read_lines(&get_keybinds_path()).map_err(|message| SaveKeybindsError::Io { message })?;
+    let (index, existing) = parse_keybinds(&lines)
+        .into_iter()
+        .find(|(_, kb)| kb.id == id)
+        .ok_or(SaveKeybindsError::NotFound { id })?;
+
+    if existing.submap == keybind.submap {
+        lines[index] = format!("{}{}", indentation(&lines[index]), keybind.to_line());
+        commit_keybind_edit(lines, index, force)
+    } else {
+        // Moving to another submap means moving the line into that block
+        lines.remove(index);
+        let index = insertion_index(&mut lines, &keybind.submap);
+        lines.insert(index, keybind.to_line());
+        commit_keybind_edit(lines, index, force)
+    }
+}
+
+/// Removes a single keybinding from the keybinds file.
+#[tauri::command]
+pub fn delete_keybind(id: String) -> Result<(), SaveKeybindsError> {
+    let config_path = get_keybinds_path();
+    let mut lines =
+        read_lines(&config_path).map_err(|message| SaveKeybindsError::Io { message })?;
+    let (index, _) = parse_keybinds(&lines)
+        .into_iter()
+        .find(|(_, kb)| kb.id == id)
+        .ok_or(SaveKeybindsError::NotFound { id })?;
+
+    lines.remove(index);
+    write_lines(&config_path, &lines).map_err(|message| SaveKeybindsError::Io { message })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(line: &str) -> Keybind {
+        Keybind::parse(line, String::new()).expect("line should parse as a keybind")
+    }
+
+    fn lines(text: &str) -> Vec<String> {
+        text.lines().map(String::from).collect()
+    }
+
+    #[test]
+    fn parses_flags_from_keyword() {
+        let kb = parse("bindle = , XF86AudioRaiseVolume, exec, wpctl set-volume @DEFAULT@ 5%+");
+        assert_eq!(
+            kb.flags,
+            BTreeSet::from([BindFlag::Locked, BindFlag::Repeat])
+        );
+        assert_eq!(kb.modifiers, "");
+        assert_eq!(kb.key, "XF86AudioRaiseVolume");
+        assert_eq!(kb.dispatcher, "exec");
+        assert_eq!(kb.args, "wpctl set-volume @DEFAULT@ 5%+");
+    }
+
+    #[test]
+    fn rejects_unknown_keywords_and_flags() {
+        assert!(Keybind::parse("unbind = SUPER, Q", String::new()).is_none());
+        assert!(Keybind::parse("bindx = SUPER, Q, killactive", String::new()).is_none());
+        assert!(Keybind::parse("monitor = ,preferred,auto,1", String::new()).is_none());
+        assert!(Keybind::parse("bind = SUPER", String::new()).is_none());
+    }
+
+    #[test]
+    fn renders_flags_in_canonical_order() {
+        assert_eq!(
+            parse("bindel = , XF86MonBrightnessUp, exec, brightnessctl s 5%+").keyword(),
+            "bindle"
         );
-        if !kb.args.is_empty() {
-            line.push_str(&format!(", {}", kb.args));
+        assert_eq!(
+            parse("bindrl = SUPER, SUPER_L, exec, rofi").keyword(),
+            "bindlr"
+        );
+        assert_eq!(parse("bind = SUPER, Q, killactive").keyword(), "bind");
+    }
+
+    #[test]
+    fn round_trips_every_flag() {
+        for flag in "lrcgoenmtisp".chars() {
+            let line = format!("bind{} = SUPER, Q, killactive", flag);
+            assert_eq!(parse(&line).to_line(), line);
         }
-        line.push('\n');
-        content.push_str(&line);
+        let line = "bindlrcgoenmtisp = SUPER, Q, killactive";
+        assert_eq!(parse(line).to_line(), line);
     }
 
-    fs::write(config_path, content).map_err(|e| e.to_string())?;
+    #[test]
+    fn keeps_commas_in_arguments() {
+        let line = "bind = SUPER, P, exec, notify-send 'a, b' \"c, d\"";
+        let kb = parse(line);
+        assert_eq!(kb.args, "notify-send 'a, b' \"c, d\"");
+        assert_eq!(kb.to_line(), line);
+    }
 
-    Ok(())
+    #[test]
+    fn parses_bindd_description() {
+        let line = "bindd = SUPER, Return, Open terminal, exec, kitty --title a,b";
+        let kb = parse(line);
+        assert_eq!(kb.description.as_deref(), Some("Open terminal"));
+        assert_eq!(kb.dispatcher, "exec");
+        assert_eq!(kb.args, "kitty --title a,b");
+        assert_eq!(kb.to_line(), line);
+    }
+
+    #[test]
+    fn description_controls_d_flag() {
+        let mut kb = parse("bindd = SUPER, Q, Close window, killactive");
+        kb.description = None;
+        assert_eq!(kb.to_line(), "bind = SUPER, Q, killactive");
+
+        let mut kb = parse("bindl = SUPER, Q, killactive");
+        kb.description = Some("Close window".to_string());
+        assert_eq!(kb.to_line(), "bindld = SUPER, Q, Close window, killactive");
+    }
+
+    #[test]
+    fn rejects_commas_in_descriptions() {
+        // Hyprland splits on every comma before the dispatcher, so the description
+        // would be cut and its tail read as the dispatcher
+        let kb = parse("bindd = SUPER, Q, Close, then quit, killactive");
+        assert_eq!(kb.description.as_deref(), Some("Close"));
+        assert_eq!(kb.dispatcher, "then quit");
+
+        let mut kb = parse("bind = SUPER, Q, killactive");
+        kb.description = Some("Close, then quit".to_string());
+        let errors = validate_keybinds(&[kb], &BTreeSet::new());
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].message.contains("comma"));
+    }
+
+    #[test]
+    fn accepts_stock_hyprland_binds() {
+        let keybinds: Vec<Keybind> = [
+            "bind = $mainMod, J, togglesplit,",
+            "bind = $mainMod, P, pseudo,",
+            "bind = $mainMod, S, movetoworkspace, special:magic",
+            "bind = $mainMod, mouse_down, workspace, e+1",
+            "bindm = $mainMod, mouse:272, movewindow",
+            "bindm = $mainMod, mouse:273, resizewindow",
+            "bindel = ,XF86AudioRaiseVolume, exec, wpctl set-volume -l 1 @DEFAULT_AUDIO_SINK@ 5%+",
+        ]
+        .iter()
+        .map(|line| parse(line))
+        .collect();
+        assert!(validate_keybinds(&keybinds, &BTreeSet::new()).is_empty());
+        assert!(dispatcher_warnings(&keybinds).is_empty());
+    }
+
+    #[test]
+    fn warns_about_unknown_dispatchers() {
+        let keybinds = [
+            parse("bind = SUPER, Q, newdispatcher, some args"),
+            parse("bind = SUPER, Tab, hyprexpo:expo, toggle"),
+        ];
+        assert!(validate_keybinds(&keybinds, &BTreeSet::new()).is_empty());
+        let warnings = dispatcher_warnings(&keybinds);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].message, "Unknown dispatcher 'newdispatcher'");
+    }
+
+    #[test]
+    fn normalizes_modifiers() {
+        let variables = HashMap::from([
+            ("mainMod".to_string(), "SUPER".to_string()),
+            ("shiftMod".to_string(), "$mainMod SHIFT".to_string()),
+        ]);
+        let expected = ["SUPER", "SHIFT"];
+        assert_eq!(normalize_modifiers("$shiftMod", &variables), expected);
+        assert_eq!(normalize_modifiers("shift_win", &variables), expected);
+        assert_eq!(normalize_modifiers("SUPERSHIFT", &variables), expected);
+        assert_eq!(normalize_modifiers("$mainModSHIFT", &variables), expected);
+        assert_eq!(
+            normalize_modifiers("ControlAlt", &variables),
+            ["CTRL", "ALT"]
+        );
+        assert_eq!(normalize_modifiers("", &variables), Vec::<String>::new());
+    }
+
+    #[test]
+    fn tracks_submaps_while_parsing() {
+        let parsed = parse_keybinds(&lines(
+            "bind = SUPER, R, submap, resize\n\
+             submap = resize\n\
+             binde = , right, resizeactive, 10 0\n\
+             bind = , escape, submap, reset\n\
+             submap = reset\n\
+             bind = SUPER, Q, killactive",
+        ));
+        let submaps: Vec<&str> = parsed.iter().map(|(_, kb)| kb.submap.as_str()).collect();
+        assert_eq!(submaps, ["", "resize", "resize", ""]);
+        let indices: Vec<usize> = parsed.iter().map(|(index, _)| *index).collect();
+        assert_eq!(indices, [0, 2, 3, 5]);
+    }
+
+    #[test]
+    fn renders_submap_blocks_after_global_binds() {
+        let parsed = parse_keybinds(&lines(
+            "submap = resize\n\
+             binde = , right, resizeactive, 10 0\n\
+             submap = reset\n\
+             bind = SUPER, R, submap, resize\n\
+             submap = resize\n\
+             bind = , escape, submap, reset\n\
+             submap = reset",
+        ));
+        let keybinds: Vec<Keybind> = parsed.into_iter().map(|(_, kb)| kb).collect();
+        let (content, line_numbers) = render_keybinds(&keybinds);
+
+        assert_eq!(
+            content,
+            "# Custom keybinds\n\
+             # Auto-generated by Settings\n\
+             bind = SUPER, R, submap, resize\n\
+             \n\
+             submap = resize\n\
+             binde = , right, resizeactive, 10 0\n\
+             bind = , escape, submap, reset\n\
+             submap = reset\n"
+        );
+        assert_eq!(line_numbers[&keybinds[1].id], 3);
+        assert_eq!(line_numbers[&keybinds[0].id], 6);
+
+        // Reading the rendered file back yields the same bindings and IDs
+        let reparsed = parse_keybinds(&lines(&content));
+        let ids: BTreeSet<&str> = reparsed.iter().map(|(_, kb)| kb.id.as_str()).collect();
+        assert_eq!(ids, keybinds.iter().map(|kb| kb.id.as_str()).collect());
+    }
+
+    #[test]
+    fn inserts_into_existing_or_new_submap_block() {
+        let mut file = lines(
+            "bind = SUPER, Q, killactive\n\
+             submap = resize\n\
+             binde = , right, resizeactive, 10 0\n\
+             submap = reset",
+        );
+        assert_eq!(insertion_index(&mut file, ""), 4);
+        assert_eq!(insertion_index(&mut file, "resize"), 3);
+
+        let index = insertion_index(&mut file, "move");
+        assert_eq!(index, 6);
+        assert_eq!(file[4..], ["", "submap = move", "submap = reset"]);
+    }
+
+    const USER_FILE: &str = "\
+$mainMod = SUPER
+# Window management
+source = ~/.config/hypr/extra.conf
+unbind = SUPER, Q
+bind = $mainMod, Q, killactive
+bind = $mainMod, R, submap, resize
+
+submap = resize
+binde = , right, resizeactive, 10 0
+bind = , escape, submap, reset
+submap = reset";
+
+    /// Asserts that the lines the submap editors must not touch are still there.
+    fn assert_user_lines_kept(file: &[String]) {
+        for line in [
+            "$mainMod = SUPER",
+            "# Window management",
+            "source = ~/.config/hypr/extra.conf",
+            "unbind = SUPER, Q",
+            "bind = $mainMod, Q, killactive",
+        ] {
+            assert!(file.iter().any(|l| l == line), "{} was lost", line);
+        }
+    }
+
+    #[test]
+    fn creates_submaps_in_place() {
+        let mut file = lines(USER_FILE);
+        add_submap_lines(&mut file, "move", "$mainMod", "M").unwrap();
+
+        assert_user_lines_kept(&file);
+        assert_eq!(file[..11], lines(USER_FILE)[..]);
+        assert_eq!(
+            file[11..],
+            [
+                "bind = $mainMod, M, submap, move",
+                "",
+                "submap = move",
+                "bind = , escape, submap, reset",
+                "submap = reset",
+            ]
+        );
+
+        let err = add_submap_lines(&mut file, "resize", "", "X").unwrap_err();
+        assert!(err.contains("already exists"));
+    }
+
+    #[test]
+    fn renames_submaps_in_place() {
+        let mut file = lines(USER_FILE);
+        rename_submap_lines(&mut file, "resize", "size").unwrap();
+
+        assert_user_lines_kept(&file);
+        assert!(file.contains(&"bind = $mainMod, R, submap, size".to_string()));
+        assert!(file.contains(&"submap = size".to_string()));
+        assert!(!file
+            .iter()
+            .any(|l| l.contains("resize,") || l == "submap = resize"));
+        assert_eq!(file.len(), lines(USER_FILE).len());
+
+        let err = rename_submap_lines(&mut file, "missing", "other").unwrap_err();
+        assert!(err.contains("does not exist"));
+    }
+
+    #[test]
+    fn deletes_submaps_in_place() {
+        let mut file = lines(USER_FILE);
+        remove_submap_lines(&mut file, "resize").unwrap();
+
+        assert_user_lines_kept(&file);
+        assert_eq!(file, lines(USER_FILE)[..5]);
+
+        let err = remove_submap_lines(&mut file, "resize").unwrap_err();
+        assert!(err.contains("does not exist"));
+    }
 }
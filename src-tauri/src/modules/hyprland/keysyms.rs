@@ -0,0 +1,490 @@
+use crate::modules::hyprland::keybinds::{get_keybinds, Keybind};
+use serde::{Deserialize, Serialize};
+
+/// XKB keysyms for non-character keys and their canonical names.
+const KEYSYMS: &[(u32, &str)] = &[
+    (0xff08, "BackSpace"),
+    (0xff09, "Tab"),
+    (0xff0d, "Return"),
+    (0xff13, "Pause"),
+    (0xff14, "Scroll_Lock"),
+    (0xff1b, "Escape"),
+    (0xff50, "Home"),
+    (0xff51, "Left"),
+    (0xff52, "Up"),
+    (0xff53, "Right"),
+    (0xff54, "Down"),
+    (0xff55, "Prior"),
+    (0xff56, "Next"),
+    (0xff57, "End"),
+    (0xff61, "Print"),
+    (0xff63, "Insert"),
+    (0xff67, "Menu"),
+    (0xff7f, "Num_Lock"),
+    (0xff8d, "KP_Enter"),
+    (0xffaa, "KP_Multiply"),
+    (0xffab, "KP_Add"),
+    (0xffad, "KP_Subtract"),
+    (0xffae, "KP_Decimal"),
+    (0xffaf, "KP_Divide"),
+    (0xffb0, "KP_0"),
+    (0xffb1, "KP_1"),
+    (0xffb2, "KP_2"),
+    (0xffb3, "KP_3"),
+    (0xffb4, "KP_4"),
+    (0xffb5, "KP_5"),
+    (0xffb6, "KP_6"),
+    (0xffb7, "KP_7"),
+    (0xffb8, "KP_8"),
+    (0xffb9, "KP_9"),
+    (0xffe1, "Shift_L"),
+    (0xffe2, "Shift_R"),
+    (0xffe3, "Control_L"),
+    (0xffe4, "Control_R"),
+    (0xffe5, "Caps_Lock"),
+    (0xffe9, "Alt_L"),
+    (0xffea, "Alt_R"),
+    (0xffeb, "Super_L"),
+    (0xffec, "Super_R"),
+    (0xffff, "Delete"),
+    (0x1008ff02, "XF86MonBrightnessUp"),
+    (0x1008ff03, "XF86MonBrightnessDown"),
+    (0x1008ff11, "XF86AudioLowerVolume"),
+    (0x1008ff12, "XF86AudioMute"),
+    (0x1008ff13, "XF86AudioRaiseVolume"),
+    (0x1008ff14, "XF86AudioPlay"),
+    (0x1008ff15, "XF86AudioStop"),
+    (0x1008ff16, "XF86AudioPrev"),
+    (0x1008ff17, "XF86AudioNext"),
+    (0x1008ff1b, "XF86Search"),
+    (0x1008ff1d, "XF86Calculator"),
+    (0x1008ff2f, "XF86Sleep"),
+    (0x1008ff31, "XF86AudioPause"),
+    (0x1008ff5d, "XF86Explorer"),
+    (0x1008ff8f, "XF86WebCam"),
+    (0x1008ffa9, "XF86TouchpadToggle"),
+    (0x1008ffb2, "XF86AudioMicMute"),
+];
+
+/// Names of the printable ASCII keysyms (0x20 to 0x7e), separated by whitespace.
+const ASCII_KEYSYMS: &str = "\
+    space exclam quotedbl numbersign dollar percent ampersand apostrophe parenleft \
+    parenright asterisk plus comma minus period slash 0 1 2 3 4 5 6 7 8 9 colon semicolon \
+    less equal greater question at A B C D E F G H I J K L M N O P Q R S T U V W X Y Z \
+    bracketleft backslash bracketright asciicircum underscore grave a b c d e f g h i j k l \
+    m n o p q r s t u v w x y z braceleft bar braceright asciitilde";
+
+/// DOM `KeyboardEvent.code` values with their unshifted US-layout keysym name and evdev code.
+const PHYSICAL_KEYS: &[(&str, &str, u32)] = &[
+    ("Escape", "Escape", 1),
+    ("Digit1", "1", 2),
+    ("Digit2", "2", 3),
+    ("Digit3", "3", 4),
+    ("Digit4", "4", 5),
+    ("Digit5", "5", 6),
+    ("Digit6", "6", 7),
+    ("Digit7", "7", 8),
+    ("Digit8", "8", 9),
+    ("Digit9", "9", 10),
+    ("Digit0", "0", 11),
+    ("Minus", "minus", 12),
+    ("Equal", "equal", 13),
+    ("Backspace", "BackSpace", 14),
+    ("Tab", "Tab", 15),
+    ("KeyQ", "Q", 16),
+    ("KeyW", "W", 17),
+    ("KeyE", "E", 18),
+    ("KeyR", "R", 19),
+    ("KeyT", "T", 20),
+    ("KeyY", "Y", 21),
+    ("KeyU", "U", 22),
+    ("KeyI", "I", 23),
+    ("KeyO", "O", 24),
+    ("KeyP", "P", 25),
+    ("BracketLeft", "bracketleft", 26),
+    ("BracketRight", "bracketright", 27),
+    ("Enter", "Return", 28),
+    ("ControlLeft", "Control_L", 29),
+    ("KeyA", "A", 30),
+    ("KeyS", "S", 31),
+    ("KeyD", "D", 32),
+    ("KeyF", "F", 33),
+    ("KeyG", "G", 34),
+    ("KeyH", "H", 35),
+    ("KeyJ", "J", 36),
+    ("KeyK", "K", 37),
+    ("KeyL", "L", 38),
+    ("Semicolon", "semicolon", 39),
+    ("Quote", "apostrophe", 40),
+    ("Backquote", "grave", 41),
+    ("ShiftLeft", "Shift_L", 42),
+    ("Backslash", "backslash", 43),
+    ("KeyZ", "Z", 44),
+    ("KeyX", "X", 45),
+    ("KeyC", "C", 46),
+    ("KeyV", "V", 47),
+    ("KeyB", "B", 48),
+    ("KeyN", "N", 49),
+    ("KeyM", "M", 50),
+    ("Comma", "comma", 51),
+    ("Period", "period", 52),
+    ("Slash", "slash", 53),
+    ("ShiftRight", "Shift_R", 54),
+    ("NumpadMultiply", "KP_Multiply", 55),
+    ("AltLeft", "Alt_L", 56),
+    ("Space", "space", 57),
+    ("CapsLock", "Caps_Lock", 58),
+    ("F1", "F1", 59),
+    ("F2", "F2", 60),
+    ("F3", "F3", 61),
+    ("F4", "F4", 62),
+    ("F5", "F5", 63),
+    ("F6", "F6", 64),
+    ("F7", "F7", 65),
+    ("F8", "F8", 66),
+    ("F9", "F9", 67),
+    ("F10", "F10", 68),
+    ("NumLock", "Num_Lock", 69),
+    ("ScrollLock", "Scroll_Lock", 70),
+    ("Numpad7", "KP_7", 71),
+    ("Numpad8", "KP_8", 72),
+    ("Numpad9", "KP_9", 73),
+    ("NumpadSubtract", "KP_Subtract", 74),
+    ("Numpad4", "KP_4", 75),
+    ("Numpad5", "KP_5", 76),
+    ("Numpad6", "KP_6", 77),
+    ("NumpadAdd", "KP_Add", 78),
+    ("Numpad1", "KP_1", 79),
+    ("Numpad2", "KP_2", 80),
+    ("Numpad3", "KP_3", 81),
+    ("Numpad0", "KP_0", 82),
+    ("NumpadDecimal", "KP_Decimal", 83),
+    ("IntlBackslash", "less", 86),
+    ("F11", "F11", 87),
+    ("F12", "F12", 88),
+    ("NumpadEnter", "KP_Enter", 96),
+    ("ControlRight", "Control_R", 97),
+    ("NumpadDivide", "KP_Divide", 98),
+    ("PrintScreen", "Print", 99),
+    ("AltRight", "Alt_R", 100),
+    ("Home", "Home", 102),
+    ("ArrowUp", "Up", 103),
+    ("PageUp", "Prior", 104),
+    ("ArrowLeft", "Left", 105),
+    ("ArrowRight", "Right", 106),
+    ("End", "End", 107),
+    ("ArrowDown", "Down", 108),
+    ("PageDown", "Next", 109),
+    ("Insert", "Insert", 110),
+    ("Delete", "Delete", 111),
+    ("AudioVolumeMute", "XF86AudioMute", 113),
+    ("AudioVolumeDown", "XF86AudioLowerVolume", 114),
+    ("AudioVolumeUp", "XF86AudioRaiseVolume", 115),
+    ("Pause", "Pause", 119),
+    ("MetaLeft", "Super_L", 125),
+    ("MetaRight", "Super_R", 126),
+    ("ContextMenu", "Menu", 127),
+    ("MediaTrackNext", "XF86AudioNext", 163),
+    ("MediaPlayPause", "XF86AudioPlay", 164),
+    ("MediaTrackPrevious", "XF86AudioPrev", 165),
+    ("MediaStop", "XF86AudioStop", 166),
+];
+
+/// Difference between evdev scancodes and the XKB keycodes used by `code:NN`.
+const XKB_KEYCODE_OFFSET: u32 = 8;
+
+/// A key or mouse event captured by the webview.
+#[derive(Deserialize, Debug)]
+pub struct CapturedKey {
+    /// XKB keysym, when the frontend can provide one.
+    #[serde(default)]
+    pub keysym: Option<u32>,
+    /// XKB keycode (evdev scancode + 8), when available.
+    #[serde(default)]
+    pub keycode: Option<u32>,
+    /// DOM `KeyboardEvent.code`, e.g. `KeyQ`.
+    #[serde(default)]
+    pub code: String,
+    /// DOM `KeyboardEvent.key`, e.g. `q`.
+    #[serde(default)]
+    pub key: String,
+    /// DOM `MouseEvent.button` for mouse binds.
+    #[serde(default)]
+    pub mouse_button: Option<u16>,
+    #[serde(default)]
+    pub super_key: bool,
+    #[serde(default)]
+    pub ctrl: bool,
+    #[serde(default)]
+    pub alt: bool,
+    #[serde(default)]
+    pub shift: bool,
+}
+
+/// The Hyprland representation of a captured combination.
+#[derive(Serialize, Debug)]
+pub struct RecordedKey {
+    pub modifiers: String,
+    pub key: String,
+    /// Existing bindings in the same submap that use this combination.
+    pub conflicts: Vec<Keybind>,
+}
+
+/// Returns the XKB name of a keysym, covering ASCII, common function keys and Unicode keysyms.
+fn keysym_name(keysym: u32) -> Option<String> {
+    if (0x20..=0x7e).contains(&keysym) {
+        return ASCII_KEYSYMS
+            .split_whitespace()
+            .nth((keysym - 0x20) as usize)
+            .map(str::to_string);
+    }
+    if (0xffbe..=0xffd5).contains(&keysym) {
+        return Some(format!("F{}", keysym - 0xffbe + 1));
+    }
+    if let Some((_, name)) = KEYSYMS.iter().find(|(sym, _)| *sym == keysym) {
+        return Some(name.to_string());
+    }
+    // Unicode keysyms encode the code point directly
+    if (0x0100_00a0..=0x0110_ffff).contains(&keysym) {
+        return Some(format!("U{:04X}", keysym - 0x0100_0000));
+    }
+    None
+}
+
+/// Returns the keysym of a character: ASCII keysyms match the character, others use
+/// the Unicode keysym range.
+fn char_keysym(c: char) -> u32 {
+    if (' '..='~').contains(&c) {
+        c as u32
+    } else {
+        0x0100_0000 + c as u32
+    }
+}
+
+/// Returns the character a keysym types, if it is a printable ASCII or Unicode keysym.
+fn keysym_char(keysym: u32) -> Option<char> {
+    if (0x20..=0x7e).contains(&keysym) {
+        char::from_u32(keysym)
+    } else if (0x0100_00a0..=0x0110_ffff).contains(&keysym) {
+        char::from_u32(keysym - 0x0100_0000)
+    } else {
+        None
+    }
+}
+
+/// Returns true for keys that only act as modifiers.
+fn is_modifier_key(name: &str) -> bool {
+    matches!(
+        name,
+        "Shift_L"
+            | "Shift_R"
+            | "Control_L"
+            | "Control_R"
+            | "Alt_L"
+            | "Alt_R"
+            | "Super_L"
+            | "Super_R"
+            | "Caps_Lock"
+    )
+}
+
+/// Maps a DOM mouse button to Hyprland's `mouse:NNN` key.
+fn mouse_key(button: u16) -> Result<String, String> {
+    let code = match button {
+        0 => 272, // BTN_LEFT
+        1 => 274, // BTN_MIDDLE
+        2 => 273, // BTN_RIGHT
+        3 => 275, // BTN_SIDE
+        4 => 276, // BTN_EXTRA
+        _ => return Err(format!("Unsupported mouse button {}", button)),
+    };
+    Ok(format!("mouse:{}", code))
+}
+
+/// Resolves the canonical Hyprland key token for a captured event.
+fn resolve_key(event: &CapturedKey) -> Result<String, String> {
+    if let Some(button) = event.mouse_button {
+        return mouse_key(button);
+    }
+
+    let physical = PHYSICAL_KEYS
+        .iter()
+        .find(|(code, _, _)| *code == event.code);
+
+    // The character `KeyboardEvent.key` reports follows the active layout, unlike the
+    // physical code, so it names the right key on AZERTY, QWERTZ and other layouts
+    let mut chars = event.key.chars();
+    let typed = match (chars.next(), chars.next()) {
+        (Some(c), None) if !c.is_control() => Some(char_keysym(c)),
+        _ => None,
+    };
+    let keysym = event.keysym.or(typed);
+    let from_keysym = keysym.and_then(keysym_name);
+
+    // With Shift held the keysym is the shifted symbol (e.g. `exclam`); Hyprland expects
+    // the unshifted key plus the SHIFT modifier, so prefer the physical key in that case.
+    let shifted_symbol = event.shift
+        && keysym
+            .and_then(keysym_char)
+            .is_some_and(|c| !c.is_alphabetic());
+
+    let name = match (from_keysym, physical) {
+        (Some(_), Some((_, name, _))) if shifted_symbol => name.to_string(),
+        (Some(name), _) => name,
+        (None, Some((_, name, _))) => name.to_string(),
+        (None, None) => match event.keycode {
+            Some(keycode) => format!("code:{}", keycode),
+            None => return Err(format!("Unrecognised key '{}'", event.key)),
+        },
+    };
+
+    if is_modifier_key(&name) {
+        return Err("Press a non-modifier key to complete the combination".to_string());
+    }
+
+    // Letters are written in upper case in Hyprland configs
+    if name.chars().count() == 1 {
+        Ok(name.to_uppercase())
+    } else {
+        Ok(name)
+    }
+}
+
+/// Returns the `code:NN` token for a captured key, usable when the keysym is layout dependent.
+fn keycode_token(event: &CapturedKey) -> Option<String> {
+    event
+        .keycode
+        .or_else(|| {
+            PHYSICAL_KEYS
+                .iter()
+                .find(|(code, _, _)| *code == event.code)
+                .map(|(_, _, evdev)| evdev + XKB_KEYCODE_OFFSET)
+        })
+        .map(|keycode| format!("code:{}", keycode))
+}
+
+/// Converts a captured key event into Hyprland modifiers and key name, and lists
+/// existing bindings in `submap` that already use the combination.
+#[tauri::command]
+pub fn resolve_captured_key(
+    event: CapturedKey,
+    submap: Option<String>,
+) -> Result<RecordedKey, String> {
+    let key = resolve_key(&event)?;
+    let keycode = keycode_token(&event);
+
+    let mut mods = Vec::new();
+    if event.super_key {
+        mods.push("SUPER");
+    }
+    if event.ctrl {
+        mods.push("CTRL");
+    }
+    if event.alt {
+        mods.push("ALT");
+    }
+    if event.shift {
+        mods.push("SHIFT");
+    }
+
+    let submap = submap.unwrap_or_default();
+    let conflicts = get_keybinds()?
+        .keybinds
+        .into_iter()
+        .filter(|kb| {
+            kb.submap == submap
+                && kb.normalized_modifiers == mods
+                && (kb.key.eq_ignore_ascii_case(&key) || Some(&kb.key) == keycode.as_ref())
+        })
+        .collect();
+
+    Ok(RecordedKey {
+        modifiers: mods.join(" "),
+        key,
+        conflicts,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key_event(code: &str, key: &str, shift: bool) -> CapturedKey {
+        CapturedKey {
+            keysym: None,
+            keycode: None,
+            code: code.to_string(),
+            key: key.to_string(),
+            mouse_button: None,
+            super_key: true,
+            ctrl: false,
+            alt: false,
+            shift,
+        }
+    }
+
+    #[test]
+    fn follows_the_active_layout() {
+        // AZERTY: the key in the QWERTY `Q` position types `a`
+        assert_eq!(resolve_key(&key_event("KeyQ", "a", false)).unwrap(), "A");
+        // QWERTZ: the key in the QWERTY `Y` position types `z`
+        assert_eq!(resolve_key(&key_event("KeyY", "z", false)).unwrap(), "Z");
+        assert_eq!(resolve_key(&key_event("KeyY", "Z", true)).unwrap(), "Z");
+        // AZERTY: the unshifted digit row types symbols
+        assert_eq!(
+            resolve_key(&key_event("Digit1", "&", false)).unwrap(),
+            "ampersand"
+        );
+        assert_eq!(
+            resolve_key(&key_event("KeyM", ",", false)).unwrap(),
+            "comma"
+        );
+    }
+
+    #[test]
+    fn names_non_ascii_characters_by_unicode_keysym() {
+        assert_eq!(
+            resolve_key(&key_event("Digit2", "é", false)).unwrap(),
+            "U00E9"
+        );
+        assert_eq!(
+            resolve_key(&key_event("Semicolon", "ö", false)).unwrap(),
+            "U00F6"
+        );
+    }
+
+    #[test]
+    fn uses_the_unshifted_key_for_shifted_symbols() {
+        assert_eq!(resolve_key(&key_event("Digit1", "!", true)).unwrap(), "1");
+        assert_eq!(
+            resolve_key(&key_event("Slash", "?", true)).unwrap(),
+            "slash"
+        );
+    }
+
+    #[test]
+    fn resolves_named_keys_and_mouse_buttons() {
+        assert_eq!(
+            resolve_key(&key_event("Enter", "Enter", false)).unwrap(),
+            "Return"
+        );
+        assert_eq!(
+            resolve_key(&key_event("Space", " ", false)).unwrap(),
+            "space"
+        );
+        assert_eq!(resolve_key(&key_event("F5", "F5", false)).unwrap(), "F5");
+        assert!(resolve_key(&key_event("ShiftLeft", "Shift", true)).is_err());
+
+        let mut event = key_event("", "", false);
+        event.mouse_button = Some(0);
+        assert_eq!(resolve_key(&event).unwrap(), "mouse:272");
+    }
+
+    #[test]
+    fn prefers_a_keysym_when_given() {
+        let mut event = key_event("KeyQ", "q", false);
+        event.keysym = Some(0x61);
+        assert_eq!(resolve_key(&event).unwrap(), "A");
+        assert_eq!(keycode_token(&event).as_deref(), Some("code:24"));
+    }
+}
@@ -1,8 +1,15 @@
 pub mod appearance;
+pub mod cheatsheet;
+pub mod config_edit;
+pub mod dispatchers;
 pub mod display;
 pub mod env;
 pub mod keybinds;
+pub mod keysyms;
 pub mod kitty;
+pub mod nightlight;
+pub mod services;
+pub mod session_env;
 pub mod startup;
 pub mod wallpaper;
 pub mod waybar;
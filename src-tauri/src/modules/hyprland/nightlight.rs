@@ -0,0 +1,536 @@
+use crate::modules::settings::{get_app_settings, write_app_settings};
+use chrono::{Datelike, Local, NaiveDate, NaiveDateTime, Timelike};
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+use tokio::process::{Child, Command};
+use tokio::sync::Mutex;
+
+/// How often the scheduler re-evaluates whether the filter should be active.
+const SCHEDULER_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How long a previewed temperature stays applied before the schedule takes over again.
+const PREVIEW_DURATION: Duration = Duration::from_secs(10);
+
+/// Lowest and highest color temperatures accepted by hyprsunset.
+const MIN_TEMPERATURE: u32 = 1000;
+const MAX_TEMPERATURE: u32 = 20000;
+
+/// Determines when the night light is switched on.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum NightLightSchedule {
+    /// Between the fixed `start` and `end` times.
+    Fixed,
+    /// Between sunset and sunrise computed from `latitude`/`longitude`.
+    Sun,
+}
+
+/// Persistent night light configuration, stored in `AppSettings`.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct NightLightSettings {
+    pub enabled: bool,
+    pub temperature: u32,
+    pub schedule: NightLightSchedule,
+    pub start: String, // "HH:MM", used by the fixed schedule
+    pub end: String,
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+impl Default for NightLightSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            temperature: 4000,
+            schedule: NightLightSchedule::Fixed,
+            start: "20:00".to_string(),
+            end: "07:00".to_string(),
+            latitude: 0.0,
+            longitude: 0.0,
+        }
+    }
+}
+
+/// Current night light state reported to the UI.
+#[derive(Debug, Serialize)]
+pub struct NightLightStatus {
+    pub active: bool,
+    pub temperature: Option<u32>,
+    pub next_start: String, // local "YYYY-MM-DD HH:MM" of the next switch-on, empty when none
+    pub next_end: String,   // same for the next switch-off
+}
+
+/// Manages the running hyprsunset process.
+pub struct NightLightState {
+    inner: Mutex<NightLightProcess>,
+}
+
+/// The hyprsunset child (if any) and the temperature it was started with.
+struct NightLightProcess {
+    child: Option<Child>,
+    temperature: Option<u32>,
+    preview_until: Option<Instant>,
+}
+
+impl NightLightState {
+    pub fn new() -> Self {
+        Self {
+            inner: Mutex::new(NightLightProcess {
+                child: None,
+                temperature: None,
+                preview_until: None,
+            }),
+        }
+    }
+}
+
+impl NightLightProcess {
+    /// Starts hyprsunset with the given temperature, replacing any running instance.
+    async fn apply(&mut self, temperature: u32) -> Result<(), String> {
+        if self.temperature == Some(temperature) && self.is_running() {
+            return Ok(());
+        }
+        self.stop().await;
+
+        let child = Command::new("hyprsunset")
+            .args(["-t", &temperature.to_string()])
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| format!("Failed to start hyprsunset: {}", e))?;
+
+        self.child = Some(child);
+        self.temperature = Some(temperature);
+        Ok(())
+    }
+
+    /// Stops the running hyprsunset instance, restoring the normal color temperature.
+    async fn stop(&mut self) {
+        if let Some(mut child) = self.child.take() {
+            let _ = child.kill().await;
+        }
+        self.temperature = None;
+    }
+
+    /// Returns true if the spawned hyprsunset process is still alive.
+    fn is_running(&mut self) -> bool {
+        match self.child.as_mut() {
+            Some(child) => matches!(child.try_wait(), Ok(None)),
+            None => false,
+        }
+    }
+}
+
+/// Parses an "HH:MM" string into minutes since midnight.
+fn parse_time(value: &str) -> Option<u32> {
+    let (h, m) = value.trim().split_once(':')?;
+    let h: u32 = h.parse().ok()?;
+    let m: u32 = m.parse().ok()?;
+    if h < 24 && m < 60 {
+        Some(h * 60 + m)
+    } else {
+        None
+    }
+}
+
+/// Sunrise and sunset for a given day.
+#[derive(Debug, PartialEq)]
+enum SunEvents {
+    /// Local sunrise and sunset, in minutes since midnight.
+    Times(u32, u32),
+    /// The sun does not set (polar day).
+    AlwaysUp,
+    /// The sun does not rise (polar night).
+    AlwaysDown,
+}
+
+/// Computes sunrise and sunset offline using the NOAA solar position approximation.
+fn sun_events(
+    date: NaiveDate,
+    latitude: f64,
+    longitude: f64,
+    utc_offset_minutes: i32,
+) -> SunEvents {
+    let day = date.ordinal() as f64;
+    let gamma = 2.0 * std::f64::consts::PI / 365.0 * (day - 1.0 + 0.5);
+
+    // Equation of time (minutes) and solar declination (radians)
+    let eqtime = 229.18
+        * (0.000075 + 0.001868 * gamma.cos()
+            - 0.032077 * gamma.sin()
+            - 0.014615 * (2.0 * gamma).cos()
+            - 0.040849 * (2.0 * gamma).sin());
+    let decl = 0.006918 - 0.399912 * gamma.cos() + 0.070257 * gamma.sin()
+        - 0.006758 * (2.0 * gamma).cos()
+        + 0.000907 * (2.0 * gamma).sin()
+        - 0.002697 * (3.0 * gamma).cos()
+        + 0.00148 * (3.0 * gamma).sin();
+
+    let lat = latitude.to_radians();
+    let cos_ha = 90.833_f64.to_radians().cos() / (lat.cos() * decl.cos()) - lat.tan() * decl.tan();
+
+    if cos_ha > 1.0 {
+        return SunEvents::AlwaysDown;
+    }
+    if cos_ha < -1.0 {
+        return SunEvents::AlwaysUp;
+    }
+
+    let ha = cos_ha.acos().to_degrees();
+    let to_local = |utc_minutes: f64| -> u32 {
+        let local = utc_minutes.round() as i32 + utc_offset_minutes;
+        local.rem_euclid(24 * 60) as u32
+    };
+
+    let sunrise = 720.0 - 4.0 * (longitude + ha) - eqtime;
+    let sunset = 720.0 - 4.0 * (longitude - ha) - eqtime;
+
+    SunEvents::Times(to_local(sunrise), to_local(sunset))
+}
+
+/// Returns true if `now` falls inside the window from `start` to `end`, wrapping past midnight.
+fn in_window(now: u32, start: u32, end: u32) -> bool {
+    if start <= end {
+        now >= start && now < end
+    } else {
+        now >= start || now < end
+    }
+}
+
+/// Resolves the night window of a day as (start, end) minutes since midnight.
+/// None when the schedule is invalid or the sun neither sets nor rises that day.
+fn night_window(
+    settings: &NightLightSettings,
+    date: NaiveDate,
+    utc_offset_minutes: i32,
+) -> Option<(u32, u32)> {
+    match settings.schedule {
+        NightLightSchedule::Fixed => {
+            Some((parse_time(&settings.start)?, parse_time(&settings.end)?))
+        }
+        NightLightSchedule::Sun => match sun_events(
+            date,
+            settings.latitude,
+            settings.longitude,
+            utc_offset_minutes,
+        ) {
+            SunEvents::Times(sunrise, sunset) => Some((sunset, sunrise)),
+            SunEvents::AlwaysUp | SunEvents::AlwaysDown => None,
+        },
+    }
+}
+
+/// Returns true if the schedule puts `now` inside the night window.
+fn is_night(settings: &NightLightSettings, now: NaiveDateTime, utc_offset_minutes: i32) -> bool {
+    let minutes = now.hour() * 60 + now.minute();
+    match night_window(settings, now.date(), utc_offset_minutes) {
+        Some((start, end)) => in_window(minutes, start, end),
+        // Polar night keeps the filter on all day
+        None => {
+            settings.schedule == NightLightSchedule::Sun
+                && sun_events(
+                    now.date(),
+                    settings.latitude,
+                    settings.longitude,
+                    utc_offset_minutes,
+                ) == SunEvents::AlwaysDown
+        }
+    }
+}
+
+/// Finds the first window start (or end, when `start` is false) after `now`,
+/// looking up to a year ahead to get past polar days and nights.
+fn next_transition(
+    settings: &NightLightSettings,
+    now: NaiveDateTime,
+    utc_offset_minutes: i32,
+    start: bool,
+) -> Option<NaiveDateTime> {
+    (0..=366).find_map(|days| {
+        let date = now.date() + chrono::Duration::days(days);
+        let (on, off) = night_window(settings, date, utc_offset_minutes)?;
+        let minutes = if start { on } else { off };
+        let at = date.and_hms_opt(minutes / 60, minutes % 60, 0)?;
+        (at > now).then_some(at)
+    })
+}
+
+/// Validates a color temperature against the range hyprsunset accepts.
+fn validate_temperature(temperature: u32) -> Result<(), String> {
+    if (MIN_TEMPERATURE..=MAX_TEMPERATURE).contains(&temperature) {
+        Ok(())
+    } else {
+        Err(format!(
+            "Temperature must be between {}K and {}K",
+            MIN_TEMPERATURE, MAX_TEMPERATURE
+        ))
+    }
+}
+
+/// Applies or clears the filter according to the current settings and time.
+async fn reconcile(state: &NightLightState) {
+    let settings = get_app_settings().night_light;
+    let mut process = state.inner.lock().await;
+
+    // Leave a running preview alone until it expires
+    if let Some(until) = process.preview_until {
+        if Instant::now() < until {
+            return;
+        }
+        process.preview_until = None;
+    }
+
+    let now = Local::now();
+    let offset = now.offset().local_minus_utc() / 60;
+    if settings.enabled && is_night(&settings, now.naive_local(), offset) {
+        let _ = process.apply(settings.temperature).await;
+    } else {
+        process.stop().await;
+    }
+}
+
+/// Spawns the background task that keeps hyprsunset in sync with the schedule.
+pub fn start_night_light_scheduler(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            reconcile(app.state::<NightLightState>().inner()).await;
+            tokio::time::sleep(SCHEDULER_INTERVAL).await;
+        }
+    });
+}
+
+/// Returns whether the night light is currently applied and when it next switches on and off.
+#[tauri::command]
+pub async fn get_night_light_status(
+    state: tauri::State<'_, NightLightState>,
+) -> Result<NightLightStatus, String> {
+    let settings = get_app_settings().night_light;
+    let now = Local::now();
+    let offset = now.offset().local_minus_utc() / 60;
+    let next = |start: bool| {
+        next_transition(&settings, now.naive_local(), offset, start)
+            .map(|at| at.format("%Y-%m-%d %H:%M").to_string())
+            .unwrap_or_default()
+    };
+    let mut process = state.inner.lock().await;
+
+    let active = process.is_running();
+    Ok(NightLightStatus {
+        active,
+        temperature: if active { process.temperature } else { None },
+        next_start: next(true),
+        next_end: next(false),
+    })
+}
+
+/// Saves the night light settings, enables the schedule and applies it immediately.
+#[tauri::command]
+pub async fn enable_night_light(
+    state: tauri::State<'_, NightLightState>,
+    settings: NightLightSettings,
+) -> Result<(), String> {
+    validate_temperature(settings.temperature)?;
+    if settings.schedule == NightLightSchedule::Fixed
+        && (parse_time(&settings.start).is_none() || parse_time(&settings.end).is_none())
+    {
+        return Err("Start and end times must use the HH:MM format".to_string());
+    }
+    if settings.schedule == NightLightSchedule::Sun
+        && (!(-90.0..=90.0).contains(&settings.latitude)
+            || !(-180.0..=180.0).contains(&settings.longitude))
+    {
+        return Err("Latitude or longitude is out of range".to_string());
+    }
+
+    let mut app_settings = get_app_settings();
+    app_settings.night_light = NightLightSettings {
+        enabled: true,
+        ..settings
+    };
+    write_app_settings(&app_settings)?;
+
+    state.inner.lock().await.preview_until = None;
+    reconcile(state.inner()).await;
+    Ok(())
+}
+
+/// Disables the night light schedule and restores the normal color temperature.
+#[tauri::command]
+pub async fn disable_night_light(state: tauri::State<'_, NightLightState>) -> Result<(), String> {
+    let mut app_settings = get_app_settings();
+    app_settings.night_light.enabled = false;
+    write_app_settings(&app_settings)?;
+
+    let mut process = state.inner.lock().await;
+    process.preview_until = None;
+    process.stop().await;
+    Ok(())
+}
+
+/// Temporarily applies a color temperature so the user can preview it.
+#[tauri::command]
+pub async fn preview_night_light(
+    app: AppHandle,
+    state: tauri::State<'_, NightLightState>,
+    temperature: u32,
+) -> Result<(), String> {
+    validate_temperature(temperature)?;
+
+    let mut process = state.inner.lock().await;
+    process.apply(temperature).await?;
+    process.preview_until = Some(Instant::now() + PREVIEW_DURATION);
+
+    // Hand control back to the schedule once the preview expires
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(PREVIEW_DURATION).await;
+        reconcile(app.state::<NightLightState>().inner()).await;
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(date: &str, time: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(&format!("{} {}", date, time), "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap()
+    }
+
+    fn fixed(start: &str, end: &str) -> NightLightSettings {
+        NightLightSettings {
+            start: start.to_string(),
+            end: end.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn sun(latitude: f64, longitude: f64) -> NightLightSettings {
+        NightLightSettings {
+            schedule: NightLightSchedule::Sun,
+            latitude,
+            longitude,
+            ..Default::default()
+        }
+    }
+
+    /// Asserts that sunrise and sunset are within three minutes of the expected times.
+    fn assert_sun_times(events: SunEvents, sunrise: &str, sunset: &str) {
+        let SunEvents::Times(rise, set) = events else {
+            panic!("expected sun times, got {:?}", events);
+        };
+        for (actual, expected) in [(rise, sunrise), (set, sunset)] {
+            let expected = parse_time(expected).unwrap();
+            assert!(
+                actual.abs_diff(expected) <= 3,
+                "{} minutes is not close to {}",
+                actual,
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn parses_times() {
+        assert_eq!(parse_time("00:00"), Some(0));
+        assert_eq!(parse_time(" 7:05 "), Some(425));
+        assert_eq!(parse_time("23:59"), Some(1439));
+        for invalid in ["24:00", "12:60", "12", "ab:cd", "", "-1:00"] {
+            assert_eq!(parse_time(invalid), None, "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn checks_windows() {
+        // Same-day window
+        assert!(in_window(12 * 60, 9 * 60, 17 * 60));
+        assert!(!in_window(17 * 60, 9 * 60, 17 * 60));
+        assert!(!in_window(8 * 60, 9 * 60, 17 * 60));
+        // Window crossing midnight
+        assert!(in_window(23 * 60, 20 * 60, 7 * 60));
+        assert!(in_window(0, 20 * 60, 7 * 60));
+        assert!(in_window(6 * 60 + 59, 20 * 60, 7 * 60));
+        assert!(!in_window(7 * 60, 20 * 60, 7 * 60));
+        assert!(!in_window(12 * 60, 20 * 60, 7 * 60));
+    }
+
+    #[test]
+    fn computes_sunrise_and_sunset() {
+        // London at midsummer, in BST
+        assert_sun_times(
+            sun_events(date("2024-06-21"), 51.5074, -0.1278, 60),
+            "04:43",
+            "21:21",
+        );
+        // Null Island at the March equinox
+        assert_sun_times(
+            sun_events(date("2024-03-20"), 0.0, 0.0, 0),
+            "06:04",
+            "18:11",
+        );
+        // Sydney in midwinter, AEST
+        assert_sun_times(
+            sun_events(date("2024-06-21"), -33.8688, 151.2093, 600),
+            "07:00",
+            "16:54",
+        );
+    }
+
+    #[test]
+    fn detects_polar_day_and_night() {
+        assert_eq!(
+            sun_events(date("2024-06-21"), 69.65, 18.96, 120),
+            SunEvents::AlwaysUp
+        );
+        assert_eq!(
+            sun_events(date("2024-12-21"), 69.65, 18.96, 60),
+            SunEvents::AlwaysDown
+        );
+        assert!(is_night(&sun(69.65, 18.96), at("2024-12-21", "12:00"), 60));
+        assert!(!is_night(
+            &sun(69.65, 18.96),
+            at("2024-06-21", "00:00"),
+            120
+        ));
+    }
+
+    #[test]
+    fn follows_fixed_schedules_past_midnight() {
+        let settings = fixed("20:00", "07:00");
+        assert!(is_night(&settings, at("2024-05-01", "22:30"), 0));
+        assert!(is_night(&settings, at("2024-05-01", "03:00"), 0));
+        assert!(!is_night(&settings, at("2024-05-01", "12:00"), 0));
+        assert!(!is_night(
+            &fixed("20:00", "bad"),
+            at("2024-05-01", "22:30"),
+            0
+        ));
+    }
+
+    #[test]
+    fn reports_the_next_transitions() {
+        let settings = fixed("20:00", "07:00");
+        let next = |now: NaiveDateTime, start: bool| next_transition(&settings, now, 0, start);
+
+        // In the afternoon the window starts tonight and ends tomorrow morning
+        let now = at("2024-05-01", "15:00");
+        assert_eq!(next(now, true), Some(at("2024-05-01", "20:00")));
+        assert_eq!(next(now, false), Some(at("2024-05-02", "07:00")));
+
+        // After tonight's start has passed, the next start is tomorrow
+        let now = at("2024-05-01", "23:00");
+        assert_eq!(next(now, true), Some(at("2024-05-02", "20:00")));
+        assert_eq!(next(now, false), Some(at("2024-05-02", "07:00")));
+    }
+
+    #[test]
+    fn skips_polar_days_when_looking_ahead() {
+        let next =
+            next_transition(&sun(69.65, 18.96), at("2024-06-21", "12:00"), 120, true).unwrap();
+        assert!(next.date() > date("2024-07-15"));
+        assert!(next.date() < date("2024-08-15"));
+    }
+}
@@ -0,0 +1,506 @@
+use crate::modules::hyprland::startup::{
+    command_executable, get_startup_commands, set_startup_entry_enabled, StartupKind,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tokio::process::Command;
+
+/// Target the generated units are bound to.
+const SESSION_TARGET: &str = "graphical-session.target";
+
+/// Journal lines returned when no count is given.
+const DEFAULT_LOG_LINES: u32 = 50;
+
+/// A systemd user service with its load, activation and enablement state.
+#[derive(Debug, Serialize)]
+pub struct UserUnit {
+    pub name: String,
+    pub description: String,
+    pub load_state: String,      // e.g. "loaded", "not-found"
+    pub active_state: String,    // e.g. "active", "inactive", "failed"
+    pub sub_state: String,       // e.g. "running", "dead", "exited"
+    pub unit_file_state: String, // e.g. "enabled", "disabled", "static"
+    pub user_defined: bool,      // the unit file lives in ~/.config/systemd/user
+}
+
+/// Operations that can be applied to a user unit.
+#[derive(Debug, Deserialize, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum UnitAction {
+    Start,
+    Stop,
+    Restart,
+    Enable,
+    Disable,
+}
+
+impl UnitAction {
+    /// Returns the matching `systemctl` verb.
+    fn verb(self) -> &'static str {
+        match self {
+            UnitAction::Start => "start",
+            UnitAction::Stop => "stop",
+            UnitAction::Restart => "restart",
+            UnitAction::Enable => "enable",
+            UnitAction::Disable => "disable",
+        }
+    }
+}
+
+/// Retrieves the user's home directory.
+fn get_home_dir() -> String {
+    env::var("HOME").unwrap_or_else(|_| "/home/i4104".to_string())
+}
+
+/// Returns the directory for user-defined units.
+fn get_user_unit_dir() -> PathBuf {
+    Path::new(&get_home_dir()).join(".config/systemd/user")
+}
+
+/// Runs `systemctl --user` with the given arguments and returns its stdout.
+async fn systemctl(args: &[&str]) -> Result<String, String> {
+    run_systemctl("systemctl", args).await
+}
+
+/// Runs the given `systemctl` program in user mode, so tests can substitute a stub.
+async fn run_systemctl(program: &str, args: &[&str]) -> Result<String, String> {
+    let output = Command::new(program)
+        .arg("--user")
+        .args(args)
+        .output()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
+
+/// Checks that a name is a plain service unit name, so it cannot be read as an option or path.
+fn validate_unit_name(name: &str) -> Result<(), String> {
+    let valid = name.ends_with(".service")
+        && !name.starts_with('-')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_.@:\\".contains(c));
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("Invalid unit name '{}'", name))
+    }
+}
+
+/// Lists user services, including installed units that are not currently loaded.
+#[tauri::command]
+pub async fn get_user_units() -> Result<Vec<UserUnit>, String> {
+    let loaded = systemctl(&[
+        "list-units",
+        "--type=service",
+        "--all",
+        "--plain",
+        "--no-legend",
+        "--no-pager",
+    ])
+    .await?;
+    let files = systemctl(&[
+        "list-unit-files",
+        "--type=service",
+        "--plain",
+        "--no-legend",
+        "--no-pager",
+    ])
+    .await?;
+    let user_dir = get_user_unit_dir();
+
+    let mut units: BTreeMap<String, UserUnit> = BTreeMap::new();
+    let new_unit = |name: &str| UserUnit {
+        name: name.to_string(),
+        description: String::new(),
+        load_state: "not-loaded".to_string(),
+        active_state: "inactive".to_string(),
+        sub_state: "dead".to_string(),
+        unit_file_state: String::new(),
+        user_defined: user_dir.join(name).exists(),
+    };
+
+    // Format: UNIT LOAD ACTIVE SUB DESCRIPTION...
+    for line in loaded.lines() {
+        let mut parts = line.split_whitespace();
+        let (Some(name), Some(load), Some(active), Some(sub)) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            continue;
+        };
+        let unit = units
+            .entry(name.to_string())
+            .or_insert_with(|| new_unit(name));
+        unit.load_state = load.to_string();
+        unit.active_state = active.to_string();
+        unit.sub_state = sub.to_string();
+        unit.description = parts.collect::<Vec<_>>().join(" ");
+    }
+
+    // Format: UNIT STATE PRESET
+    for line in files.lines() {
+        let mut parts = line.split_whitespace();
+        let (Some(name), Some(state)) = (parts.next(), parts.next()) else {
+            continue;
+        };
+        // Templates such as foo@.service cannot run on their own
+        if name.ends_with("@.service") {
+            continue;
+        }
+        units
+            .entry(name.to_string())
+            .or_insert_with(|| new_unit(name))
+            .unit_file_state = state.to_string();
+    }
+
+    Ok(units.into_values().collect())
+}
+
+/// Returns the most recent journal lines of a user unit.
+#[tauri::command]
+pub async fn get_user_unit_logs(name: String, lines: Option<u32>) -> Result<Vec<String>, String> {
+    validate_unit_name(&name)?;
+    let count = lines.unwrap_or(DEFAULT_LOG_LINES).to_string();
+
+    let output = Command::new("journalctl")
+        .args([
+            "--user-unit",
+            &name,
+            "-n",
+            &count,
+            "--no-pager",
+            "-o",
+            "short-iso",
+        ])
+        .output()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter(|l| !l.starts_with("-- "))
+        .map(String::from)
+        .collect())
+}
+
+/// Starts, stops, restarts, enables or disables a user unit.
+#[tauri::command]
+pub async fn manage_user_unit(name: String, action: UnitAction) -> Result<(), String> {
+    validate_unit_name(&name)?;
+    systemctl(&[action.verb(), &name]).await.map(|_| ())
+}
+
+/// Returns true if the command is a single simple command the shell can `exec`,
+/// as opposed to a list, pipeline, subshell or compound command.
+fn is_simple_command(command: &str) -> bool {
+    const COMPOUND_WORDS: [&str; 7] = ["if", "for", "while", "until", "case", "{", "!"];
+
+    !command.chars().any(|c| "|&;()\n".contains(c))
+        && command
+            .split_whitespace()
+            .next()
+            .is_some_and(|word| !COMPOUND_WORDS.contains(&word))
+}
+
+/// Builds the `ExecStart=` value for a command.
+/// A trailing `&` is dropped: a backgrounded command would leave the service without a
+/// main process and systemd would stop it. Shell syntax runs through `sh -c`, which `exec`s
+/// single commands so they become the main process; lists such as `sleep 1 && swww init`
+/// run in a shell that waits for them. `$` and `%` are escaped so systemd leaves them
+/// to the shell.
+fn exec_start(command: &str) -> String {
+    let mut command = command.trim();
+    if let Some(rest) = command.strip_suffix('&') {
+        if !rest.ends_with('&') {
+            command = rest.trim_end();
+        }
+    }
+    let needs_shell = command.chars().any(|c| "|&;<>()$`*?~\"'\\\n".contains(c));
+
+    let exec = if !needs_shell {
+        command.to_string()
+    } else {
+        let script = if is_simple_command(command) {
+            format!("exec {}", command)
+        } else {
+            command.to_string()
+        };
+        format!(
+            "/bin/sh -c \"{}\"",
+            script.replace('\\', "\\\\").replace('"', "\\\"")
+        )
+    };
+    exec.replace('%', "%%").replace('$', "$$")
+}
+
+/// Derives a unit name such as `waybar.service` from a command.
+fn unit_name_for(command: &str) -> Option<String> {
+    let name: String = command_executable(command)?
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    let name = name.trim_matches('-');
+    (!name.is_empty()).then(|| format!("{}.service", name))
+}
+
+/// Renders a service unit that runs a command as part of the graphical session.
+fn render_unit(description: &str, command: &str) -> String {
+    format!(
+        "# Generated by Settings from a Hyprland exec-once entry\n\
+         [Unit]\n\
+         Description={description}\n\
+         PartOf={target}\n\
+         After={target}\n\
+         \n\
+         [Service]\n\
+         ExecStart={exec}\n\
+         Restart=on-failure\n\
+         Slice=app-graphical.slice\n\
+         \n\
+         [Install]\n\
+         WantedBy={target}\n",
+        description = description.replace('\n', " "),
+        target = SESSION_TARGET,
+        exec = exec_start(command),
+    )
+}
+
+/// Loads, enables and starts a freshly written unit, then runs `finish`.
+/// If any step fails the unit is disabled again, its file removed and systemd reloaded,
+/// so a failed conversion leaves nothing behind.
+async fn install_unit(
+    program: &str,
+    path: &Path,
+    name: &str,
+    finish: impl FnOnce() -> Result<(), String>,
+) -> Result<(), String> {
+    let mut enabling = false;
+    let result = async {
+        run_systemctl(program, &["daemon-reload"]).await?;
+        enabling = true;
+        run_systemctl(program, &["enable", "--now", name]).await?;
+        finish()
+    }
+    .await;
+
+    if result.is_err() {
+        // `enable --now` can fail after enabling, e.g. when the unit does not start
+        if enabling {
+            let _ = run_systemctl(program, &["disable", "--now", name]).await;
+        }
+        let _ = fs::remove_file(path);
+        let _ = run_systemctl(program, &["daemon-reload"]).await;
+    }
+    result
+}
+
+/// Turns an `exec-once` startup entry into a user unit bound to the graphical session.
+/// The unit is enabled and started, and the entry is disabled so the command does not run twice.
+/// Returns the name of the generated unit.
+#[tauri::command]
+pub async fn convert_startup_to_unit(
+    id: String,
+    unit_name: Option<String>,
+) -> Result<String, String> {
+    let entry = get_startup_commands()?
+        .into_iter()
+        .find(|e| e.id == id)
+        .ok_or_else(|| format!("Startup entry '{}' not found", id))?;
+    if entry.kind != StartupKind::ExecOnce {
+        return Err("Only exec-once entries can be converted to a service".to_string());
+    }
+
+    let name = match unit_name
+        .map(|n| n.trim().to_string())
+        .filter(|n| !n.is_empty())
+    {
+        Some(name) if name.ends_with(".service") => name,
+        Some(name) => format!("{}.service", name),
+        None => unit_name_for(&entry.command)
+            .ok_or_else(|| "Could not derive a unit name from the command".to_string())?,
+    };
+    validate_unit_name(&name)?;
+
+    let path = get_user_unit_dir().join(&name);
+    if path.exists() {
+        return Err(format!("Unit '{}' already exists", name));
+    }
+
+    let description = entry.label.clone().unwrap_or_else(|| entry.command.clone());
+    fs::create_dir_all(get_user_unit_dir()).map_err(|e| e.to_string())?;
+    fs::write(&path, render_unit(&description, &entry.command))
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+
+    install_unit("systemctl", &path, &name, || {
+        set_startup_entry_enabled(entry.id, false)
+    })
+    .await?;
+
+    Ok(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn passes_plain_commands_through() {
+        assert_eq!(exec_start("waybar"), "waybar");
+        assert_eq!(
+            exec_start("  nm-applet --indicator "),
+            "nm-applet --indicator"
+        );
+        assert_eq!(exec_start("date +%H"), "date +%%H");
+    }
+
+    #[test]
+    fn execs_single_shell_commands() {
+        assert_eq!(
+            exec_start("swaybg -i ~/wall.png"),
+            "/bin/sh -c \"exec swaybg -i ~/wall.png\""
+        );
+        assert_eq!(
+            exec_start("echo \"a b\" > /tmp/log"),
+            "/bin/sh -c \"exec echo \\\"a b\\\" > /tmp/log\""
+        );
+    }
+
+    #[test]
+    fn keeps_the_shell_for_lists() {
+        assert_eq!(
+            exec_start("sleep 1 && swww init"),
+            "/bin/sh -c \"sleep 1 && swww init\""
+        );
+        assert_eq!(
+            exec_start("wl-paste --watch cliphist store | cat"),
+            "/bin/sh -c \"wl-paste --watch cliphist store | cat\""
+        );
+        assert_eq!(
+            exec_start("if true; then waybar; fi"),
+            "/bin/sh -c \"if true; then waybar; fi\""
+        );
+    }
+
+    #[test]
+    fn drops_a_trailing_background_operator() {
+        assert_eq!(exec_start("waybar &"), "waybar");
+        assert_eq!(exec_start("hyprpaper&"), "hyprpaper");
+        assert_eq!(
+            exec_start("swww-daemon & swww img a.png &"),
+            "/bin/sh -c \"swww-daemon & swww img a.png\""
+        );
+    }
+
+    #[test]
+    fn escapes_dollar_signs_for_systemd() {
+        assert_eq!(
+            exec_start("kitty --directory $HOME"),
+            "/bin/sh -c \"exec kitty --directory $$HOME\""
+        );
+    }
+
+    /// Writes a `systemctl` stub that logs its arguments and fails for `fail_on`.
+    fn stub_systemctl(dir: &Path, fail_on: &str) -> PathBuf {
+        use std::os::unix::fs::PermissionsExt;
+
+        let stub = dir.join("systemctl");
+        fs::write(
+            &stub,
+            format!(
+                "#!/bin/sh\n\
+                 echo \"$*\" >> '{log}'\n\
+                 case \"$2\" in {fail_on}) echo 'stub failure' >&2; exit 1;; esac\n",
+                log = dir.join("calls.log").display(),
+                fail_on = fail_on,
+            ),
+        )
+        .unwrap();
+        fs::set_permissions(&stub, fs::Permissions::from_mode(0o755)).unwrap();
+        stub
+    }
+
+    /// Creates a scratch directory holding a unit file, and returns both paths.
+    fn scratch_unit(name: &str) -> (PathBuf, PathBuf) {
+        let dir = env::temp_dir().join(format!("services-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let unit = dir.join("waybar.service");
+        fs::write(&unit, render_unit("Waybar", "waybar")).unwrap();
+        (dir, unit)
+    }
+
+    fn calls(dir: &Path) -> Vec<String> {
+        fs::read_to_string(dir.join("calls.log"))
+            .unwrap_or_default()
+            .lines()
+            .map(String::from)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn rolls_back_when_the_entry_cannot_be_disabled() {
+        let (dir, unit) = scratch_unit("finish");
+        let stub = stub_systemctl(&dir, "none");
+
+        let result = install_unit(stub.to_str().unwrap(), &unit, "waybar.service", || {
+            Err("exec.conf is read-only".to_string())
+        })
+        .await;
+
+        assert_eq!(result, Err("exec.conf is read-only".to_string()));
+        assert!(!unit.exists());
+        assert_eq!(
+            calls(&dir),
+            [
+                "--user daemon-reload",
+                "--user enable --now waybar.service",
+                "--user disable --now waybar.service",
+                "--user daemon-reload",
+            ]
+        );
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn rolls_back_when_enabling_fails() {
+        let (dir, unit) = scratch_unit("enable");
+        let stub = stub_systemctl(&dir, "enable");
+
+        let result = install_unit(stub.to_str().unwrap(), &unit, "waybar.service", || Ok(())).await;
+
+        assert_eq!(result, Err("stub failure".to_string()));
+        assert!(!unit.exists());
+        assert!(calls(&dir).contains(&"--user disable --now waybar.service".to_string()));
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn keeps_the_unit_on_success() {
+        let (dir, unit) = scratch_unit("success");
+        let stub = stub_systemctl(&dir, "none");
+
+        let result = install_unit(stub.to_str().unwrap(), &unit, "waybar.service", || Ok(())).await;
+
+        assert_eq!(result, Ok(()));
+        assert!(unit.exists());
+        assert_eq!(calls(&dir).len(), 2);
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
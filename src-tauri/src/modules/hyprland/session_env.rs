@@ -0,0 +1,437 @@
+use crate::modules::hyprland::config_edit::{read_lines, write_lines};
+use crate::modules::hyprland::env::{get_env_vars, EnvVar};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Markers delimiting the block this app manages inside the uwsm env file.
+const UWSM_BLOCK_START: &str = "# >>> i4archsettings env >>>";
+const UWSM_BLOCK_END: &str = "# <<< i4archsettings env <<<";
+
+/// File written to `~/.config/environment.d`.
+const ENVIRONMENT_D_FILE: &str = "90-i4archsettings.conf";
+
+/// A variable from `env.conf` compared with the environment actually in effect.
+#[derive(Debug, Serialize)]
+pub struct EffectiveEnvVar {
+    pub key: String,
+    pub configured: String,
+    pub hyprland: Option<String>,
+    pub systemd: Option<String>,
+    pub uwsm: Option<String>,
+    pub mismatch: bool,
+}
+
+/// The comparison for every variable, and whether a Hyprland process could be read.
+/// Without one, the `hyprland` column is empty and not counted as a mismatch.
+#[derive(Debug, Serialize)]
+pub struct EffectiveEnvReport {
+    pub hyprland_available: bool,
+    pub vars: Vec<EffectiveEnvVar>,
+}
+
+/// Where variables can be synced so user services and uwsm-launched apps see them.
+#[derive(Debug, Deserialize, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum EnvSyncTarget {
+    /// `~/.config/environment.d/*.conf`, read by the systemd user manager.
+    EnvironmentD,
+    /// `~/.config/uwsm/env`, sourced by uwsm before starting the session.
+    Uwsm,
+}
+
+/// Retrieves the user's home directory.
+fn get_home_dir() -> String {
+    env::var("HOME").unwrap_or_else(|_| "/home/i4104".to_string())
+}
+
+/// Returns the path of the uwsm env file.
+fn get_uwsm_env_path() -> PathBuf {
+    Path::new(&get_home_dir()).join(".config/uwsm/env")
+}
+
+/// Finds the PID of the running Hyprland compositor by scanning `/proc`.
+fn find_hyprland_pid() -> Option<u32> {
+    fs::read_dir("/proc").ok()?.flatten().find_map(|entry| {
+        let pid: u32 = entry.file_name().to_str()?.parse().ok()?;
+        // `comm` is truncated to 15 characters, so Nix's `.Hyprland-wrapped` shows up cut short
+        let comm = fs::read_to_string(entry.path().join("comm")).ok()?;
+        matches!(comm.trim(), "Hyprland" | ".Hyprland-wrapp").then_some(pid)
+    })
+}
+
+/// Reads the environment of the running Hyprland process.
+/// Returns `None` when no Hyprland process is found or its environment cannot be read.
+fn read_hyprland_env() -> Option<HashMap<String, String>> {
+    let raw = fs::read(format!("/proc/{}/environ", find_hyprland_pid()?)).ok()?;
+    Some(
+        raw.split(|b| *b == 0)
+            .filter_map(|entry| {
+                let entry = String::from_utf8_lossy(entry);
+                let (key, value) = entry.split_once('=')?;
+                Some((key.to_string(), value.to_string()))
+            })
+            .collect(),
+    )
+}
+
+/// Removes shell quoting from a value (`'x'`, `"x"` or systemd's `$'x'`).
+fn unquote(value: &str) -> String {
+    let value = value.trim();
+    let inner = value
+        .strip_prefix("$'")
+        .and_then(|v| v.strip_suffix('\''))
+        .or_else(|| value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')))
+        .or_else(|| value.strip_prefix('"').and_then(|v| v.strip_suffix('"')));
+
+    match inner {
+        Some(inner) => inner.replace("\\'", "'").replace("\\\"", "\""),
+        None => value.to_string(),
+    }
+}
+
+/// Reads the systemd user manager's environment.
+fn read_systemd_env() -> HashMap<String, String> {
+    Command::new("systemctl")
+        .args(["--user", "show-environment"])
+        .output()
+        .map(|o| {
+            String::from_utf8_lossy(&o.stdout)
+                .lines()
+                .filter_map(|line| {
+                    let (key, value) = line.split_once('=')?;
+                    Some((key.to_string(), unquote(value)))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Reads `export KEY=VALUE` lines from the uwsm env file.
+fn read_uwsm_env() -> Result<HashMap<String, String>, String> {
+    Ok(read_lines(&get_uwsm_env_path())?
+        .iter()
+        .filter_map(|line| {
+            let line = line.trim();
+            let assignment = line.strip_prefix("export ")?;
+            let (key, value) = assignment.split_once('=')?;
+            Some((key.trim().to_string(), unquote(value)))
+        })
+        .collect())
+}
+
+/// Compares configured variables with the environments that were read.
+/// A value missing from a readable Hyprland process, or differing anywhere it is set,
+/// is a mismatch.
+fn compare_env(
+    vars: Vec<EnvVar>,
+    hyprland: Option<&HashMap<String, String>>,
+    systemd: &HashMap<String, String>,
+    uwsm: &HashMap<String, String>,
+) -> Vec<EffectiveEnvVar> {
+    vars.into_iter()
+        .map(|var| {
+            let hyprland_value = hyprland.and_then(|env| env.get(&var.key).cloned());
+            let systemd = systemd.get(&var.key).cloned();
+            let uwsm = uwsm.get(&var.key).cloned();
+
+            let differs = |v: &Option<String>| v.as_ref().is_some_and(|v| *v != var.value);
+            let mismatch = (hyprland.is_some() && hyprland_value.is_none())
+                || differs(&hyprland_value)
+                || differs(&systemd)
+                || differs(&uwsm);
+
+            EffectiveEnvVar {
+                key: var.key,
+                configured: var.value,
+                hyprland: hyprland_value,
+                systemd,
+                uwsm,
+                mismatch,
+            }
+        })
+        .collect()
+}
+
+/// Compares `env.conf` with the Hyprland process, systemd user manager and uwsm env file.
+#[tauri::command]
+pub fn get_effective_env() -> Result<EffectiveEnvReport, String> {
+    let hyprland = read_hyprland_env();
+    let systemd = read_systemd_env();
+    let uwsm = read_uwsm_env()?;
+
+    Ok(EffectiveEnvReport {
+        hyprland_available: hyprland.is_some(),
+        vars: compare_env(get_env_vars()?, hyprland.as_ref(), &systemd, &uwsm),
+    })
+}
+
+/// Quotes a value for a POSIX shell.
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+/// Formats a variable as an `environment.d` assignment. Values with spaces, `$`,
+/// quotes or backslashes are quoted so systemd neither splits nor expands them.
+fn environment_d_line(var: &EnvVar) -> String {
+    let needs_quoting = var
+        .value
+        .contains(|c: char| c.is_whitespace() || matches!(c, '$' | '\'' | '"' | '\\'));
+    if needs_quoting {
+        format!("{}={}", var.key, shell_quote(&var.value))
+    } else {
+        format!("{}={}", var.key, var.value)
+    }
+}
+
+/// Returns the keys assigned in a previous `environment.d` file that are no longer synced.
+fn dropped_keys(previous: &[String], vars: &[EnvVar]) -> Vec<String> {
+    previous
+        .iter()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| line.split_once('=').map(|(key, _)| key.trim().to_string()))
+        .filter(|key| !vars.iter().any(|v| v.key == *key))
+        .collect()
+}
+
+/// Runs `systemctl --user` with the given action and arguments, if there are any.
+fn systemctl_user_env(action: &str, args: &[String]) -> Result<(), String> {
+    if args.is_empty() {
+        return Ok(());
+    }
+    let output = Command::new("systemctl")
+        .args(["--user", action])
+        .args(args)
+        .output()
+        .map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).to_string());
+    }
+    Ok(())
+}
+
+/// Writes the variables to `environment.d` and pushes them into the running user manager.
+/// Variables synced before but since removed from `env.conf` are unset there too.
+fn sync_environment_d(vars: &[EnvVar]) -> Result<(), String> {
+    let path = Path::new(&get_home_dir())
+        .join(".config/environment.d")
+        .join(ENVIRONMENT_D_FILE);
+    let dropped = dropped_keys(&read_lines(&path)?, vars);
+
+    let mut lines = vec![
+        "# Environment variables synced from Hyprland env.conf".to_string(),
+        "# Auto-generated by Settings".to_string(),
+    ];
+    lines.extend(vars.iter().map(environment_d_line));
+    write_lines(&path, &lines)?;
+
+    // Make the values visible to services started from now on without a re-login.
+    // `set-environment` takes each assignment as one argument, so no quoting is needed.
+    let assignments: Vec<String> = vars
+        .iter()
+        .map(|v| format!("{}={}", v.key, v.value))
+        .collect();
+    systemctl_user_env("set-environment", &assignments)?;
+    systemctl_user_env("unset-environment", &dropped)
+}
+
+/// Replaces the lines between the managed block markers, or appends the block if the
+/// file has none. Refuses to touch a file whose markers are missing, repeated or out of order.
+fn replace_managed_block(lines: &mut Vec<String>, block: Vec<String>) -> Result<(), String> {
+    let positions = |marker: &str| -> Vec<usize> {
+        lines
+            .iter()
+            .enumerate()
+            .filter(|(_, l)| l.trim() == marker)
+            .map(|(index, _)| index)
+            .collect()
+    };
+
+    let mut managed = vec![UWSM_BLOCK_START.to_string()];
+    managed.extend(block);
+    managed.push(UWSM_BLOCK_END.to_string());
+
+    match (
+        positions(UWSM_BLOCK_START).as_slice(),
+        positions(UWSM_BLOCK_END).as_slice(),
+    ) {
+        ([], []) => {
+            lines.extend(managed);
+            Ok(())
+        }
+        ([start], [end]) if start < end => {
+            lines.splice(*start..=*end, managed);
+            Ok(())
+        }
+        _ => Err(format!(
+            "The managed block in {} is damaged; fix or remove its '{}' and '{}' lines",
+            get_uwsm_env_path().display(),
+            UWSM_BLOCK_START,
+            UWSM_BLOCK_END
+        )),
+    }
+}
+
+/// Replaces the managed block in the uwsm env file, keeping the user's own lines.
+fn sync_uwsm(vars: &[EnvVar]) -> Result<(), String> {
+    let path = get_uwsm_env_path();
+    let mut lines = read_lines(&path)?;
+
+    replace_managed_block(
+        &mut lines,
+        vars.iter()
+            .map(|v| format!("export {}={}", v.key, shell_quote(&v.value)))
+            .collect(),
+    )?;
+    write_lines(&path, &lines)
+}
+
+/// Copies the variables from `env.conf` to the selected targets.
+#[tauri::command]
+pub fn sync_env_vars(targets: Vec<EnvSyncTarget>) -> Result<(), String> {
+    let vars = get_env_vars()?;
+
+    for target in targets {
+        match target {
+            EnvSyncTarget::EnvironmentD => sync_environment_d(&vars)?,
+            EnvSyncTarget::Uwsm => sync_uwsm(&vars)?,
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(text: &str) -> Vec<String> {
+        text.lines().map(String::from).collect()
+    }
+
+    #[test]
+    fn appends_block_when_missing() {
+        let mut file = lines("export EDITOR=vim");
+        replace_managed_block(&mut file, vec!["export A='1'".to_string()]).unwrap();
+        assert_eq!(
+            file,
+            [
+                "export EDITOR=vim",
+                UWSM_BLOCK_START,
+                "export A='1'",
+                UWSM_BLOCK_END
+            ]
+        );
+    }
+
+    #[test]
+    fn replaces_block_in_place() {
+        let mut file = vec![
+            "export EDITOR=vim".to_string(),
+            UWSM_BLOCK_START.to_string(),
+            "export OLD='x'".to_string(),
+            UWSM_BLOCK_END.to_string(),
+            "export PAGER=less".to_string(),
+        ];
+        replace_managed_block(&mut file, vec!["export A='1'".to_string()]).unwrap();
+        assert_eq!(
+            file,
+            [
+                "export EDITOR=vim",
+                UWSM_BLOCK_START,
+                "export A='1'",
+                UWSM_BLOCK_END,
+                "export PAGER=less"
+            ]
+        );
+    }
+
+    #[test]
+    fn refuses_damaged_markers() {
+        let damaged = [
+            vec![UWSM_BLOCK_START],
+            vec![UWSM_BLOCK_END],
+            vec![UWSM_BLOCK_END, UWSM_BLOCK_START],
+            vec![
+                UWSM_BLOCK_START,
+                UWSM_BLOCK_END,
+                UWSM_BLOCK_START,
+                UWSM_BLOCK_END,
+            ],
+        ];
+        for markers in damaged {
+            let mut file: Vec<String> = markers.iter().map(|m| m.to_string()).collect();
+            let before = file.clone();
+            assert!(replace_managed_block(&mut file, Vec::new()).is_err());
+            assert_eq!(file, before);
+        }
+    }
+
+    fn var(key: &str, value: &str) -> EnvVar {
+        EnvVar {
+            id: String::new(),
+            key: key.to_string(),
+            value: value.to_string(),
+        }
+    }
+
+    #[test]
+    fn quotes_environment_d_values_when_needed() {
+        assert_eq!(environment_d_line(&var("EDITOR", "nvim")), "EDITOR=nvim");
+        assert_eq!(
+            environment_d_line(&var("GTK_THEME", "Adwaita:dark")),
+            "GTK_THEME=Adwaita:dark"
+        );
+        assert_eq!(
+            environment_d_line(&var("OPTS", "--a --b")),
+            "OPTS='--a --b'"
+        );
+        assert_eq!(
+            environment_d_line(&var("PATH_EXTRA", "$HOME/bin")),
+            "PATH_EXTRA='$HOME/bin'"
+        );
+        assert_eq!(
+            environment_d_line(&var("GREETING", "it's")),
+            "GREETING='it'\\''s'"
+        );
+    }
+
+    #[test]
+    fn finds_keys_dropped_since_the_last_sync() {
+        let previous = lines(
+            "# Environment variables synced from Hyprland env.conf\n\
+             # Auto-generated by Settings\n\
+             EDITOR=nvim\n\
+             OPTS='--a --b'\n\
+             XCURSOR_SIZE=24",
+        );
+        let vars = [var("EDITOR", "vim"), var("XCURSOR_SIZE", "32")];
+        assert_eq!(dropped_keys(&previous, &vars), ["OPTS"]);
+        assert!(dropped_keys(&[], &vars).is_empty());
+    }
+
+    #[test]
+    fn only_counts_missing_hyprland_values_when_hyprland_was_read() {
+        let vars = || vec![var("EDITOR", "nvim")];
+        let none = HashMap::new();
+
+        // No Hyprland process: nothing to compare against
+        let report = compare_env(vars(), None, &none, &none);
+        assert!(!report[0].mismatch);
+
+        // Hyprland read but the variable is not set there
+        let report = compare_env(vars(), Some(&none), &none, &none);
+        assert!(report[0].mismatch);
+
+        let hyprland = HashMap::from([("EDITOR".to_string(), "nvim".to_string())]);
+        let report = compare_env(vars(), Some(&hyprland), &none, &none);
+        assert!(!report[0].mismatch);
+
+        let systemd = HashMap::from([("EDITOR".to_string(), "vim".to_string())]);
+        let report = compare_env(vars(), None, &systemd, &none);
+        assert!(report[0].mismatch);
+    }
+}
@@ -1,54 +1,729 @@
+use crate::modules::hyprland::config_edit::{indentation, read_lines, stable_id, write_lines};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::env;
-use std::fs;
-use std::path::Path;
+use std::fs::{self, DirBuilder, File, OpenOptions};
+use std::os::unix::fs::{DirBuilderExt, OpenOptionsExt};
+use std::os::unix::process::CommandExt;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// How long a test run is observed when no duration is given.
+const DEFAULT_TEST_RUN_SECS: u64 = 3;
+
+/// Upper bound for a test run so the UI never waits indefinitely.
+const MAX_TEST_RUN_SECS: u64 = 15;
+
+/// Output kept from each stream of a test run.
+const MAX_TEST_RUN_OUTPUT: usize = 64 * 1024;
+
+/// Comment prefix marking the line above an entry as its label.
+const LABEL_PREFIX: &str = "# label:";
+
+/// Comment prefix of entries disabled from the app. Ordinary comments, such as
+/// commented-out examples, are not listed as entries.
+const DISABLED_PREFIX: &str = "#!";
+
+/// System-wide XDG autostart directory.
+const SYSTEM_AUTOSTART_DIR: &str = "/etc/xdg/autostart";
+
+/// When a startup command runs.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum StartupKind {
+    /// Runs once when Hyprland starts.
+    #[serde(rename = "exec-once")]
+    ExecOnce,
+    /// Runs on start and on every config reload.
+    #[serde(rename = "exec")]
+    Exec,
+    /// Runs when Hyprland shuts down.
+    #[serde(rename = "exec-shutdown")]
+    ExecShutdown,
+}
+
+impl StartupKind {
+    /// Returns the Hyprland keyword for the kind.
+    fn keyword(self) -> &'static str {
+        match self {
+            StartupKind::ExecOnce => "exec-once",
+            StartupKind::Exec => "exec",
+            StartupKind::ExecShutdown => "exec-shutdown",
+        }
+    }
+
+    fn from_keyword(keyword: &str) -> Option<Self> {
+        match keyword {
+            "exec-once" => Some(StartupKind::ExecOnce),
+            "exec" => Some(StartupKind::Exec),
+            "exec-shutdown" => Some(StartupKind::ExecShutdown),
+            _ => None,
+        }
+    }
+}
+
+/// A command launched by Hyprland from `exec.conf`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StartupEntry {
+    #[serde(default)]
+    pub id: String,
+    pub kind: StartupKind,
+    pub command: String,
+    pub enabled: bool, // disabled entries stay in the file, commented out with `#!`
+    #[serde(default)]
+    pub label: Option<String>,
+}
+
+impl StartupEntry {
+    /// Serializes the entry as a `kind = command` line, commented out when disabled.
+    fn to_line(&self) -> String {
+        let line = format!("{} = {}", self.kind.keyword(), self.command.trim());
+        if self.enabled {
+            line
+        } else {
+            format!("{} {}", DISABLED_PREFIX, line)
+        }
+    }
+
+    /// Returns the label comment line, if the entry has a label.
+    fn label_line(&self) -> Option<String> {
+        self.label
+            .as_deref()
+            .map(str::trim)
+            .filter(|l| !l.is_empty())
+            .map(|l| format!("{} {}", LABEL_PREFIX, l))
+    }
+}
+
+/// An XDG autostart `.desktop` entry.
+#[derive(Debug, Serialize)]
+pub struct AutostartEntry {
+    pub id: String, // desktop file name, shared by the user override and the system file
+    pub name: String,
+    pub comment: String,
+    pub exec: String,
+    pub enabled: bool,
+    pub source: String, // "user" or "system"
+    pub path: String,
+}
+
+/// Whether a process matching a startup entry is running.
+#[derive(Debug, Serialize)]
+pub struct StartupStatus {
+    pub id: String,
+    pub executable: String,
+    pub running: bool,
+    pub pids: Vec<u32>,
+}
+
+/// How a test run launches the command.
+#[derive(Debug, Deserialize, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum TestRunMode {
+    /// Through `hyprctl dispatch exec`, with the same environment Hyprland gives startup entries.
+    Hyprctl,
+    /// As a detached child of this app.
+    Direct,
+}
+
+/// Output captured while a startup command was test-run.
+#[derive(Debug, Serialize)]
+pub struct TestRunResult {
+    pub stdout: String,
+    pub stderr: String,
+    pub exit_code: Option<i32>, // None while the command is still running or when launched via hyprctl
+    pub running: bool,
+}
 
 /// Returns the user's home directory, defaulting to a specific path if HOME is not set.
 fn get_home_dir() -> String {
     env::var("HOME").unwrap_or_else(|_| "/home/i4104".to_string())
 }
 
-/// Retrieves list of startup commands from Hyprland config.
+/// Returns the path of the Hyprland startup config file.
+fn get_exec_path() -> PathBuf {
+    Path::new(&get_home_dir()).join(".config/hypr/exec.conf")
+}
+
+/// Returns the user's XDG autostart directory.
+fn get_user_autostart_dir() -> PathBuf {
+    Path::new(&get_home_dir()).join(".config/autostart")
+}
+
+/// Parses a `kind = command` line, active or disabled with `#!`.
+/// Returns the kind, the command and whether the line is active.
+fn parse_exec_line(line: &str) -> Option<(StartupKind, String, bool)> {
+    let trim = line.trim();
+    let (body, enabled) = match trim.strip_prefix(DISABLED_PREFIX) {
+        Some(rest) => (rest.trim(), false),
+        None if trim.starts_with('#') => return None,
+        None => (trim, true),
+    };
+
+    let (keyword, command) = body.split_once('=')?;
+    let kind = StartupKind::from_keyword(keyword.trim())?;
+    let command = command.trim();
+    if command.is_empty() {
+        return None;
+    }
+    Some((kind, command.to_string(), enabled))
+}
+
+/// Returns the label stored on a `# label:` comment line.
+fn parse_label_line(line: &str) -> Option<String> {
+    line.trim()
+        .strip_prefix(LABEL_PREFIX)
+        .map(|l| l.trim().to_string())
+        .filter(|l| !l.is_empty())
+}
+
+/// Parses startup entries, returning each with its line index.
+/// IDs are derived from kind and command so they survive edits elsewhere in the file.
+fn parse_startup_entries(lines: &[String]) -> Vec<(usize, StartupEntry)> {
+    let mut entries = Vec::new();
+    let mut seen = HashMap::new();
+
+    for (index, line) in lines.iter().enumerate() {
+        let Some((kind, command, enabled)) = parse_exec_line(line) else {
+            continue;
+        };
+        let label = index
+            .checked_sub(1)
+            .and_then(|prev| parse_label_line(&lines[prev]));
+
+        entries.push((
+            index,
+            StartupEntry {
+                id: stable_id(
+                    "startup",
+                    &format!("{}={}", kind.keyword(), command),
+                    &mut seen,
+                ),
+                kind,
+                command,
+                enabled,
+                label,
+            },
+        ));
+    }
+    entries
+}
+
+/// Finds the line index of the entry with the given ID.
+fn find_startup_entry(lines: &[String], id: &str) -> Result<usize, String> {
+    parse_startup_entries(lines)
+        .into_iter()
+        .find(|(_, entry)| entry.id == id)
+        .map(|(index, _)| index)
+        .ok_or_else(|| format!("Startup entry '{}' not found", id))
+}
+
+/// Retrieves the startup entries from the Hyprland config, including disabled ones.
+#[tauri::command]
+pub fn get_startup_commands() -> Result<Vec<StartupEntry>, String> {
+    Ok(parse_startup_entries(&read_lines(&get_exec_path())?)
+        .into_iter()
+        .map(|(_, entry)| entry)
+        .collect())
+}
+
+/// Saves startup entries to the Hyprland configuration file.
+/// Existing entries and their labels are replaced; other lines are kept.
+#[tauri::command]
+pub fn save_startup_commands(entries: Vec<StartupEntry>) -> Result<(), String> {
+    let config_path = get_exec_path();
+    let existing = read_lines(&config_path)?;
+
+    let mut drop = vec![false; existing.len()];
+    for (index, entry) in parse_startup_entries(&existing) {
+        drop[index] = true;
+        if entry.label.is_some() {
+            drop[index - 1] = true;
+        }
+    }
+
+    let mut lines: Vec<String> = existing
+        .into_iter()
+        .zip(drop)
+        .filter(|(_, drop)| !drop)
+        .map(|(line, _)| line)
+        .collect();
+
+    if lines.is_empty() {
+        lines.push("# Custom startup commands".to_string());
+        lines.push("# Auto-generated by Settings".to_string());
+    }
+
+    for entry in entries.iter().filter(|e| !e.command.trim().is_empty()) {
+        lines.extend(entry.label_line());
+        lines.push(entry.to_line());
+    }
+
+    write_lines(&config_path, &lines)
+}
+
+/// Enables or disables a startup entry by commenting its line in or out.
+#[tauri::command]
+pub fn set_startup_entry_enabled(id: String, enabled: bool) -> Result<(), String> {
+    let config_path = get_exec_path();
+    let mut lines = read_lines(&config_path)?;
+    let index = find_startup_entry(&lines, &id)?;
+
+    let (kind, command, _) = parse_exec_line(&lines[index])
+        .ok_or_else(|| format!("Startup entry '{}' not found", id))?;
+    let entry = StartupEntry {
+        id,
+        kind,
+        command,
+        enabled,
+        label: None,
+    };
+    lines[index] = format!("{}{}", indentation(&lines[index]), entry.to_line());
+    write_lines(&config_path, &lines)
+}
+
+/// Reads the `[Desktop Entry]` group of a desktop file into a key/value map.
+/// Localized keys such as `Name[de]` are skipped.
+fn read_desktop_entry(path: &Path) -> Option<HashMap<String, String>> {
+    let content = fs::read_to_string(path).ok()?;
+    let mut values = HashMap::new();
+    let mut in_entry = false;
+
+    for line in content.lines() {
+        let trim = line.trim();
+        if trim.starts_with('[') {
+            in_entry = trim == "[Desktop Entry]";
+            continue;
+        }
+        if !in_entry || trim.starts_with('#') {
+            continue;
+        }
+        if let Some((key, value)) = trim.split_once('=') {
+            let key = key.trim();
+            if !key.contains('[') {
+                values
+                    .entry(key.to_string())
+                    .or_insert_with(|| value.trim().to_string());
+            }
+        }
+    }
+    Some(values)
+}
+
+/// Builds an autostart entry from a desktop file.
+fn autostart_entry(path: &Path, source: &str) -> Option<AutostartEntry> {
+    let values = read_desktop_entry(path)?;
+    let get = |key: &str| values.get(key).cloned().unwrap_or_default();
+    let is_true = |key: &str| values.get(key).is_some_and(|v| v == "true");
+
+    let enabled = !is_true("Hidden")
+        && values
+            .get("X-GNOME-Autostart-enabled")
+            .is_none_or(|v| v != "false");
+
+    Some(AutostartEntry {
+        id: path.file_name()?.to_string_lossy().to_string(),
+        name: get("Name"),
+        comment: get("Comment"),
+        exec: get("Exec"),
+        enabled,
+        source: source.to_string(),
+        path: path.to_string_lossy().to_string(),
+    })
+}
+
+/// Lists the desktop files in a directory.
+fn desktop_files(dir: &Path) -> Vec<PathBuf> {
+    fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|e| e.path())
+                .filter(|p| p.extension().and_then(|s| s.to_str()) == Some("desktop"))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Lists XDG autostart entries. A user file overrides the system file of the same name.
+#[tauri::command]
+pub fn get_autostart_entries() -> Vec<AutostartEntry> {
+    let mut entries: HashMap<String, AutostartEntry> = HashMap::new();
+
+    for (dir, source) in [
+        (PathBuf::from(SYSTEM_AUTOSTART_DIR), "system"),
+        (get_user_autostart_dir(), "user"),
+    ] {
+        for path in desktop_files(&dir) {
+            if let Some(entry) = autostart_entry(&path, source) {
+                entries.insert(entry.id.clone(), entry);
+            }
+        }
+    }
+
+    let mut entries: Vec<AutostartEntry> = entries.into_values().collect();
+    entries.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+    entries
+}
+
+/// Sets `key=value` in the `[Desktop Entry]` group, adding the key if missing.
+fn set_desktop_key(lines: &mut Vec<String>, key: &str, value: &str) {
+    let line = format!("{}={}", key, value);
+    let mut in_entry = false;
+    let mut insert_at = None;
+
+    for (index, current) in lines.iter().enumerate() {
+        let trim = current.trim();
+        if trim.starts_with('[') {
+            if in_entry {
+                break;
+            }
+            in_entry = trim == "[Desktop Entry]";
+            insert_at = in_entry.then_some(index + 1);
+            continue;
+        }
+        if in_entry {
+            if trim.split_once('=').is_some_and(|(k, _)| k.trim() == key) {
+                lines[index] = line;
+                return;
+            }
+            if !trim.is_empty() {
+                insert_at = Some(index + 1);
+            }
+        }
+    }
+
+    match insert_at {
+        Some(index) => lines.insert(index, line),
+        None => {
+            lines.insert(0, "[Desktop Entry]".to_string());
+            lines.insert(1, line);
+        }
+    }
+}
+
+/// Enables or disables an XDG autostart entry.
+/// System entries are disabled by a user override with `Hidden=true`, which is
+/// a copy of the system file so the entry keeps its name in other tools.
 #[tauri::command]
-pub fn get_startup_commands() -> Vec<String> {
-    let home = get_home_dir();
-    let config_path = Path::new(&home).join(".config/hypr/exec.conf");
-    let mut commands = Vec::new();
-
-    if let Ok(content) = fs::read_to_string(config_path) {
-        for line in content.lines() {
-            let trim = line.trim();
-            if trim.starts_with("exec-once") {
-                if let Some((_, cmd)) = trim.split_once('=') {
-                    commands.push(cmd.trim().to_string());
+pub fn set_autostart_enabled(id: String, enabled: bool) -> Result<(), String> {
+    if id.contains('/') || !id.ends_with(".desktop") {
+        return Err(format!("Invalid autostart entry '{}'", id));
+    }
+
+    let user_path = get_user_autostart_dir().join(&id);
+    let system_path = Path::new(SYSTEM_AUTOSTART_DIR).join(&id);
+
+    let mut lines = if user_path.exists() {
+        read_lines(&user_path)?
+    } else if system_path.exists() {
+        read_lines(&system_path)?
+    } else {
+        return Err(format!("Autostart entry '{}' not found", id));
+    };
+
+    set_desktop_key(&mut lines, "Hidden", if enabled { "false" } else { "true" });
+    if enabled {
+        set_desktop_key(&mut lines, "X-GNOME-Autostart-enabled", "true");
+    }
+    write_lines(&user_path, &lines)
+}
+
+/// Extracts the executable name a startup command launches.
+/// Leading `VAR=value` assignments and launcher wrappers such as `uwsm app --` are skipped.
+pub fn command_executable(command: &str) -> Option<String> {
+    let mut words = command
+        .split_whitespace()
+        .map(|w| w.trim_matches(|c| c == '"' || c == '\''))
+        .skip_while(|w| w.contains('=') && !w.starts_with('-'));
+
+    loop {
+        let word = words.next()?;
+        match word {
+            "env" | "nohup" | "setsid" | "exec" => continue,
+            "uwsm" | "uwsm-app" | "app2unit" => {
+                // Skip the wrapper's own arguments up to `--` or the first non-option word
+                let mut rest: Vec<&str> = words.by_ref().collect();
+                if let Some(sep) = rest.iter().position(|w| *w == "--") {
+                    rest.drain(..=sep);
+                } else {
+                    rest.retain(|w| !w.starts_with('-') && *w != "app");
                 }
+                return rest.first().map(|w| {
+                    Path::new(w)
+                        .file_name()
+                        .unwrap_or_default()
+                        .to_string_lossy()
+                        .to_string()
+                });
+            }
+            _ => {
+                let name = Path::new(word).file_name()?.to_string_lossy().to_string();
+                return (!name.is_empty()).then_some(name);
             }
         }
     }
-    commands
 }
 
-/// Saves startup commands to the Hyprland configuration file.
+/// Reads the command line of every process as `(pid, argv)`.
+fn read_process_cmdlines() -> Vec<(u32, Vec<String>)> {
+    let Ok(entries) = fs::read_dir("/proc") else {
+        return Vec::new();
+    };
+
+    entries
+        .flatten()
+        .filter_map(|entry| {
+            let pid: u32 = entry.file_name().to_str()?.parse().ok()?;
+            let raw = fs::read(entry.path().join("cmdline")).ok()?;
+            let argv: Vec<String> = raw
+                .split(|b| *b == 0)
+                .filter(|arg| !arg.is_empty())
+                .map(|arg| String::from_utf8_lossy(arg).to_string())
+                .collect();
+            (!argv.is_empty()).then_some((pid, argv))
+        })
+        .collect()
+}
+
+/// Finds processes whose executable, or script run by an interpreter, matches `executable`.
+fn matching_pids(processes: &[(u32, Vec<String>)], executable: &str) -> Vec<u32> {
+    let basename = |arg: &str| {
+        Path::new(arg)
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default()
+    };
+
+    processes
+        .iter()
+        .filter(|(_, argv)| argv.iter().take(2).any(|arg| basename(arg) == executable))
+        .map(|(pid, _)| *pid)
+        .collect()
+}
+
+/// Reports which startup entries have a matching running process.
 #[tauri::command]
-pub fn save_startup_commands(commands: Vec<String>) -> Result<(), String> {
-    let home = get_home_dir();
-    let config_path = Path::new(&home).join(".config/hypr/exec.conf");
+pub fn get_startup_status() -> Result<Vec<StartupStatus>, String> {
+    let processes = read_process_cmdlines();
+
+    Ok(get_startup_commands()?
+        .into_iter()
+        .map(|entry| {
+            let executable = command_executable(&entry.command).unwrap_or_default();
+            let pids = if executable.is_empty() {
+                Vec::new()
+            } else {
+                matching_pids(&processes, &executable)
+            };
+            StartupStatus {
+                id: entry.id,
+                executable,
+                running: !pids.is_empty(),
+                pids,
+            }
+        })
+        .collect())
+}
+
+/// Reads the captured output of a test run, keeping at most `MAX_TEST_RUN_OUTPUT` bytes.
+fn read_capture(path: &Path) -> String {
+    let raw = fs::read(path).unwrap_or_default();
+    let _ = fs::remove_file(path);
+    let start = raw.len().saturating_sub(MAX_TEST_RUN_OUTPUT);
+    String::from_utf8_lossy(&raw[start..]).to_string()
+}
+
+/// Creates a fresh directory, readable only by the user, for the output of one test run.
+/// It lives in `$XDG_RUNTIME_DIR` when available, and creating it fails rather than
+/// reusing an existing path, so no one can plant files or symlinks in it beforehand.
+fn create_capture_dir() -> Result<PathBuf, String> {
+    let parent = env::var_os("XDG_RUNTIME_DIR")
+        .map(PathBuf::from)
+        .filter(|dir| dir.is_dir())
+        .unwrap_or_else(env::temp_dir);
+    let stamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or_default();
+    let dir = parent.join(format!(
+        "i4archsettings-testrun-{}-{}",
+        std::process::id(),
+        stamp
+    ));
+
+    DirBuilder::new()
+        .mode(0o700)
+        .create(&dir)
+        .map_err(|e| format!("Failed to create output directory: {}", e))?;
+    Ok(dir)
+}
+
+/// Creates a capture file, failing if anything already exists at the path.
+fn create_capture_file(path: &Path) -> Result<File, String> {
+    OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(0o600)
+        .open(path)
+        .map_err(|e| format!("Failed to create output file: {}", e))
+}
 
-    // Ensure the config directory exists
-    if let Some(parent) = config_path.parent() {
-        let _ = fs::create_dir_all(parent);
+/// Quotes a value for a POSIX shell.
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+/// Starts a test run with its output going to the given new files.
+/// Returns the child process when it was launched directly.
+fn launch_test_run(
+    command: &str,
+    mode: TestRunMode,
+    stdout_path: &Path,
+    stderr_path: &Path,
+) -> Result<Option<Child>, String> {
+    let stdout = create_capture_file(stdout_path)?;
+    let stderr = create_capture_file(stderr_path)?;
+
+    match mode {
+        TestRunMode::Hyprctl => {
+            // Hyprland owns the process, so its output is redirected to the files created above
+            drop((stdout, stderr));
+            let wrapped = format!(
+                "sh -c {} >{} 2>{}",
+                shell_quote(command),
+                shell_quote(&stdout_path.to_string_lossy()),
+                shell_quote(&stderr_path.to_string_lossy())
+            );
+            let output = Command::new("hyprctl")
+                .args(["dispatch", "exec", &wrapped])
+                .output()
+                .map_err(|e| format!("Failed to run hyprctl: {}", e))?;
+            let reply = String::from_utf8_lossy(&output.stdout);
+            if !output.status.success() || reply.trim() != "ok" {
+                return Err(format!("hyprctl dispatch exec failed: {}", reply.trim()));
+            }
+            Ok(None)
+        }
+        TestRunMode::Direct => Command::new("sh")
+            .args(["-c", command])
+            .stdin(Stdio::null())
+            .stdout(stdout)
+            .stderr(stderr)
+            .process_group(0)
+            .spawn()
+            .map(Some)
+            .map_err(|e| format!("Failed to launch command: {}", e)),
+    }
+}
+
+/// Launches a startup command detached and captures its output for a few seconds.
+/// The command keeps running afterwards, just like a real startup entry.
+#[tauri::command]
+pub async fn test_run_startup_command(
+    command: String,
+    mode: TestRunMode,
+    seconds: Option<u64>,
+) -> Result<TestRunResult, String> {
+    let command = command.trim().to_string();
+    if command.is_empty() {
+        return Err("Command is required".to_string());
     }
 
-    let mut content = String::new();
-    content.push_str("# Custom startup commands\n");
-    content.push_str("# Auto-generated by Settings\n");
+    let capture_dir = create_capture_dir()?;
+    let stdout_path = capture_dir.join("stdout");
+    let stderr_path = capture_dir.join("stderr");
+    let child = match launch_test_run(&command, mode, &stdout_path, &stderr_path) {
+        Ok(child) => child,
+        Err(e) => {
+            let _ = fs::remove_dir_all(&capture_dir);
+            return Err(e);
+        }
+    };
+
+    let seconds = seconds
+        .unwrap_or(DEFAULT_TEST_RUN_SECS)
+        .clamp(1, MAX_TEST_RUN_SECS);
+    tokio::time::sleep(Duration::from_secs(seconds)).await;
 
-    for cmd in commands {
-        if !cmd.trim().is_empty() {
-            content.push_str(&format!("exec-once = {}\n", cmd.trim()));
+    let (exit_code, running) = match child {
+        Some(mut child) => match child.try_wait() {
+            Ok(Some(status)) => (status.code(), false),
+            _ => {
+                // Reap the process whenever it exits so it does not linger as a zombie
+                std::thread::spawn(move || child.wait());
+                (None, true)
+            }
+        },
+        None => {
+            let executable = command_executable(&command).unwrap_or_default();
+            let running = !executable.is_empty()
+                && !matching_pids(&read_process_cmdlines(), &executable).is_empty();
+            (None, running)
         }
+    };
+
+    let result = TestRunResult {
+        stdout: read_capture(&stdout_path),
+        stderr: read_capture(&stderr_path),
+        exit_code,
+        running,
+    };
+    let _ = fs::remove_dir(&capture_dir);
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(text: &str) -> Vec<String> {
+        text.lines().map(String::from).collect()
     }
 
-    fs::write(config_path, content).map_err(|e| e.to_string())?;
+    #[test]
+    fn lists_only_entries_disabled_by_the_app() {
+        let file = lines(
+            "# Examples:\n\
+             # exec-once = waybar & hyprpaper\n\
+             ## exec = notes\n\
+             exec-once = waybar\n\
+             #! exec-once = nm-applet --indicator\n\
+             # label: Clipboard\n\
+             exec = wl-paste --watch cliphist store",
+        );
+        let entries: Vec<(usize, bool, String)> = parse_startup_entries(&file)
+            .into_iter()
+            .map(|(index, e)| (index, e.enabled, e.command))
+            .collect();
+        assert_eq!(
+            entries,
+            [
+                (3, true, "waybar".to_string()),
+                (4, false, "nm-applet --indicator".to_string()),
+                (6, true, "wl-paste --watch cliphist store".to_string()),
+            ]
+        );
+        assert_eq!(
+            parse_startup_entries(&file)[2].1.label.as_deref(),
+            Some("Clipboard")
+        );
+    }
 
-    Ok(())
+    #[test]
+    fn round_trips_disabled_entries() {
+        let entry = StartupEntry {
+            id: String::new(),
+            kind: StartupKind::ExecOnce,
+            command: "swww init".to_string(),
+            enabled: false,
+            label: None,
+        };
+        let line = entry.to_line();
+        assert_eq!(line, "#! exec-once = swww init");
+        assert_eq!(
+            parse_exec_line(&line),
+            Some((StartupKind::ExecOnce, "swww init".to_string(), false))
+        );
+    }
 }
@@ -1,3 +1,5 @@
+use crate::modules::hyprland::nightlight::NightLightSettings;
+use crate::modules::systems::vpn::VpnAutoConnectSettings;
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
@@ -8,6 +10,10 @@ pub struct AppSettings {
     pub theme: String,
     #[serde(default = "default_waybar_position")]
     pub waybar_position: String,
+    #[serde(default)]
+    pub night_light: NightLightSettings,
+    #[serde(default)]
+    pub vpn_auto_connect: VpnAutoConnectSettings,
 }
 
 /// Returns the default Waybar position.
@@ -20,6 +26,8 @@ impl Default for AppSettings {
         Self {
             theme: "light".to_string(),
             waybar_position: "top".to_string(),
+            night_light: NightLightSettings::default(),
+            vpn_auto_connect: VpnAutoConnectSettings::default(),
         }
     }
 }
@@ -65,8 +73,13 @@ pub fn save_app_settings(settings: AppSettings) -> Result<(), String> {
         .args(["set", "org.gnome.desktop.interface", "color-scheme", scheme])
         .output();
 
+    write_app_settings(&settings)
+}
+
+/// Persists the application settings without applying any side effects.
+pub fn write_app_settings(settings: &AppSettings) -> Result<(), String> {
     let path = get_config_path();
-    match serde_json::to_string_pretty(&settings) {
+    match serde_json::to_string_pretty(settings) {
         Ok(json) => fs::write(path, json).map_err(|e| e.to_string()),
         Err(e) => Err(e.to_string()),
     }
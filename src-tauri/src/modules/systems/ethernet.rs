@@ -0,0 +1,328 @@
+use crate::modules::systems::nm::client::{network_manager, DeviceInfo, NetworkManager};
+use crate::modules::systems::nm::ip_config::{
+    read_ip_config, write_ip_config, IpConfig, IpConfigReport,
+};
+use crate::modules::systems::nm::profile::{
+    Eap8021x, EapMethod, ProfileBuilder, ProfileUpdateError,
+};
+use serde::Serialize;
+
+/// NetworkManager's setting name for wired connections.
+const ETHERNET_TYPE: &str = "802-3-ethernet";
+
+/// A saved wired connection profile.
+#[derive(Debug, Serialize)]
+pub struct EthernetConnection {
+    pub uuid: String,
+    pub name: String,
+    pub active: bool,
+    pub device: String,         // interface the connection is active on
+    pub interface_name: String, // interface the profile is locked to, empty for any
+    pub autoconnect: bool,
+    pub eap: Option<EapMethod>, // 802.1X method when port authentication is configured
+}
+
+/// Lists wired devices with their state, link speed, MAC address and carrier.
+#[tauri::command]
+pub async fn get_ethernet_devices() -> Result<Vec<DeviceInfo>, String> {
+    Ok(network_manager()
+        .await
+        .devices()
+        .await?
+        .into_iter()
+        .filter(|d| d.device_type == "ethernet")
+        .collect())
+}
+
+/// Reads the saved wired profiles.
+pub async fn list_ethernet_connections(
+    nm: &dyn NetworkManager,
+) -> Result<Vec<EthernetConnection>, String> {
+    let mut connections = Vec::new();
+
+    for conn in nm.connections().await? {
+        if conn.conn_type != ETHERNET_TYPE {
+            continue;
+        }
+        let settings = nm
+            .connection_settings(
+                &conn.uuid,
+                &[
+                    "connection.autoconnect",
+                    "connection.interface-name",
+                    "802-1x.eap",
+                ],
+            )
+            .await?;
+        let get = |key: &str| settings.get(key).map(|v| v.trim()).unwrap_or("");
+
+        connections.push(EthernetConnection {
+            uuid: conn.uuid,
+            name: conn.name,
+            active: conn.active,
+            device: conn.device,
+            interface_name: get("connection.interface-name").to_string(),
+            autoconnect: get("connection.autoconnect") != "no",
+            eap: match get("802-1x.eap").split(',').next() {
+                Some("peap") => Some(EapMethod::Peap),
+                Some("ttls") => Some(EapMethod::Ttls),
+                Some("tls") => Some(EapMethod::Tls),
+                _ => None,
+            },
+        });
+    }
+    Ok(connections)
+}
+
+/// Lists saved wired connections.
+#[tauri::command]
+pub async fn get_ethernet_connections() -> Result<Vec<EthernetConnection>, String> {
+    list_ethernet_connections(network_manager().await.as_ref()).await
+}
+
+/// Creates a wired profile for an interface and returns its UUID.
+#[tauri::command]
+pub async fn add_ethernet_connection(
+    interface: String,
+    name: Option<String>,
+) -> Result<String, String> {
+    let name = name
+        .filter(|n| !n.trim().is_empty())
+        .unwrap_or_else(|| format!("Wired {}", interface));
+    let mut profile = ProfileBuilder::new(ETHERNET_TYPE, &name);
+    profile.set("connection.interface-name", interface.trim());
+
+    let settings = profile.build().map_err(|errors| {
+        errors
+            .iter()
+            .map(|e| e.message.clone())
+            .collect::<Vec<_>>()
+            .join(", ")
+    })?;
+    network_manager().await.add_connection(&settings).await
+}
+
+/// Re-activates a connection if it is up, so saved changes take effect.
+async fn reapply(nm: &dyn NetworkManager, id: &str) -> Result<(), ProfileUpdateError> {
+    let active = nm
+        .connections()
+        .await
+        .map_err(|message| ProfileUpdateError::Failed { message })?
+        .into_iter()
+        .any(|c| (c.uuid == id || c.name == id) && c.active);
+
+    if active {
+        nm.activate_connection(id)
+            .await
+            .map_err(|message| ProfileUpdateError::ActivationFailed { message })?;
+    }
+    Ok(())
+}
+
+/// Retrieves the configured and active IPv4/IPv6 settings of a wired connection.
+#[tauri::command]
+pub async fn get_ethernet_config(id: String) -> Result<IpConfigReport, String> {
+    read_ip_config(network_manager().await.as_ref(), &id).await
+}
+
+/// Validates and saves the IPv4/IPv6 settings of a wired connection.
+#[tauri::command]
+pub async fn set_ethernet_config(id: String, config: IpConfig) -> Result<(), ProfileUpdateError> {
+    let nm = network_manager().await;
+    write_ip_config(nm.as_ref(), &id, &config).await?;
+    reapply(nm.as_ref(), &id).await
+}
+
+/// Enables or disables automatic activation of a wired connection.
+#[tauri::command]
+pub async fn set_ethernet_autoconnect(uuid: String, enabled: bool) -> Result<(), String> {
+    let value = if enabled { "yes" } else { "no" };
+    network_manager()
+        .await
+        .modify_connection(
+            &uuid,
+            &[("connection.autoconnect".to_string(), value.to_string())],
+        )
+        .await
+}
+
+/// Writes or removes the 802.1X setting of a wired connection, then re-activates it if it is up.
+async fn configure_8021x(
+    nm: &dyn NetworkManager,
+    uuid: &str,
+    eap: Option<&Eap8021x>,
+) -> Result<(), ProfileUpdateError> {
+    let changes = match eap {
+        Some(eap) => {
+            let mut profile = ProfileBuilder::modify();
+            profile.eap(eap);
+            profile
+                .build()
+                .map_err(|errors| ProfileUpdateError::Invalid { errors })?
+        }
+        // `nmcli connection modify <id> remove 802-1x` drops the whole setting
+        None => vec![("remove".to_string(), "802-1x".to_string())],
+    };
+
+    nm.modify_connection(uuid, &changes)
+        .await
+        .map_err(|message| ProfileUpdateError::Failed { message })?;
+    reapply(nm, uuid).await
+}
+
+/// Configures 802.1X port authentication for a wired connection, or removes it when `eap` is None.
+#[tauri::command]
+pub async fn set_ethernet_8021x(
+    uuid: String,
+    eap: Option<Eap8021x>,
+) -> Result<(), ProfileUpdateError> {
+    configure_8021x(network_manager().await.as_ref(), &uuid, eap.as_ref()).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::systems::nm::client::ConnectionProfile;
+    use crate::modules::systems::nm::fake::{FakeNetworkManager, FakeState};
+    use std::collections::HashMap;
+
+    /// UUID, connection type, whether it is active, and its settings.
+    type Profile<'a> = (&'a str, &'a str, bool, &'a [(&'a str, &'a str)]);
+
+    /// A fake holding one saved profile per entry, with the given settings.
+    fn fake(profiles: &[Profile]) -> FakeNetworkManager {
+        let mut state = FakeState::default();
+        for (uuid, conn_type, active, settings) in profiles {
+            state.connections.push(ConnectionProfile {
+                uuid: uuid.to_string(),
+                name: uuid.to_string(),
+                conn_type: conn_type.to_string(),
+                active: *active,
+                device: if *active {
+                    "eth0".to_string()
+                } else {
+                    String::new()
+                },
+            });
+            state.settings.insert(
+                uuid.to_string(),
+                settings
+                    .iter()
+                    .map(|(k, v)| (k.to_string(), v.to_string()))
+                    .collect::<HashMap<_, _>>(),
+            );
+        }
+        FakeNetworkManager::new(state)
+    }
+
+    #[tokio::test]
+    async fn lists_wired_profiles_with_their_settings() {
+        let nm = fake(&[
+            ("office", ETHERNET_TYPE, true, &[("802-1x.eap", "peap")]),
+            (
+                "lab",
+                ETHERNET_TYPE,
+                false,
+                &[
+                    ("connection.autoconnect", "no"),
+                    ("connection.interface-name", "enp3s0"),
+                    ("802-1x.eap", "ttls,tls"),
+                ],
+            ),
+            ("dock", ETHERNET_TYPE, false, &[("802-1x.eap", "tls")]),
+            (
+                "spare",
+                ETHERNET_TYPE,
+                false,
+                &[("connection.autoconnect", "yes")],
+            ),
+            ("home", "802-11-wireless", false, &[]),
+        ]);
+
+        let connections = list_ethernet_connections(&nm).await.unwrap();
+        let summary: Vec<(&str, bool, &str, Option<EapMethod>)> = connections
+            .iter()
+            .map(|c| {
+                (
+                    c.uuid.as_str(),
+                    c.autoconnect,
+                    c.interface_name.as_str(),
+                    c.eap,
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("office", true, "", Some(EapMethod::Peap)),
+                ("lab", false, "enp3s0", Some(EapMethod::Ttls)),
+                ("dock", true, "", Some(EapMethod::Tls)),
+                ("spare", true, "", None),
+            ]
+        );
+        assert_eq!(connections[0].device, "eth0");
+    }
+
+    #[tokio::test]
+    async fn removes_the_8021x_setting() {
+        let nm = fake(&[(
+            "office",
+            ETHERNET_TYPE,
+            false,
+            &[
+                ("connection.autoconnect", "yes"),
+                ("802-1x.eap", "peap"),
+                ("802-1x.identity", "alice"),
+            ],
+        )]);
+
+        configure_8021x(&nm, "office", None).await.unwrap();
+
+        let settings = &nm.state().settings["office"];
+        assert!(settings.keys().all(|key| !key.starts_with("802-1x.")));
+        assert_eq!(settings["connection.autoconnect"], "yes");
+    }
+
+    #[tokio::test]
+    async fn writes_peap_credentials() {
+        let nm = fake(&[("office", ETHERNET_TYPE, true, &[])]);
+        let eap = Eap8021x {
+            method: EapMethod::Peap,
+            identity: "alice".to_string(),
+            anonymous_identity: None,
+            password: Some("secret".to_string()),
+            phase2_auth: None,
+            ca_cert: None,
+            client_cert: None,
+            private_key: None,
+            private_key_password: None,
+        };
+
+        configure_8021x(&nm, "office", Some(&eap)).await.unwrap();
+
+        let settings = &nm.state().settings["office"];
+        assert_eq!(settings["802-1x.eap"], "peap");
+        assert_eq!(settings["802-1x.identity"], "alice");
+        assert_eq!(settings["802-1x.phase2-auth"], "mschapv2");
+    }
+
+    #[tokio::test]
+    async fn rejects_incomplete_credentials() {
+        let nm = fake(&[("office", ETHERNET_TYPE, false, &[])]);
+        let eap = Eap8021x {
+            method: EapMethod::Tls,
+            identity: String::new(),
+            anonymous_identity: None,
+            password: None,
+            phase2_auth: None,
+            ca_cert: None,
+            client_cert: None,
+            private_key: None,
+            private_key_password: None,
+        };
+
+        let result = configure_8021x(&nm, "office", Some(&eap)).await;
+        assert!(matches!(result, Err(ProfileUpdateError::Invalid { errors }) if errors.len() == 3));
+        assert!(nm.state().settings["office"].is_empty());
+    }
+}
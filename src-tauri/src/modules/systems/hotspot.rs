@@ -0,0 +1,275 @@
+use crate::modules::systems::nm::client::{network_manager, NetworkManager};
+use crate::modules::systems::nm::profile::{ProfileBuilder, ProfileError, ProfileUpdateError};
+use crate::modules::systems::wifi::{
+    is_valid_passphrase, render_qr_svg, wifi_qr_payload, QrSecurity, WifiBand,
+};
+use serde::{Deserialize, Serialize};
+
+/// Name of the profile used for the hotspot, the same one `nmcli device wifi hotspot` uses.
+const HOTSPOT_NAME: &str = "Hotspot";
+
+/// Settings for starting a hotspot.
+#[derive(Debug, Deserialize)]
+pub struct HotspotConfig {
+    pub ssid: String,
+    pub password: String,
+    pub band: WifiBand,
+    pub channel: Option<u32>,
+    pub interface: Option<String>, // Wi-Fi device to use, any when None
+}
+
+/// The saved hotspot profile and whether it is running.
+#[derive(Debug, Serialize)]
+pub struct HotspotStatus {
+    pub active: bool,
+    pub uuid: Option<String>,
+    pub ssid: String,
+    pub password: String,
+    pub band: WifiBand,
+    pub channel: Option<u32>,
+    pub interface: String,
+}
+
+/// Checks the channel against the band; 5 GHz channels run from 36 to 177.
+fn valid_channel(band: WifiBand, channel: u32) -> bool {
+    match band {
+        WifiBand::Auto => false,
+        WifiBand::Bg => (1..=14).contains(&channel),
+        WifiBand::A => (36..=177).contains(&channel),
+    }
+}
+
+/// Validates a hotspot configuration and turns it into access point profile settings.
+pub fn build_hotspot_profile(
+    config: &HotspotConfig,
+) -> Result<Vec<(String, String)>, Vec<ProfileError>> {
+    let ssid = config.ssid.trim();
+    let mut profile = ProfileBuilder::new("802-11-wireless", HOTSPOT_NAME);
+
+    if ssid.is_empty() {
+        profile.error("ssid", "A network name is required");
+    } else if ssid.len() > 32 {
+        profile.error("ssid", "An SSID is at most 32 bytes long");
+    }
+    if !is_valid_passphrase(&config.password) {
+        profile.error("password", "The password must be 8 to 63 characters long");
+    }
+
+    profile.set("connection.autoconnect", "no");
+    profile.set(
+        "connection.interface-name",
+        config.interface.as_deref().unwrap_or("").trim(),
+    );
+    profile.set("802-11-wireless.ssid", ssid);
+    profile.set("802-11-wireless.mode", "ap");
+    profile.set("802-11-wireless.band", config.band.as_setting());
+    match config.channel {
+        Some(channel) if !valid_channel(config.band, channel) => {
+            profile.error("channel", "The channel is not valid for the selected band");
+        }
+        Some(channel) => {
+            profile.set("802-11-wireless.channel", channel.to_string());
+        }
+        None => {
+            profile.set("802-11-wireless.channel", "0");
+        }
+    }
+
+    // WPA2 with CCMP only, as `nmcli device wifi hotspot` sets it up
+    profile.set("802-11-wireless-security.key-mgmt", "wpa-psk");
+    profile.set("802-11-wireless-security.proto", "rsn");
+    profile.set("802-11-wireless-security.pairwise", "ccmp");
+    profile.set("802-11-wireless-security.group", "ccmp");
+    profile.set("802-11-wireless-security.psk", config.password.as_str());
+
+    // Share the upstream connection with clients through NAT and a DHCP server
+    profile.set("ipv4.method", "shared");
+    profile.set("ipv6.method", "ignore");
+    profile.build()
+}
+
+/// Finds the UUID of the saved hotspot profile and whether it is active.
+/// Only access point profiles count, so a client profile for a network that happens
+/// to be called "Hotspot" is never overwritten.
+async fn hotspot_uuid(nm: &dyn NetworkManager) -> Result<Option<(String, bool)>, String> {
+    for conn in nm.connections().await? {
+        if conn.conn_type != "802-11-wireless" || conn.name != HOTSPOT_NAME {
+            continue;
+        }
+        let settings = nm
+            .connection_settings(&conn.uuid, &["802-11-wireless.mode"])
+            .await?;
+        if settings.get("802-11-wireless.mode").map(|m| m.trim()) == Some("ap") {
+            return Ok(Some((conn.uuid, conn.active)));
+        }
+    }
+    Ok(None)
+}
+
+/// Reads the saved hotspot settings, including the password so it can be displayed and shared.
+#[tauri::command]
+pub async fn get_hotspot_status() -> Result<HotspotStatus, String> {
+    let nm = network_manager().await;
+    let Some((uuid, active)) = hotspot_uuid(nm.as_ref()).await? else {
+        return Ok(HotspotStatus {
+            active: false,
+            uuid: None,
+            ssid: String::new(),
+            password: String::new(),
+            band: WifiBand::Auto,
+            channel: None,
+            interface: String::new(),
+        });
+    };
+
+    let settings = nm
+        .connection_secrets(
+            &uuid,
+            &[
+                "802-11-wireless.ssid",
+                "802-11-wireless.band",
+                "802-11-wireless.channel",
+                "connection.interface-name",
+                "802-11-wireless-security.psk",
+            ],
+        )
+        .await?;
+    let get = |key: &str| settings.get(key).map(|v| v.trim()).unwrap_or("");
+
+    Ok(HotspotStatus {
+        active,
+        uuid: Some(uuid.clone()),
+        ssid: get("802-11-wireless.ssid").to_string(),
+        password: get("802-11-wireless-security.psk").to_string(),
+        band: WifiBand::from_setting(get("802-11-wireless.band")),
+        channel: get("802-11-wireless.channel")
+            .parse()
+            .ok()
+            .filter(|c| *c > 0),
+        interface: get("connection.interface-name").to_string(),
+    })
+}
+
+/// Saves the hotspot profile and brings it up. Returns the profile UUID.
+#[tauri::command]
+pub async fn start_hotspot(config: HotspotConfig) -> Result<String, ProfileUpdateError> {
+    let settings =
+        build_hotspot_profile(&config).map_err(|errors| ProfileUpdateError::Invalid { errors })?;
+    let nm = network_manager().await;
+    let failed = |message: String| ProfileUpdateError::Failed { message };
+
+    let uuid = match hotspot_uuid(nm.as_ref()).await.map_err(failed)? {
+        Some((uuid, _)) => {
+            let changes: Vec<(String, String)> = settings
+                .into_iter()
+                .filter(|(prop, _)| prop != "connection.type")
+                .collect();
+            nm.modify_connection(&uuid, &changes)
+                .await
+                .map_err(failed)?;
+            uuid
+        }
+        None => nm.add_connection(&settings).await.map_err(failed)?,
+    };
+
+    nm.activate_connection(&uuid)
+        .await
+        .map_err(|message| ProfileUpdateError::ActivationFailed { message })?;
+    Ok(uuid)
+}
+
+/// Stops the hotspot, keeping its profile for the next start.
+#[tauri::command]
+pub async fn stop_hotspot() -> Result<(), String> {
+    let nm = network_manager().await;
+    match hotspot_uuid(nm.as_ref()).await? {
+        Some((uuid, true)) => nm.deactivate_connection(&uuid).await,
+        _ => Ok(()),
+    }
+}
+
+/// Renders the hotspot credentials as a QR code for clients to scan. Returns the SVG markup.
+#[tauri::command]
+pub async fn get_hotspot_qr() -> Result<String, String> {
+    let status = get_hotspot_status().await?;
+    if status.uuid.is_none() {
+        return Err("No hotspot has been set up".to_string());
+    }
+    render_qr_svg(&wifi_qr_payload(
+        &status.ssid,
+        QrSecurity::Wpa(&status.password),
+        false,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::systems::nm::client::ConnectionProfile;
+    use crate::modules::systems::nm::fake::{FakeNetworkManager, FakeState};
+    use std::collections::HashMap;
+
+    fn wifi_profile(
+        uuid: &str,
+        mode: &str,
+        active: bool,
+    ) -> (ConnectionProfile, HashMap<String, String>) {
+        let profile = ConnectionProfile {
+            uuid: uuid.to_string(),
+            name: HOTSPOT_NAME.to_string(),
+            conn_type: "802-11-wireless".to_string(),
+            active,
+            device: String::new(),
+        };
+        let settings = HashMap::from([("802-11-wireless.mode".to_string(), mode.to_string())]);
+        (profile, settings)
+    }
+
+    fn fake(profiles: Vec<(ConnectionProfile, HashMap<String, String>)>) -> FakeNetworkManager {
+        let mut state = FakeState::default();
+        for (profile, settings) in profiles {
+            state.settings.insert(profile.uuid.clone(), settings);
+            state.connections.push(profile);
+        }
+        FakeNetworkManager::new(state)
+    }
+
+    #[tokio::test]
+    async fn ignores_client_profiles_named_hotspot() {
+        let nm = fake(vec![wifi_profile("client", "infrastructure", true)]);
+        assert_eq!(hotspot_uuid(&nm).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn finds_the_access_point_profile() {
+        let nm = fake(vec![
+            wifi_profile("client", "infrastructure", true),
+            wifi_profile("ap", "ap", false),
+        ]);
+        assert_eq!(
+            hotspot_uuid(&nm).await.unwrap(),
+            Some(("ap".to_string(), false))
+        );
+    }
+
+    #[test]
+    fn builds_an_access_point_profile() {
+        let settings = build_hotspot_profile(&HotspotConfig {
+            ssid: "Laptop".to_string(),
+            password: "correct horse".to_string(),
+            band: WifiBand::Bg,
+            channel: Some(6),
+            interface: None,
+        })
+        .unwrap();
+        let get = |prop: &str| {
+            settings
+                .iter()
+                .find(|(p, _)| p == prop)
+                .map(|(_, v)| v.as_str())
+        };
+        assert_eq!(get("connection.id"), Some(HOTSPOT_NAME));
+        assert_eq!(get("802-11-wireless.mode"), Some("ap"));
+        assert_eq!(get("802-11-wireless.channel"), Some("6"));
+    }
+}
@@ -1,4 +1,11 @@
 pub mod bluetooth;
+pub mod ethernet;
+pub mod hotspot;
+pub mod nm;
+pub mod proxy;
 pub mod system;
 pub mod vpn;
+pub mod vpn_plugins;
 pub mod wifi;
+pub mod wifi_monitor;
+pub mod wireguard;
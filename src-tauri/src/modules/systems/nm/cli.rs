@@ -0,0 +1,361 @@
+use crate::modules::systems::nm::client::{
+    split_terse, unescape_terse, AccessPoint, ConnectionProfile, DeviceInfo, NetworkManager,
+};
+use async_trait::async_trait;
+use std::collections::HashMap;
+use tokio::process::Command;
+
+/// NetworkManager backend driving the `nmcli` command line tool.
+pub struct NmcliClient;
+
+/// Runs nmcli and returns its stdout, or its stderr as the error.
+async fn nmcli(args: &[&str]) -> Result<String, String> {
+    let output = Command::new("nmcli")
+        .args(args)
+        .output()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
+
+/// Parses the leading number of values such as `5180 MHz` or `540 Mbit/s`.
+fn leading_number(value: &str) -> u32 {
+    value
+        .split_whitespace()
+        .next()
+        .and_then(|n| n.parse().ok())
+        .unwrap_or(0)
+}
+
+/// Collects `nmcli -t -f` multiline output by field name. Runtime values that repeat
+/// (`IP4.ADDRESS[1]`, `IP4.ADDRESS[2]`) are joined with ` | ` like nmcli's regular output.
+/// Requested fields nmcli did not print, such as `IP4.*` of an inactive connection, are empty.
+fn parse_fields(stdout: &str, properties: &[&str]) -> HashMap<String, String> {
+    let mut values: HashMap<String, String> = properties
+        .iter()
+        .map(|prop| (prop.to_string(), String::new()))
+        .collect();
+
+    for line in stdout.lines() {
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        let name = name.split_once('[').map_or(name, |(base, _)| base);
+        if let Some(entry) = values.get_mut(name) {
+            if !entry.is_empty() {
+                entry.push_str(" | ");
+            }
+            entry.push_str(&unescape_terse(value));
+        }
+    }
+    values
+}
+
+/// Reads connection properties with `nmcli -t -f`, optionally including secrets.
+async fn show_settings(
+    id: &str,
+    properties: &[&str],
+    secrets: bool,
+) -> Result<HashMap<String, String>, String> {
+    let fields = properties.join(",");
+    let mut args = vec!["-t", "-f", fields.as_str()];
+    if secrets {
+        args.push("--show-secrets");
+    }
+    args.extend(["connection", "show", id]);
+    Ok(parse_fields(&nmcli(&args).await?, properties))
+}
+
+/// Extracts the UUID from nmcli's "Connection '...' (UUID) successfully added." message.
+fn added_uuid(stdout: &str) -> Result<String, String> {
+    let start = stdout.rfind('(');
+    let end = stdout.rfind(')');
+    match (start, end) {
+        (Some(start), Some(end)) if start < end => Ok(stdout[start + 1..end].to_string()),
+        _ => Err(format!("Unexpected nmcli output: {}", stdout.trim())),
+    }
+}
+
+#[async_trait]
+impl NetworkManager for NmcliClient {
+    async fn wireless_enabled(&self) -> Result<bool, String> {
+        Ok(nmcli(&["radio", "wifi"]).await?.trim() == "enabled")
+    }
+
+    async fn set_wireless_enabled(&self, enabled: bool) -> Result<(), String> {
+        let state = if enabled { "on" } else { "off" };
+        nmcli(&["radio", "wifi", state]).await.map(|_| ())
+    }
+
+    async fn request_scan(&self) -> Result<(), String> {
+        nmcli(&["device", "wifi", "rescan"]).await.map(|_| ())
+    }
+
+    async fn access_points(&self) -> Result<Vec<AccessPoint>, String> {
+        let stdout = nmcli(&[
+            "-t",
+            "-f",
+            "IN-USE,SSID,BSSID,SECURITY,SIGNAL,FREQ,RATE,WPA-FLAGS,RSN-FLAGS,DEVICE",
+            "device",
+            "wifi",
+            "list",
+            "--rescan",
+            "no",
+        ])
+        .await?;
+
+        Ok(stdout
+            .lines()
+            .filter_map(|line| {
+                let fields = split_terse(line);
+                if fields.len() < 10 {
+                    return None;
+                }
+                let flags = |value: &str| match value {
+                    "(none)" => String::new(),
+                    flags => flags.to_string(),
+                };
+                Some(AccessPoint {
+                    active: fields[0] == "*",
+                    ssid: fields[1].clone(),
+                    bssid: fields[2].clone(),
+                    security: fields[3].clone(),
+                    signal: fields[4].parse().unwrap_or(0),
+                    frequency: leading_number(&fields[5]),
+                    max_bitrate: leading_number(&fields[6]) * 1000,
+                    wpa_flags: flags(&fields[7]),
+                    rsn_flags: flags(&fields[8]),
+                    // nmcli does not report when an AP was last seen
+                    last_seen: None,
+                    device: fields[9].clone(),
+                })
+            })
+            .collect())
+    }
+
+    async fn wifi_bitrate(&self, interface: &str) -> Result<Option<u32>, String> {
+        // nmcli only reports the AP's maximum rate; iw has the current one,
+        // e.g. "tx bitrate: 866.7 MBit/s VHT-MCS 9 80MHz short GI VHT-NSS 2"
+        let output = Command::new("iw")
+            .args(["dev", interface, "link"])
+            .output()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .find_map(|line| line.trim().strip_prefix("tx bitrate:"))
+            .and_then(|rate| rate.split_whitespace().next())
+            .and_then(|mbits| mbits.parse::<f64>().ok())
+            .map(|mbits| (mbits * 1000.0) as u32))
+    }
+
+    async fn devices(&self) -> Result<Vec<DeviceInfo>, String> {
+        let stdout = nmcli(&[
+            "-t",
+            "-f",
+            "DEVICE,TYPE,STATE,CONNECTION",
+            "device",
+            "status",
+        ])
+        .await?;
+
+        let mut devices = Vec::new();
+        for line in stdout.lines() {
+            let fields = split_terse(line);
+            if fields.len() < 4 {
+                continue;
+            }
+
+            // Link details are only available per device
+            let details = nmcli(&[
+                "-g",
+                "GENERAL.HWADDR,CAPABILITIES.SPEED,WIRED-PROPERTIES.CARRIER",
+                "device",
+                "show",
+                &fields[0],
+            ])
+            .await
+            .unwrap_or_default();
+            let details: Vec<String> = details.lines().map(unescape_terse).collect();
+            let detail = |i: usize| details.get(i).map(String::as_str).unwrap_or("");
+
+            devices.push(DeviceInfo {
+                interface: fields[0].clone(),
+                device_type: fields[1].clone(),
+                // e.g. "connecting (getting IP configuration)" or "connected (externally)"
+                state: fields[2]
+                    .split_whitespace()
+                    .next()
+                    .unwrap_or_default()
+                    .to_string(),
+                mac: detail(0).to_string(),
+                speed: Some(leading_number(detail(1))).filter(|s| *s > 0),
+                carrier: detail(2) == "on",
+                connection: if fields[3] == "--" {
+                    String::new()
+                } else {
+                    fields[3].clone()
+                },
+            });
+        }
+        Ok(devices)
+    }
+
+    async fn connections(&self) -> Result<Vec<ConnectionProfile>, String> {
+        let stdout = nmcli(&[
+            "-t",
+            "-f",
+            "UUID,NAME,TYPE,ACTIVE,DEVICE",
+            "connection",
+            "show",
+        ])
+        .await?;
+
+        Ok(stdout
+            .lines()
+            .filter_map(|line| {
+                let fields = split_terse(line);
+                if fields.len() < 5 {
+                    return None;
+                }
+                Some(ConnectionProfile {
+                    uuid: fields[0].clone(),
+                    name: fields[1].clone(),
+                    conn_type: fields[2].clone(),
+                    active: fields[3] == "yes",
+                    device: if fields[4] == "--" {
+                        String::new()
+                    } else {
+                        fields[4].clone()
+                    },
+                })
+            })
+            .collect())
+    }
+
+    async fn activate_connection(&self, id: &str) -> Result<(), String> {
+        nmcli(&["connection", "up", id]).await.map(|_| ())
+    }
+
+    async fn deactivate_connection(&self, id: &str) -> Result<(), String> {
+        nmcli(&["connection", "down", id]).await.map(|_| ())
+    }
+
+    async fn connect_wifi(&self, ssid: &str, password: Option<&str>) -> Result<(), String> {
+        let mut args = vec!["device", "wifi", "connect", ssid];
+        if let Some(password) = password {
+            args.extend(["password", password]);
+        }
+        nmcli(&args).await.map(|_| ())
+    }
+
+    async fn connection_settings(
+        &self,
+        id: &str,
+        properties: &[&str],
+    ) -> Result<HashMap<String, String>, String> {
+        show_settings(id, properties, false).await
+    }
+
+    async fn connection_secrets(
+        &self,
+        id: &str,
+        properties: &[&str],
+    ) -> Result<HashMap<String, String>, String> {
+        show_settings(id, properties, true).await
+    }
+
+    async fn add_connection(&self, settings: &[(String, String)]) -> Result<String, String> {
+        let mut args = vec!["connection", "add"];
+        for (prop, value) in settings {
+            args.push(prop);
+            args.push(value);
+        }
+        added_uuid(&nmcli(&args).await?)
+    }
+
+    async fn delete_connection(&self, id: &str) -> Result<(), String> {
+        nmcli(&["connection", "delete", id]).await.map(|_| ())
+    }
+
+    async fn modify_connection(
+        &self,
+        id: &str,
+        changes: &[(String, String)],
+    ) -> Result<(), String> {
+        let mut args = vec!["connection", "modify", id];
+        for (prop, value) in changes {
+            args.push(prop);
+            args.push(value);
+        }
+        nmcli(&args).await.map(|_| ())
+    }
+
+    async fn import_connection(&self, vpn_type: &str, file_path: &str) -> Result<String, String> {
+        added_uuid(&nmcli(&["connection", "import", "type", vpn_type, "file", file_path]).await?)
+    }
+
+    async fn export_connection(&self, id: &str, file_path: &str) -> Result<(), String> {
+        nmcli(&["connection", "export", id, file_path])
+            .await
+            .map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_fields_by_name() {
+        let stdout = "ipv4.method:manual\n\
+                      ipv4.addresses:192.168.1.50/24\n\
+                      ipv6.method:auto\n\
+                      IP4.ADDRESS[1]:192.168.1.50/24\n\
+                      IP4.ADDRESS[2]:10.0.0.2/8\n\
+                      IP6.ADDRESS[1]:fe80\\:\\:1/64\n";
+        let fields = parse_fields(
+            stdout,
+            &[
+                "ipv4.method",
+                "ipv4.addresses",
+                "ipv6.method",
+                "IP4.ADDRESS",
+                "IP6.ADDRESS",
+            ],
+        );
+        assert_eq!(fields["ipv4.method"], "manual");
+        assert_eq!(fields["ipv6.method"], "auto");
+        assert_eq!(fields["IP4.ADDRESS"], "192.168.1.50/24 | 10.0.0.2/8");
+        assert_eq!(fields["IP6.ADDRESS"], "fe80::1/64");
+    }
+
+    #[test]
+    fn leaves_missing_runtime_fields_empty() {
+        // An inactive connection prints no IP4/IP6 lines at all
+        let stdout = "ipv4.method:auto\nipv4.gateway:\nipv6.method:ignore\n";
+        let fields = parse_fields(
+            stdout,
+            &["ipv4.method", "IP4.ADDRESS", "ipv4.gateway", "ipv6.method"],
+        );
+        assert_eq!(fields["ipv4.method"], "auto");
+        assert_eq!(fields["IP4.ADDRESS"], "");
+        assert_eq!(fields["ipv4.gateway"], "");
+        assert_eq!(fields["ipv6.method"], "ignore");
+    }
+
+    #[test]
+    fn extracts_added_uuids() {
+        assert_eq!(
+            added_uuid("Connection 'Home (5G)' (0b1c2d3e-aaaa-bbbb-cccc-000000000001) successfully added.\n"),
+            Ok("0b1c2d3e-aaaa-bbbb-cccc-000000000001".to_string())
+        );
+        assert!(added_uuid("Warning: nothing happened").is_err());
+    }
+}
@@ -0,0 +1,216 @@
+use crate::modules::systems::nm::cli::NmcliClient;
+use crate::modules::systems::nm::dbus::DbusClient;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// A Wi-Fi access point as seen by the last scan.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct AccessPoint {
+    pub ssid: String,
+    pub bssid: String,
+    pub security: String, // nmcli-style label, e.g. "WPA2", "WPA1 WPA2", "" for open networks
+    pub signal: u8,       // 0-100
+    pub frequency: u32,   // MHz
+    pub max_bitrate: u32, // kbit/s
+    pub wpa_flags: String, // nmcli-style flag list, e.g. "pair_tkip group_tkip psk", "" when none
+    pub rsn_flags: String, // e.g. "pair_ccmp group_ccmp psk sae"
+    pub last_seen: Option<u32>, // seconds since the AP was last seen, None when unknown
+    pub device: String,   // interface the AP was seen on
+    pub active: bool,
+}
+
+/// A saved connection profile and whether it is currently active.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ConnectionProfile {
+    pub uuid: String,
+    pub name: String,
+    pub conn_type: String, // NM setting name, e.g. "802-11-wireless", "vpn", "wireguard"
+    pub active: bool,
+    pub device: String, // interface of the active connection, empty when inactive
+}
+
+/// A network device and its link state.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct DeviceInfo {
+    pub interface: String,
+    pub device_type: String, // e.g. "ethernet", "wifi", "wireguard"
+    pub state: String,       // e.g. "connected", "connecting", "disconnected", "unavailable"
+    pub mac: String,
+    pub speed: Option<u32>, // Mb/s, wired devices with a link only
+    pub carrier: bool,      // a cable is plugged in (wired devices)
+    pub connection: String, // name of the active connection, empty when none
+}
+
+/// Operations the Wi-Fi and VPN commands need from NetworkManager.
+///
+/// Connections are addressed by UUID or name, the same identifiers `nmcli` accepts.
+/// Settings are read and written using nmcli property names (e.g. `ipv4.method`)
+/// and their string representations, regardless of the backend.
+#[async_trait]
+pub trait NetworkManager: Send + Sync {
+    /// Returns whether the Wi-Fi radio is enabled.
+    async fn wireless_enabled(&self) -> Result<bool, String>;
+
+    /// Switches the Wi-Fi radio on or off.
+    async fn set_wireless_enabled(&self, enabled: bool) -> Result<(), String>;
+
+    /// Asks the Wi-Fi devices to rescan. Results show up in later `access_points` calls.
+    async fn request_scan(&self) -> Result<(), String>;
+
+    /// Lists the access points visible to all Wi-Fi devices, one entry per BSSID.
+    async fn access_points(&self) -> Result<Vec<AccessPoint>, String>;
+
+    /// Returns the current transmit bitrate of a Wi-Fi device in kbit/s, None when not connected.
+    async fn wifi_bitrate(&self, interface: &str) -> Result<Option<u32>, String>;
+
+    /// Lists network devices with their state.
+    async fn devices(&self) -> Result<Vec<DeviceInfo>, String>;
+
+    /// Lists saved connection profiles.
+    async fn connections(&self) -> Result<Vec<ConnectionProfile>, String>;
+
+    /// Activates a saved connection and waits until it is up.
+    async fn activate_connection(&self, id: &str) -> Result<(), String>;
+
+    /// Deactivates an active connection.
+    async fn deactivate_connection(&self, id: &str) -> Result<(), String>;
+
+    /// Connects to a Wi-Fi network, creating or updating its profile when a password is given.
+    async fn connect_wifi(&self, ssid: &str, password: Option<&str>) -> Result<(), String>;
+
+    /// Reads settings of a connection, keyed by property name.
+    /// Properties without a value are returned as empty strings.
+    async fn connection_settings(
+        &self,
+        id: &str,
+        properties: &[&str],
+    ) -> Result<HashMap<String, String>, String>;
+
+    /// Like `connection_settings`, but also returns stored secrets such as `802-11-wireless-security.psk`.
+    async fn connection_secrets(
+        &self,
+        id: &str,
+        properties: &[&str],
+    ) -> Result<HashMap<String, String>, String>;
+
+    /// Creates a connection profile from nmcli properties (including `connection.type`)
+    /// and returns its UUID.
+    async fn add_connection(&self, settings: &[(String, String)]) -> Result<String, String>;
+
+    /// Deletes a saved connection profile.
+    async fn delete_connection(&self, id: &str) -> Result<(), String>;
+
+    /// Changes settings of a saved connection. Properties prefixed with `+` or `-` add or remove items.
+    async fn modify_connection(&self, id: &str, changes: &[(String, String)])
+        -> Result<(), String>;
+
+    /// Imports a connection from a file through a VPN plugin and returns its UUID.
+    async fn import_connection(&self, vpn_type: &str, file_path: &str) -> Result<String, String>;
+
+    /// Exports a VPN connection to a file in its plugin's native format (e.g. `.ovpn`).
+    async fn export_connection(&self, id: &str, file_path: &str) -> Result<(), String>;
+}
+
+/// Connects to NetworkManager over D-Bus, falling back to `nmcli` when the bus is unavailable.
+pub async fn network_manager() -> Box<dyn NetworkManager> {
+    match DbusClient::connect().await {
+        Ok(client) => Box::new(client),
+        Err(_) => Box::new(NmcliClient),
+    }
+}
+
+/// Maps a signal strength to the bar glyphs nmcli prints.
+pub fn signal_bars(signal: u8) -> String {
+    let bars = match signal {
+        s if s > 80 => 4,
+        s if s > 55 => 3,
+        s if s > 30 => 2,
+        s if s > 5 => 1,
+        _ => 0,
+    };
+    ["▂", "▄", "▆", "█"]
+        .iter()
+        .enumerate()
+        .map(|(i, glyph)| if i < bars { *glyph } else { "_" })
+        .collect()
+}
+
+/// Maps a Wi-Fi frequency in MHz to its channel number, 0 when unknown.
+pub fn frequency_channel(frequency: u32) -> u32 {
+    match frequency {
+        2484 => 14,
+        2412..=2472 => (frequency - 2407) / 5,
+        5000..=5925 => (frequency - 5000) / 5,
+        // 6 GHz channel 2 sits below channel 1 (5955 MHz)
+        5935 => 2,
+        5955..=7115 => (frequency - 5950) / 5,
+        _ => 0,
+    }
+}
+
+/// Names the band a Wi-Fi frequency in MHz belongs to.
+pub fn frequency_band(frequency: u32) -> &'static str {
+    match frequency {
+        2400..=2500 => "2.4 GHz",
+        5000..=5925 => "5 GHz",
+        5926..=7125 => "6 GHz",
+        _ => "",
+    }
+}
+
+/// Splits a line of `nmcli -t` output on unescaped colons and removes the escaping.
+pub fn split_terse(line: &str) -> Vec<String> {
+    let mut fields = vec![String::new()];
+    let mut chars = line.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                if let Some(next) = chars.next() {
+                    fields.last_mut().unwrap().push(next);
+                }
+            }
+            ':' => fields.push(String::new()),
+            _ => fields.last_mut().unwrap().push(c),
+        }
+    }
+    fields
+}
+
+/// Removes nmcli's backslash escaping from a single value.
+pub fn unescape_terse(value: &str) -> String {
+    split_terse(value).join(":")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_terse_lines_on_unescaped_colons() {
+        assert_eq!(
+            split_terse("*:Home:AA\\:BB\\:CC\\:00\\:11\\:22:WPA2"),
+            vec!["*", "Home", "AA:BB:CC:00:11:22", "WPA2"]
+        );
+        assert_eq!(split_terse("a\\\\b:"), vec!["a\\b", ""]);
+        assert_eq!(split_terse(""), vec![""]);
+    }
+
+    #[test]
+    fn unescapes_single_values() {
+        assert_eq!(unescape_terse("fe80\\:\\:1/64"), "fe80::1/64");
+        assert_eq!(unescape_terse("fe80::1/64"), "fe80::1/64");
+        assert_eq!(unescape_terse("C\\\\D"), "C\\D");
+    }
+
+    #[test]
+    fn maps_frequencies_to_channels() {
+        assert_eq!(frequency_channel(2412), 1);
+        assert_eq!(frequency_channel(2484), 14);
+        assert_eq!(frequency_channel(5180), 36);
+        assert_eq!(frequency_channel(5955), 1);
+        assert_eq!(frequency_band(5955), "6 GHz");
+        assert_eq!(signal_bars(60), "▂▄▆_");
+    }
+}
@@ -0,0 +1,813 @@
+use crate::modules::systems::nm::cli::NmcliClient;
+use crate::modules::systems::nm::client::{
+    AccessPoint, ConnectionProfile, DeviceInfo, NetworkManager,
+};
+use crate::modules::systems::nm::properties::{
+    apply_changes, format_addresses, merge_secrets, read_property, write_property, Settings,
+    SECRET_SETTINGS,
+};
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::net::Ipv6Addr;
+use std::time::Duration;
+use tokio::sync::OnceCell;
+use zbus::proxy::CacheProperties;
+use zbus::zvariant::{ObjectPath, OwnedObjectPath, OwnedValue, Value};
+use zbus::{Connection, Proxy};
+
+const NM_SERVICE: &str = "org.freedesktop.NetworkManager";
+const NM_PATH: &str = "/org/freedesktop/NetworkManager";
+const NM_IFACE: &str = "org.freedesktop.NetworkManager";
+const SETTINGS_PATH: &str = "/org/freedesktop/NetworkManager/Settings";
+const SETTINGS_IFACE: &str = "org.freedesktop.NetworkManager.Settings";
+const CONNECTION_IFACE: &str = "org.freedesktop.NetworkManager.Settings.Connection";
+const ACTIVE_IFACE: &str = "org.freedesktop.NetworkManager.Connection.Active";
+const DEVICE_IFACE: &str = "org.freedesktop.NetworkManager.Device";
+const WIRED_IFACE: &str = "org.freedesktop.NetworkManager.Device.Wired";
+const WIRELESS_IFACE: &str = "org.freedesktop.NetworkManager.Device.Wireless";
+const AP_IFACE: &str = "org.freedesktop.NetworkManager.AccessPoint";
+const IP4_CONFIG_IFACE: &str = "org.freedesktop.NetworkManager.IP4Config";
+const IP6_CONFIG_IFACE: &str = "org.freedesktop.NetworkManager.IP6Config";
+const PROPERTIES_IFACE: &str = "org.freedesktop.DBus.Properties";
+
+/// `NM_DEVICE_TYPE_ETHERNET` and `NM_DEVICE_TYPE_WIFI`
+const DEVICE_TYPE_ETHERNET: u32 = 1;
+const DEVICE_TYPE_WIFI: u32 = 2;
+
+/// `NM_ACTIVE_CONNECTION_STATE_*` values.
+const ACTIVE_STATE_ACTIVATED: u32 = 2;
+const ACTIVE_STATE_DEACTIVATING: u32 = 3;
+const ACTIVE_STATE_DEACTIVATED: u32 = 4;
+
+/// `NM_802_11_AP_FLAGS_PRIVACY` and the `NM_802_11_AP_SEC_KEY_MGMT_*` bits.
+const AP_FLAGS_PRIVACY: u32 = 0x1;
+const KEY_MGMT_PSK: u32 = 0x100;
+const KEY_MGMT_802_1X: u32 = 0x200;
+const KEY_MGMT_SAE: u32 = 0x400;
+const KEY_MGMT_OWE: u32 = 0x800;
+
+/// `NM_802_11_AP_SEC_*` bits with the names nmcli prints for them.
+const AP_SEC_FLAG_NAMES: [(u32, &str); 14] = [
+    (0x1, "pair_wep40"),
+    (0x2, "pair_wep104"),
+    (0x4, "pair_tkip"),
+    (0x8, "pair_ccmp"),
+    (0x10, "group_wep40"),
+    (0x20, "group_wep104"),
+    (0x40, "group_tkip"),
+    (0x80, "group_ccmp"),
+    (KEY_MGMT_PSK, "psk"),
+    (KEY_MGMT_802_1X, "802.1X"),
+    (KEY_MGMT_SAE, "sae"),
+    (KEY_MGMT_OWE, "owe"),
+    (0x1000, "owe_transition_mode"),
+    (0x2000, "eap_suite_b_192"),
+];
+
+/// How often and how long activation is polled before giving up.
+const ACTIVATION_POLL: Duration = Duration::from_millis(500);
+const ACTIVATION_TIMEOUT_POLLS: u32 = 90;
+
+/// The system bus connection, shared by all commands.
+static SYSTEM_BUS: OnceCell<Connection> = OnceCell::const_new();
+
+/// NetworkManager backend talking to `org.freedesktop.NetworkManager` on the system bus.
+///
+/// Profile settings are translated from and to nmcli's property syntax, which the editors use.
+/// Only importing and exporting VPN files goes through nmcli, see `import_connection`.
+pub struct DbusClient {
+    conn: Connection,
+}
+
+/// Converts a D-Bus error into the string errors the commands return.
+fn dbus_err(e: impl std::fmt::Display) -> String {
+    format!("NetworkManager D-Bus error: {}", e)
+}
+
+/// Takes a typed value out of a property map.
+fn take<T>(props: &mut HashMap<String, OwnedValue>, key: &str) -> Option<T>
+where
+    T: TryFrom<OwnedValue>,
+{
+    props.remove(key).and_then(|v| T::try_from(v).ok())
+}
+
+/// Reads a string setting such as `connection.id` from connection settings.
+fn setting_str(settings: &Settings, group: &str, key: &str) -> String {
+    settings
+        .get(group)
+        .and_then(|g| g.get(key))
+        .and_then(|v| v.try_clone().ok())
+        .and_then(|v| String::try_from(v).ok())
+        .unwrap_or_default()
+}
+
+/// Reads the SSID of a Wi-Fi connection's settings.
+fn setting_ssid(settings: &Settings) -> Option<String> {
+    let value = settings
+        .get("802-11-wireless")?
+        .get("ssid")?
+        .try_clone()
+        .ok()?;
+    let bytes: Vec<u8> = value.try_into().ok()?;
+    Some(String::from_utf8_lossy(&bytes).to_string())
+}
+
+/// Generates a random UUID for a new connection profile.
+fn new_uuid() -> Result<String, String> {
+    std::fs::read_to_string("/proc/sys/kernel/random/uuid")
+        .map(|uuid| uuid.trim().to_string())
+        .map_err(|e| format!("Could not generate a connection UUID: {}", e))
+}
+
+/// Returns true for runtime values such as `IP4.ADDRESS`, which are not profile settings.
+fn is_runtime_property(property: &str) -> bool {
+    property.starts_with("IP4.") || property.starts_with("IP6.")
+}
+
+/// Builds the security label nmcli shows for an access point's flags.
+pub fn security_label(flags: u32, wpa_flags: u32, rsn_flags: u32) -> String {
+    let mut parts = Vec::new();
+    if flags & AP_FLAGS_PRIVACY != 0 && wpa_flags == 0 && rsn_flags == 0 {
+        parts.push("WEP");
+    }
+    if wpa_flags != 0 {
+        parts.push("WPA1");
+    }
+    if rsn_flags & (KEY_MGMT_PSK | KEY_MGMT_802_1X) != 0 {
+        parts.push("WPA2");
+    }
+    if rsn_flags & KEY_MGMT_SAE != 0 {
+        parts.push("WPA3");
+    }
+    if rsn_flags & KEY_MGMT_OWE != 0 {
+        parts.push("OWE");
+    }
+    if (wpa_flags | rsn_flags) & KEY_MGMT_802_1X != 0 {
+        parts.push("802.1X");
+    }
+    parts.join(" ")
+}
+
+/// Lists the names of the set `NM_802_11_AP_SEC_*` bits, space separated like nmcli.
+fn ap_flag_names(flags: u32) -> String {
+    AP_SEC_FLAG_NAMES
+        .iter()
+        .filter(|(bit, _)| flags & bit != 0)
+        .map(|(_, name)| *name)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Seconds since boot on `CLOCK_BOOTTIME`, the clock of the AP `LastSeen` property.
+fn seconds_since_boot() -> Option<u64> {
+    std::fs::read_to_string("/proc/uptime")
+        .ok()?
+        .split('.')
+        .next()?
+        .parse()
+        .ok()
+}
+
+/// Names a `NM_DEVICE_TYPE_*` value the way nmcli does.
+fn device_type_name(device_type: u32) -> &'static str {
+    match device_type {
+        1 => "ethernet",
+        2 => "wifi",
+        5 => "bt",
+        10 => "bond",
+        11 => "vlan",
+        13 => "bridge",
+        14 => "generic",
+        16 => "tun",
+        29 => "wireguard",
+        32 => "loopback",
+        _ => "unknown",
+    }
+}
+
+/// Names a `NM_DEVICE_STATE_*` value using the first word nmcli prints.
+fn device_state_name(state: u32) -> &'static str {
+    match state {
+        10 => "unmanaged",
+        20 => "unavailable",
+        30 => "disconnected",
+        40..=90 => "connecting",
+        100 => "connected",
+        110 => "deactivating",
+        120 => "failed",
+        _ => "unknown",
+    }
+}
+
+impl DbusClient {
+    /// Connects to the system bus and checks that NetworkManager is running.
+    pub async fn connect() -> Result<Self, String> {
+        let conn = SYSTEM_BUS
+            .get_or_try_init(Connection::system)
+            .await
+            .map_err(dbus_err)?
+            .clone();
+        let client = Self { conn };
+
+        client
+            .proxy(NM_PATH, NM_IFACE)
+            .await?
+            .get_property::<String>("Version")
+            .await
+            .map_err(dbus_err)?;
+        Ok(client)
+    }
+
+    /// Creates an uncached proxy for an object and interface.
+    async fn proxy<'a>(&self, path: &'a str, interface: &'a str) -> Result<Proxy<'a>, String> {
+        zbus::proxy::Builder::<Proxy>::new(&self.conn)
+            .destination(NM_SERVICE)
+            .and_then(|b| b.path(path))
+            .and_then(|b| b.interface(interface))
+            .map_err(dbus_err)?
+            .cache_properties(CacheProperties::No)
+            .build()
+            .await
+            .map_err(dbus_err)
+    }
+
+    /// Fetches all properties of an interface in a single call.
+    async fn get_all(
+        &self,
+        path: &str,
+        interface: &str,
+    ) -> Result<HashMap<String, OwnedValue>, String> {
+        self.proxy(path, PROPERTIES_IFACE)
+            .await?
+            .call("GetAll", &(interface,))
+            .await
+            .map_err(dbus_err)
+    }
+
+    /// Returns the object paths of all Wi-Fi devices.
+    async fn wifi_devices(&self) -> Result<Vec<OwnedObjectPath>, String> {
+        let devices: Vec<OwnedObjectPath> = self
+            .proxy(NM_PATH, NM_IFACE)
+            .await?
+            .call("GetDevices", &())
+            .await
+            .map_err(dbus_err)?;
+
+        let mut wifi = Vec::new();
+        for device in devices {
+            let device_type: u32 = self
+                .proxy(device.as_str(), DEVICE_IFACE)
+                .await?
+                .get_property("DeviceType")
+                .await
+                .map_err(dbus_err)?;
+            if device_type == DEVICE_TYPE_WIFI {
+                wifi.push(device);
+            }
+        }
+        Ok(wifi)
+    }
+
+    /// Returns every saved connection with its settings.
+    async fn saved_connections(&self) -> Result<Vec<(OwnedObjectPath, Settings)>, String> {
+        let paths: Vec<OwnedObjectPath> = self
+            .proxy(SETTINGS_PATH, SETTINGS_IFACE)
+            .await?
+            .call("ListConnections", &())
+            .await
+            .map_err(dbus_err)?;
+
+        let mut connections = Vec::new();
+        for path in paths {
+            let settings: Settings = self
+                .proxy(path.as_str(), CONNECTION_IFACE)
+                .await?
+                .call("GetSettings", &())
+                .await
+                .map_err(dbus_err)?;
+            connections.push((path, settings));
+        }
+        Ok(connections)
+    }
+
+    /// Finds a saved connection by UUID or name.
+    async fn find_connection(&self, id: &str) -> Result<(OwnedObjectPath, Settings), String> {
+        self.saved_connections()
+            .await?
+            .into_iter()
+            .find(|(_, s)| {
+                setting_str(s, "connection", "uuid") == id
+                    || setting_str(s, "connection", "id") == id
+            })
+            .ok_or_else(|| format!("Connection '{}' not found", id))
+    }
+
+    /// Returns active connections as `(path, uuid, name, device interface)`.
+    async fn active_connections(
+        &self,
+    ) -> Result<Vec<(OwnedObjectPath, String, String, String)>, String> {
+        let paths: Vec<OwnedObjectPath> = self
+            .proxy(NM_PATH, NM_IFACE)
+            .await?
+            .get_property("ActiveConnections")
+            .await
+            .map_err(dbus_err)?;
+
+        let mut active = Vec::new();
+        for path in paths {
+            // Active connections can vanish between listing and reading them
+            let Ok(mut props) = self.get_all(path.as_str(), ACTIVE_IFACE).await else {
+                continue;
+            };
+            let uuid: String = take(&mut props, "Uuid").unwrap_or_default();
+            let name: String = take(&mut props, "Id").unwrap_or_default();
+            let devices: Vec<OwnedObjectPath> = take(&mut props, "Devices").unwrap_or_default();
+
+            let mut interface = String::new();
+            if let Some(device) = devices.first() {
+                interface = self
+                    .proxy(device.as_str(), DEVICE_IFACE)
+                    .await?
+                    .get_property("Interface")
+                    .await
+                    .unwrap_or_default();
+            }
+            active.push((path, uuid, name, interface));
+        }
+        Ok(active)
+    }
+
+    /// Adds the stored secrets of the named settings. Settings without secrets, or whose
+    /// secrets are kept by an agent that is not running, are left as they are.
+    async fn add_secrets(
+        &self,
+        path: &OwnedObjectPath,
+        settings: &mut Settings,
+        names: &[&str],
+    ) -> Result<(), String> {
+        let proxy = self.proxy(path.as_str(), CONNECTION_IFACE).await?;
+        for name in names {
+            if let Ok(secrets) = proxy.call::<_, _, Settings>("GetSecrets", &(*name,)).await {
+                merge_secrets(settings, secrets);
+            }
+        }
+        Ok(())
+    }
+
+    /// Reads the addresses, gateway and DNS servers in use by an active connection,
+    /// keyed like nmcli's `IP4.ADDRESS`. Empty when the connection is not active.
+    async fn runtime_ip(&self, uuid: &str) -> Result<HashMap<String, String>, String> {
+        let mut values = HashMap::new();
+        let Some((path, _, _, _)) = self
+            .active_connections()
+            .await?
+            .into_iter()
+            .find(|(_, active_uuid, _, _)| active_uuid == uuid)
+        else {
+            return Ok(values);
+        };
+        let mut active = self.get_all(path.as_str(), ACTIVE_IFACE).await?;
+
+        for (prefix, property, interface) in [
+            ("IP4", "Ip4Config", IP4_CONFIG_IFACE),
+            ("IP6", "Ip6Config", IP6_CONFIG_IFACE),
+        ] {
+            // "/" until the connection has an address of the family
+            let Some(config) =
+                take::<OwnedObjectPath>(&mut active, property).filter(|p| p.as_str() != "/")
+            else {
+                continue;
+            };
+            let mut props = self.get_all(config.as_str(), interface).await?;
+            let addresses: Vec<HashMap<String, OwnedValue>> =
+                take(&mut props, "AddressData").unwrap_or_default();
+            let gateway: String = take(&mut props, "Gateway").unwrap_or_default();
+            let dns: Vec<String> = if prefix == "IP4" {
+                take::<Vec<HashMap<String, OwnedValue>>>(&mut props, "NameserverData")
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter_map(|mut server| take(&mut server, "address"))
+                    .collect()
+            } else {
+                take::<Vec<Vec<u8>>>(&mut props, "Nameservers")
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter_map(|bytes| <[u8; 16]>::try_from(bytes).ok())
+                    .map(|bytes| Ipv6Addr::from(bytes).to_string())
+                    .collect()
+            };
+
+            // nmcli separates runtime values with " | "
+            values.insert(
+                format!("{}.ADDRESS", prefix),
+                format_addresses(&addresses, " | "),
+            );
+            values.insert(format!("{}.GATEWAY", prefix), gateway);
+            values.insert(format!("{}.DNS", prefix), dns.join(" | "));
+        }
+        Ok(values)
+    }
+
+    /// Reads connection properties, optionally including the secrets of the settings they belong to.
+    async fn read_settings(
+        &self,
+        id: &str,
+        properties: &[&str],
+        secrets: bool,
+    ) -> Result<HashMap<String, String>, String> {
+        let (path, mut settings) = self.find_connection(id).await?;
+        if secrets {
+            let names: Vec<&str> = SECRET_SETTINGS
+                .into_iter()
+                .filter(|name| {
+                    properties
+                        .iter()
+                        .any(|p| p.split('.').next() == Some(*name))
+                })
+                .collect();
+            self.add_secrets(&path, &mut settings, &names).await?;
+        }
+
+        let runtime = if properties.iter().any(|p| is_runtime_property(p)) {
+            self.runtime_ip(&setting_str(&settings, "connection", "uuid"))
+                .await?
+        } else {
+            HashMap::new()
+        };
+
+        properties
+            .iter()
+            .map(|prop| {
+                let value = if is_runtime_property(prop) {
+                    runtime.get(*prop).cloned().unwrap_or_default()
+                } else {
+                    read_property(&settings, prop)?
+                };
+                Ok((prop.to_string(), value))
+            })
+            .collect()
+    }
+
+    /// Waits until an active connection reaches the activated state.
+    async fn wait_for_activation(&self, active: &OwnedObjectPath) -> Result<(), String> {
+        let proxy = self.proxy(active.as_str(), ACTIVE_IFACE).await?;
+
+        for _ in 0..ACTIVATION_TIMEOUT_POLLS {
+            match proxy.get_property::<u32>("State").await {
+                Ok(ACTIVE_STATE_ACTIVATED) => return Ok(()),
+                Ok(ACTIVE_STATE_DEACTIVATING | ACTIVE_STATE_DEACTIVATED) | Err(_) => {
+                    return Err("Connection activation failed".to_string())
+                }
+                Ok(_) => tokio::time::sleep(ACTIVATION_POLL).await,
+            }
+        }
+        Err("Timed out waiting for the connection to activate".to_string())
+    }
+}
+
+#[async_trait]
+impl NetworkManager for DbusClient {
+    async fn wireless_enabled(&self) -> Result<bool, String> {
+        self.proxy(NM_PATH, NM_IFACE)
+            .await?
+            .get_property("WirelessEnabled")
+            .await
+            .map_err(dbus_err)
+    }
+
+    async fn set_wireless_enabled(&self, enabled: bool) -> Result<(), String> {
+        self.proxy(NM_PATH, NM_IFACE)
+            .await?
+            .set_property("WirelessEnabled", enabled)
+            .await
+            .map_err(dbus_err)
+    }
+
+    async fn request_scan(&self) -> Result<(), String> {
+        let mut result = Err("No Wi-Fi device found".to_string());
+        for device in self.wifi_devices().await? {
+            let options: HashMap<&str, Value> = HashMap::new();
+            // NetworkManager rejects scans requested too often; any accepted scan is enough
+            let scan: Result<(), String> = self
+                .proxy(device.as_str(), WIRELESS_IFACE)
+                .await?
+                .call("RequestScan", &(options,))
+                .await
+                .map_err(dbus_err);
+            if result.is_err() {
+                result = scan;
+            }
+        }
+        result
+    }
+
+    async fn access_points(&self) -> Result<Vec<AccessPoint>, String> {
+        let mut access_points = Vec::new();
+        let now = seconds_since_boot();
+
+        for device in self.wifi_devices().await? {
+            let interface: String = self
+                .proxy(device.as_str(), DEVICE_IFACE)
+                .await?
+                .get_property("Interface")
+                .await
+                .map_err(dbus_err)?;
+            let wireless = self.proxy(device.as_str(), WIRELESS_IFACE).await?;
+            let paths: Vec<OwnedObjectPath> = wireless
+                .call("GetAllAccessPoints", &())
+                .await
+                .map_err(dbus_err)?;
+            let active_ap: Option<OwnedObjectPath> =
+                wireless.get_property("ActiveAccessPoint").await.ok();
+
+            for path in paths {
+                let Ok(mut props) = self.get_all(path.as_str(), AP_IFACE).await else {
+                    continue;
+                };
+                let ssid: Vec<u8> = take(&mut props, "Ssid").unwrap_or_default();
+                let flags: u32 = take(&mut props, "Flags").unwrap_or(0);
+                let wpa_flags: u32 = take(&mut props, "WpaFlags").unwrap_or(0);
+                let rsn_flags: u32 = take(&mut props, "RsnFlags").unwrap_or(0);
+                // -1 when the AP was never seen by a scan
+                let last_seen: i32 = take(&mut props, "LastSeen").unwrap_or(-1);
+
+                access_points.push(AccessPoint {
+                    ssid: String::from_utf8_lossy(&ssid).to_string(),
+                    bssid: take(&mut props, "HwAddress").unwrap_or_default(),
+                    security: security_label(flags, wpa_flags, rsn_flags),
+                    signal: take(&mut props, "Strength").unwrap_or(0),
+                    frequency: take(&mut props, "Frequency").unwrap_or(0),
+                    max_bitrate: take(&mut props, "MaxBitrate").unwrap_or(0),
+                    wpa_flags: ap_flag_names(wpa_flags),
+                    rsn_flags: ap_flag_names(rsn_flags),
+                    last_seen: match (now, u64::try_from(last_seen)) {
+                        (Some(now), Ok(seen)) => u32::try_from(now.saturating_sub(seen)).ok(),
+                        _ => None,
+                    },
+                    device: interface.clone(),
+                    active: active_ap.as_ref() == Some(&path),
+                });
+            }
+        }
+        Ok(access_points)
+    }
+
+    async fn wifi_bitrate(&self, interface: &str) -> Result<Option<u32>, String> {
+        for device in self.wifi_devices().await? {
+            let name: String = self
+                .proxy(device.as_str(), DEVICE_IFACE)
+                .await?
+                .get_property("Interface")
+                .await
+                .map_err(dbus_err)?;
+            if name != interface {
+                continue;
+            }
+
+            let mut props = self.get_all(device.as_str(), WIRELESS_IFACE).await?;
+            let active_ap: Option<OwnedObjectPath> = take(&mut props, "ActiveAccessPoint");
+            let bitrate: u32 = take(&mut props, "Bitrate").unwrap_or(0);
+            // "/" when not associated
+            return Ok(active_ap.filter(|ap| ap.as_str() != "/").map(|_| bitrate));
+        }
+        Err(format!("No Wi-Fi device named '{}'", interface))
+    }
+
+    async fn devices(&self) -> Result<Vec<DeviceInfo>, String> {
+        let paths: Vec<OwnedObjectPath> = self
+            .proxy(NM_PATH, NM_IFACE)
+            .await?
+            .call("GetDevices", &())
+            .await
+            .map_err(dbus_err)?;
+        let active = self.active_connections().await?;
+
+        let mut devices = Vec::new();
+        for path in paths {
+            let Ok(mut props) = self.get_all(path.as_str(), DEVICE_IFACE).await else {
+                continue;
+            };
+            let device_type: u32 = take(&mut props, "DeviceType").unwrap_or(0);
+            let state: u32 = take(&mut props, "State").unwrap_or(0);
+            let active_path: Option<OwnedObjectPath> = take(&mut props, "ActiveConnection");
+
+            let mut info = DeviceInfo {
+                interface: take(&mut props, "Interface").unwrap_or_default(),
+                device_type: device_type_name(device_type).to_string(),
+                state: device_state_name(state).to_string(),
+                mac: take(&mut props, "HwAddress").unwrap_or_default(),
+                speed: None,
+                carrier: false,
+                connection: active
+                    .iter()
+                    .find(|(p, _, _, _)| Some(p) == active_path.as_ref())
+                    .map(|(_, _, name, _)| name.clone())
+                    .unwrap_or_default(),
+            };
+
+            if device_type == DEVICE_TYPE_ETHERNET {
+                if let Ok(mut wired) = self.get_all(path.as_str(), WIRED_IFACE).await {
+                    info.speed = take::<u32>(&mut wired, "Speed").filter(|s| *s > 0);
+                    info.carrier = take(&mut wired, "Carrier").unwrap_or(false);
+                    if info.mac.is_empty() {
+                        info.mac = take(&mut wired, "HwAddress").unwrap_or_default();
+                    }
+                }
+            }
+            devices.push(info);
+        }
+        Ok(devices)
+    }
+
+    async fn connections(&self) -> Result<Vec<ConnectionProfile>, String> {
+        let active = self.active_connections().await?;
+
+        Ok(self
+            .saved_connections()
+            .await?
+            .into_iter()
+            .map(|(_, settings)| {
+                let uuid = setting_str(&settings, "connection", "uuid");
+                let device = active
+                    .iter()
+                    .find(|(_, active_uuid, _, _)| *active_uuid == uuid)
+                    .map(|(_, _, _, device)| device.clone());
+                ConnectionProfile {
+                    name: setting_str(&settings, "connection", "id"),
+                    conn_type: setting_str(&settings, "connection", "type"),
+                    active: device.is_some(),
+                    device: device.unwrap_or_default(),
+                    uuid,
+                }
+            })
+            .collect())
+    }
+
+    async fn activate_connection(&self, id: &str) -> Result<(), String> {
+        let (path, _) = self.find_connection(id).await?;
+        let root = ObjectPath::from_static_str_unchecked("/");
+        let active: OwnedObjectPath = self
+            .proxy(NM_PATH, NM_IFACE)
+            .await?
+            .call("ActivateConnection", &(path.as_ref(), &root, &root))
+            .await
+            .map_err(dbus_err)?;
+        self.wait_for_activation(&active).await
+    }
+
+    async fn deactivate_connection(&self, id: &str) -> Result<(), String> {
+        let (path, _, _, _) = self
+            .active_connections()
+            .await?
+            .into_iter()
+            .find(|(_, uuid, name, _)| uuid == id || name == id)
+            .ok_or_else(|| format!("Connection '{}' is not active", id))?;
+
+        self.proxy(NM_PATH, NM_IFACE)
+            .await?
+            .call::<_, _, ()>("DeactivateConnection", &(path.as_ref(),))
+            .await
+            .map_err(dbus_err)
+    }
+
+    async fn connect_wifi(&self, ssid: &str, password: Option<&str>) -> Result<(), String> {
+        // Reuse a saved profile for the network when there is one
+        let saved = self.saved_connections().await?.into_iter().find(|(_, s)| {
+            setting_ssid(s).as_deref() == Some(ssid) || setting_str(s, "connection", "id") == ssid
+        });
+        if let Some((_, settings)) = saved {
+            let uuid = setting_str(&settings, "connection", "uuid");
+            if let Some(password) = password {
+                self.modify_connection(
+                    &uuid,
+                    &[(
+                        "802-11-wireless-security.psk".to_string(),
+                        password.to_string(),
+                    )],
+                )
+                .await?;
+            }
+            return self.activate_connection(&uuid).await;
+        }
+
+        // Otherwise pick the strongest access point and let NetworkManager complete the profile
+        let mut best: Option<(OwnedObjectPath, OwnedObjectPath, u8)> = None;
+        for device in self.wifi_devices().await? {
+            let paths: Vec<OwnedObjectPath> = self
+                .proxy(device.as_str(), WIRELESS_IFACE)
+                .await?
+                .call("GetAllAccessPoints", &())
+                .await
+                .map_err(dbus_err)?;
+            for path in paths {
+                let Ok(mut props) = self.get_all(path.as_str(), AP_IFACE).await else {
+                    continue;
+                };
+                let ap_ssid: Vec<u8> = take(&mut props, "Ssid").unwrap_or_default();
+                let strength: u8 = take(&mut props, "Strength").unwrap_or(0);
+                if ap_ssid == ssid.as_bytes() && best.as_ref().is_none_or(|b| strength > b.2) {
+                    best = Some((device.clone(), path, strength));
+                }
+            }
+        }
+        let (device, ap, _) =
+            best.ok_or_else(|| format!("No network with name '{}' found", ssid))?;
+
+        let mut settings: HashMap<&str, HashMap<&str, Value>> = HashMap::new();
+        settings.insert(
+            "802-11-wireless",
+            HashMap::from([("ssid", Value::from(ssid.as_bytes().to_vec()))]),
+        );
+        if let Some(password) = password {
+            settings.insert(
+                "802-11-wireless-security",
+                HashMap::from([("psk", Value::from(password))]),
+            );
+        }
+
+        let (_, active): (OwnedObjectPath, OwnedObjectPath) = self
+            .proxy(NM_PATH, NM_IFACE)
+            .await?
+            .call(
+                "AddAndActivateConnection",
+                &(settings, device.as_ref(), ap.as_ref()),
+            )
+            .await
+            .map_err(dbus_err)?;
+        self.wait_for_activation(&active).await
+    }
+
+    async fn connection_settings(
+        &self,
+        id: &str,
+        properties: &[&str],
+    ) -> Result<HashMap<String, String>, String> {
+        self.read_settings(id, properties, false).await
+    }
+
+    async fn connection_secrets(
+        &self,
+        id: &str,
+        properties: &[&str],
+    ) -> Result<HashMap<String, String>, String> {
+        self.read_settings(id, properties, true).await
+    }
+
+    async fn add_connection(&self, settings: &[(String, String)]) -> Result<String, String> {
+        let uuid = new_uuid()?;
+        let mut profile = Settings::new();
+        write_property(&mut profile, "connection.uuid", &uuid)?;
+        apply_changes(&mut profile, settings)?;
+        // The setting named by the type must exist, even when all its values are defaults
+        let conn_type = setting_str(&profile, "connection", "type");
+        profile.entry(conn_type).or_default();
+
+        self.proxy(SETTINGS_PATH, SETTINGS_IFACE)
+            .await?
+            .call::<_, _, OwnedObjectPath>("AddConnection", &(profile,))
+            .await
+            .map_err(dbus_err)?;
+        Ok(uuid)
+    }
+
+    async fn delete_connection(&self, id: &str) -> Result<(), String> {
+        let (path, _) = self.find_connection(id).await?;
+        self.proxy(path.as_str(), CONNECTION_IFACE)
+            .await?
+            .call::<_, _, ()>("Delete", &())
+            .await
+            .map_err(dbus_err)
+    }
+
+    async fn modify_connection(
+        &self,
+        id: &str,
+        changes: &[(String, String)],
+    ) -> Result<(), String> {
+        let (path, mut settings) = self.find_connection(id).await?;
+        // Update replaces the whole profile, so secrets are read back first to keep them
+        let names: Vec<&str> = SECRET_SETTINGS
+            .into_iter()
+            .filter(|name| settings.contains_key(*name))
+            .collect();
+        self.add_secrets(&path, &mut settings, &names).await?;
+        apply_changes(&mut settings, changes)?;
+
+        self.proxy(path.as_str(), CONNECTION_IFACE)
+            .await?
+            .call::<_, _, ()>("Update", &(settings,))
+            .await
+            .map_err(dbus_err)
+    }
+
+    // NetworkManager has no D-Bus method for importing or exporting. Converting from and to a
+    // plugin's file format is done by the plugin's editor library, which nmcli loads.
+    async fn import_connection(&self, vpn_type: &str, file_path: &str) -> Result<String, String> {
+        NmcliClient.import_connection(vpn_type, file_path).await
+    }
+
+    async fn export_connection(&self, id: &str, file_path: &str) -> Result<(), String> {
+        NmcliClient.export_connection(id, file_path).await
+    }
+}
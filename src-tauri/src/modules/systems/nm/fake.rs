@@ -0,0 +1,249 @@
+use crate::modules::systems::nm::client::{
+    AccessPoint, ConnectionProfile, DeviceInfo, NetworkManager,
+};
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard};
+
+/// Everything the in-memory NetworkManager knows about.
+#[derive(Debug, Default, Clone)]
+pub struct FakeState {
+    pub wireless_enabled: bool,
+    pub access_points: Vec<AccessPoint>,
+    pub devices: Vec<DeviceInfo>,
+    pub connections: Vec<ConnectionProfile>,
+    /// Settings per connection UUID, keyed by nmcli property name.
+    pub settings: HashMap<String, HashMap<String, String>>,
+    pub scans_requested: u32,
+}
+
+/// In-memory NetworkManager for exercising the Wi-Fi and VPN logic without a running daemon.
+#[derive(Debug, Default)]
+pub struct FakeNetworkManager {
+    state: Mutex<FakeState>,
+}
+
+impl FakeNetworkManager {
+    pub fn new(state: FakeState) -> Self {
+        Self {
+            state: Mutex::new(state),
+        }
+    }
+
+    /// Gives access to the current state, e.g. to inspect it after a command ran.
+    pub fn state(&self) -> MutexGuard<'_, FakeState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl FakeState {
+    /// Finds a connection by UUID or name.
+    fn connection_mut(&mut self, id: &str) -> Result<&mut ConnectionProfile, String> {
+        self.connections
+            .iter_mut()
+            .find(|c| c.uuid == id || c.name == id)
+            .ok_or_else(|| format!("Connection '{}' not found", id))
+    }
+}
+
+#[async_trait]
+impl NetworkManager for FakeNetworkManager {
+    async fn wireless_enabled(&self) -> Result<bool, String> {
+        Ok(self.state().wireless_enabled)
+    }
+
+    async fn set_wireless_enabled(&self, enabled: bool) -> Result<(), String> {
+        let mut state = self.state();
+        state.wireless_enabled = enabled;
+        if !enabled {
+            state.access_points.clear();
+        }
+        Ok(())
+    }
+
+    async fn request_scan(&self) -> Result<(), String> {
+        self.state().scans_requested += 1;
+        Ok(())
+    }
+
+    async fn access_points(&self) -> Result<Vec<AccessPoint>, String> {
+        Ok(self.state().access_points.clone())
+    }
+
+    async fn wifi_bitrate(&self, interface: &str) -> Result<Option<u32>, String> {
+        let state = self.state();
+        Ok(state
+            .access_points
+            .iter()
+            .find(|ap| ap.active && ap.device == interface)
+            .map(|ap| ap.max_bitrate))
+    }
+
+    async fn devices(&self) -> Result<Vec<DeviceInfo>, String> {
+        Ok(self.state().devices.clone())
+    }
+
+    async fn connections(&self) -> Result<Vec<ConnectionProfile>, String> {
+        Ok(self.state().connections.clone())
+    }
+
+    async fn activate_connection(&self, id: &str) -> Result<(), String> {
+        let mut state = self.state();
+        let connection = state.connection_mut(id)?;
+        connection.active = true;
+        if connection.conn_type == "802-11-wireless" {
+            let name = connection.name.clone();
+            for ap in state.access_points.iter_mut() {
+                ap.active = ap.ssid == name;
+            }
+        }
+        Ok(())
+    }
+
+    async fn deactivate_connection(&self, id: &str) -> Result<(), String> {
+        let mut state = self.state();
+        let connection = state.connection_mut(id)?;
+        if !connection.active {
+            return Err(format!("Connection '{}' is not active", id));
+        }
+        connection.active = false;
+        connection.device.clear();
+        Ok(())
+    }
+
+    async fn connect_wifi(&self, ssid: &str, password: Option<&str>) -> Result<(), String> {
+        {
+            let mut state = self.state();
+            if !state.access_points.iter().any(|ap| ap.ssid == ssid) {
+                return Err(format!("No network with name '{}' found", ssid));
+            }
+
+            if state.connection_mut(ssid).is_err() {
+                let uuid = format!("fake-{}", state.connections.len() + 1);
+                state.connections.push(ConnectionProfile {
+                    uuid,
+                    name: ssid.to_string(),
+                    conn_type: "802-11-wireless".to_string(),
+                    active: false,
+                    device: String::new(),
+                });
+            }
+            let uuid = state.connection_mut(ssid)?.uuid.clone();
+            if let Some(password) = password {
+                state.settings.entry(uuid).or_default().insert(
+                    "802-11-wireless-security.psk".to_string(),
+                    password.to_string(),
+                );
+            }
+        }
+        self.activate_connection(ssid).await
+    }
+
+    async fn connection_settings(
+        &self,
+        id: &str,
+        properties: &[&str],
+    ) -> Result<HashMap<String, String>, String> {
+        let mut state = self.state();
+        let uuid = state.connection_mut(id)?.uuid.clone();
+        let settings = state.settings.get(&uuid).cloned().unwrap_or_default();
+        Ok(properties
+            .iter()
+            .map(|p| (p.to_string(), settings.get(*p).cloned().unwrap_or_default()))
+            .collect())
+    }
+
+    async fn connection_secrets(
+        &self,
+        id: &str,
+        properties: &[&str],
+    ) -> Result<HashMap<String, String>, String> {
+        // The fake keeps secrets alongside the other settings
+        self.connection_settings(id, properties).await
+    }
+
+    async fn add_connection(&self, settings: &[(String, String)]) -> Result<String, String> {
+        let mut state = self.state();
+        let uuid = format!("fake-{}", state.connections.len() + 1);
+        let settings: HashMap<String, String> = settings.iter().cloned().collect();
+
+        state.connections.push(ConnectionProfile {
+            uuid: uuid.clone(),
+            name: settings.get("connection.id").cloned().unwrap_or_default(),
+            conn_type: settings.get("connection.type").cloned().unwrap_or_default(),
+            active: false,
+            device: String::new(),
+        });
+        state.settings.insert(uuid.clone(), settings);
+        Ok(uuid)
+    }
+
+    async fn delete_connection(&self, id: &str) -> Result<(), String> {
+        let mut state = self.state();
+        let uuid = state.connection_mut(id)?.uuid.clone();
+        state.connections.retain(|c| c.uuid != uuid);
+        state.settings.remove(&uuid);
+        Ok(())
+    }
+
+    async fn modify_connection(
+        &self,
+        id: &str,
+        changes: &[(String, String)],
+    ) -> Result<(), String> {
+        let mut state = self.state();
+        let uuid = state.connection_mut(id)?.uuid.clone();
+        let settings = state.settings.entry(uuid).or_default();
+        for (prop, value) in changes {
+            // `remove <setting>` drops the whole setting, like `nmcli connection modify`
+            if prop == "remove" {
+                let prefix = format!("{}.", value);
+                settings.retain(|key, _| !key.starts_with(&prefix));
+                continue;
+            }
+            settings.insert(
+                prop.trim_start_matches(['+', '-']).to_string(),
+                value.clone(),
+            );
+        }
+        Ok(())
+    }
+
+    async fn import_connection(&self, vpn_type: &str, file_path: &str) -> Result<String, String> {
+        let mut state = self.state();
+        let uuid = format!("fake-{}", state.connections.len() + 1);
+        let name = std::path::Path::new(file_path)
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_else(|| uuid.clone());
+        let conn_type = if vpn_type == "wireguard" {
+            "wireguard"
+        } else {
+            "vpn"
+        };
+
+        state.connections.push(ConnectionProfile {
+            uuid: uuid.clone(),
+            name,
+            conn_type: conn_type.to_string(),
+            active: false,
+            device: String::new(),
+        });
+        Ok(uuid)
+    }
+
+    async fn export_connection(&self, id: &str, file_path: &str) -> Result<(), String> {
+        // Writes the stored settings, one `property=value` per line
+        let mut state = self.state();
+        let uuid = state.connection_mut(id)?.uuid.clone();
+        let mut lines: Vec<String> = state
+            .settings
+            .get(&uuid)
+            .into_iter()
+            .flatten()
+            .map(|(prop, value)| format!("{}={}", prop, value))
+            .collect();
+        lines.sort();
+        std::fs::write(file_path, lines.join("\n")).map_err(|e| e.to_string())
+    }
+}
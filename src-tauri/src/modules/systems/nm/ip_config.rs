@@ -0,0 +1,561 @@
+use crate::modules::systems::nm::client::NetworkManager;
+use crate::modules::systems::nm::profile::{ProfileError, ProfileUpdateError};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+/// Address family of an `IpSettings` block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IpFamily {
+    V4,
+    V6,
+}
+
+impl IpFamily {
+    /// Prefix of the persistent settings, e.g. `ipv4.method`.
+    fn setting(self) -> &'static str {
+        match self {
+            IpFamily::V4 => "ipv4",
+            IpFamily::V6 => "ipv6",
+        }
+    }
+
+    /// Prefix of the runtime values, e.g. `IP4.ADDRESS`.
+    fn runtime(self) -> &'static str {
+        match self {
+            IpFamily::V4 => "IP4",
+            IpFamily::V6 => "IP6",
+        }
+    }
+
+    /// Methods NetworkManager accepts for the family.
+    fn methods(self) -> &'static [&'static str] {
+        match self {
+            IpFamily::V4 => &["auto", "manual", "link-local", "shared", "disabled"],
+            IpFamily::V6 => &[
+                "auto",
+                "dhcp",
+                "manual",
+                "link-local",
+                "shared",
+                "ignore",
+                "disabled",
+            ],
+        }
+    }
+
+    fn max_prefix(self) -> u8 {
+        match self {
+            IpFamily::V4 => 32,
+            IpFamily::V6 => 128,
+        }
+    }
+
+    /// Parses an address of this family.
+    fn parse_addr(self, text: &str) -> Option<IpAddr> {
+        match self {
+            IpFamily::V4 => text.parse::<Ipv4Addr>().ok().map(IpAddr::V4),
+            IpFamily::V6 => text.parse::<Ipv6Addr>().ok().map(IpAddr::V6),
+        }
+    }
+}
+
+/// A static route: destination network, optional next hop and metric.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct StaticRoute {
+    pub destination: String, // CIDR, e.g. "10.0.0.0/8"
+    #[serde(default)]
+    pub next_hop: Option<String>,
+    #[serde(default)]
+    pub metric: Option<u32>,
+}
+
+/// Configured settings of one address family.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct IpSettings {
+    pub method: String,
+    #[serde(default)]
+    pub addresses: Vec<String>, // CIDR, e.g. "192.168.1.50/24"
+    #[serde(default)]
+    pub gateway: String,
+    #[serde(default)]
+    pub dns: Vec<String>,
+    #[serde(default)]
+    pub dns_search: Vec<String>,
+    #[serde(default)]
+    pub route_metric: Option<u32>, // None lets NetworkManager pick
+    #[serde(default)]
+    pub routes: Vec<StaticRoute>,
+}
+
+/// IPv6 privacy extensions (RFC 4941), `ipv6.ip6-privacy`.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum Ipv6Privacy {
+    /// Use the global default.
+    #[default]
+    Default,
+    Disabled,
+    /// Generate temporary addresses but prefer the public one.
+    PreferPublic,
+    /// Generate temporary addresses and prefer them.
+    PreferTemporary,
+}
+
+/// IPv4 and IPv6 configuration of a connection profile.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct IpConfig {
+    pub ipv4: IpSettings,
+    pub ipv6: IpSettings,
+    #[serde(default)]
+    pub ipv6_privacy: Ipv6Privacy,
+}
+
+impl Default for IpConfig {
+    fn default() -> Self {
+        Self {
+            ipv4: IpSettings {
+                method: "auto".to_string(),
+                ..Default::default()
+            },
+            ipv6: IpSettings {
+                method: "auto".to_string(),
+                ..Default::default()
+            },
+            ipv6_privacy: Ipv6Privacy::Default,
+        }
+    }
+}
+
+/// Addresses, gateway and DNS servers currently in use for one address family.
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct ActiveIp {
+    pub addresses: Vec<String>,
+    pub gateway: String,
+    pub dns: Vec<String>,
+}
+
+/// Runtime addressing of an active connection.
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct ActiveIpConfig {
+    pub ipv4: ActiveIp,
+    pub ipv6: ActiveIp,
+}
+
+/// Configured values next to the values in effect, which may come from DHCP or SLAAC.
+#[derive(Debug, Serialize)]
+pub struct IpConfigReport {
+    pub configured: IpConfig,
+    pub active: Option<ActiveIpConfig>, // None when the connection is not active
+}
+
+/// Properties to request from `connection_settings` for `parse_ip_config`.
+pub fn ip_config_properties() -> Vec<String> {
+    let mut props = Vec::new();
+    for family in [IpFamily::V4, IpFamily::V6] {
+        let s = family.setting();
+        for key in [
+            "method",
+            "addresses",
+            "gateway",
+            "dns",
+            "dns-search",
+            "route-metric",
+            "routes",
+        ] {
+            props.push(format!("{}.{}", s, key));
+        }
+        let r = family.runtime();
+        for key in ["ADDRESS", "GATEWAY", "DNS"] {
+            props.push(format!("{}.{}", r, key));
+        }
+    }
+    props.push("ipv6.ip6-privacy".to_string());
+    props
+}
+
+/// Splits nmcli list values, which use `,` for settings and ` | ` for runtime values.
+fn split_list(value: &str) -> Vec<String> {
+    value
+        .split([',', '|'])
+        .map(str::trim)
+        .filter(|v| !v.is_empty() && *v != "--")
+        .map(String::from)
+        .collect()
+}
+
+/// Parses nmcli's route syntax: `dest[/prefix] [next-hop] [metric] [attributes]`.
+fn parse_routes(value: &str) -> Vec<StaticRoute> {
+    split_list(value)
+        .into_iter()
+        .filter_map(|route| {
+            let mut parts = route.split_whitespace();
+            let destination = parts.next()?.to_string();
+            let mut next_hop = None;
+            let mut metric = None;
+            for part in parts.take(2) {
+                if let Ok(m) = part.parse::<u32>() {
+                    metric = Some(m);
+                } else if part.parse::<IpAddr>().is_ok() {
+                    next_hop = Some(part.to_string());
+                }
+            }
+            Some(StaticRoute {
+                destination,
+                next_hop,
+                metric,
+            })
+        })
+        .collect()
+}
+
+/// Builds the configured settings of a family from `connection_settings` output.
+fn parse_settings(settings: &HashMap<String, String>, family: IpFamily) -> IpSettings {
+    let get = |key: &str| {
+        settings
+            .get(&format!("{}.{}", family.setting(), key))
+            .map(|v| v.trim())
+            .unwrap_or("")
+    };
+
+    IpSettings {
+        method: match get("method") {
+            "" => "auto".to_string(),
+            method => method.to_string(),
+        },
+        addresses: split_list(get("addresses")),
+        gateway: get("gateway").trim_matches('-').to_string(),
+        dns: split_list(get("dns")),
+        dns_search: split_list(get("dns-search")),
+        route_metric: get("route-metric").parse().ok(),
+        routes: parse_routes(get("routes")),
+    }
+}
+
+/// Builds the runtime values of a family from `connection_settings` output.
+fn parse_active(settings: &HashMap<String, String>, family: IpFamily) -> ActiveIp {
+    let get = |key: &str| {
+        settings
+            .get(&format!("{}.{}", family.runtime(), key))
+            .map(|v| v.trim())
+            .unwrap_or("")
+    };
+
+    ActiveIp {
+        addresses: split_list(get("ADDRESS")),
+        gateway: get("GATEWAY").trim_matches('-').to_string(),
+        dns: split_list(get("DNS")),
+    }
+}
+
+/// Parses configured and runtime values read with `ip_config_properties`.
+pub fn parse_ip_config(settings: &HashMap<String, String>) -> IpConfigReport {
+    // `ip6-privacy` reads e.g. "2 (enabled, prefer temporary IP)" or "-1"
+    let privacy = settings
+        .get("ipv6.ip6-privacy")
+        .and_then(|v| v.split_whitespace().next())
+        .and_then(|v| v.parse::<i32>().ok());
+    let configured = IpConfig {
+        ipv4: parse_settings(settings, IpFamily::V4),
+        ipv6: parse_settings(settings, IpFamily::V6),
+        ipv6_privacy: match privacy {
+            Some(0) => Ipv6Privacy::Disabled,
+            Some(1) => Ipv6Privacy::PreferPublic,
+            Some(2) => Ipv6Privacy::PreferTemporary,
+            _ => Ipv6Privacy::Default,
+        },
+    };
+
+    let active = ActiveIpConfig {
+        ipv4: parse_active(settings, IpFamily::V4),
+        ipv6: parse_active(settings, IpFamily::V6),
+    };
+    let is_active = !active.ipv4.addresses.is_empty() || !active.ipv6.addresses.is_empty();
+
+    IpConfigReport {
+        configured,
+        active: is_active.then_some(active),
+    }
+}
+
+/// Parses `address/prefix`, returning the address and prefix length.
+fn parse_cidr(text: &str, family: IpFamily) -> Option<(IpAddr, u8)> {
+    let (addr, prefix) = match text.split_once('/') {
+        Some((addr, prefix)) => (addr, prefix.parse::<u8>().ok()?),
+        None => (text, family.max_prefix()),
+    };
+    let addr = family.parse_addr(addr.trim())?;
+    (prefix <= family.max_prefix()).then_some((addr, prefix))
+}
+
+/// Checks one family's settings, adding errors under `ipv4.*` / `ipv6.*` field names.
+fn validate_settings(settings: &IpSettings, family: IpFamily, errors: &mut Vec<ProfileError>) {
+    let field = |name: &str| format!("{}.{}", family.setting(), name);
+    let label = match family {
+        IpFamily::V4 => "IPv4",
+        IpFamily::V6 => "IPv6",
+    };
+
+    if !family.methods().contains(&settings.method.as_str()) {
+        errors.push(ProfileError::new(
+            &field("method"),
+            format!("Unknown {} method '{}'", label, settings.method),
+        ));
+    }
+
+    if settings.method == "manual" && settings.addresses.is_empty() {
+        errors.push(ProfileError::new(
+            &field("addresses"),
+            "Manual configuration needs at least one address",
+        ));
+    }
+    for address in &settings.addresses {
+        if parse_cidr(address, family).is_none() {
+            errors.push(ProfileError::new(
+                &field("addresses"),
+                format!("'{}' is not a valid {} address", address, label),
+            ));
+        }
+    }
+
+    if !settings.gateway.is_empty() && family.parse_addr(&settings.gateway).is_none() {
+        errors.push(ProfileError::new(
+            &field("gateway"),
+            format!("'{}' is not a valid {} gateway", settings.gateway, label),
+        ));
+    }
+
+    for server in &settings.dns {
+        if family.parse_addr(server).is_none() {
+            errors.push(ProfileError::new(
+                &field("dns"),
+                format!("'{}' is not a valid {} DNS server", server, label),
+            ));
+        }
+    }
+
+    for domain in &settings.dns_search {
+        let valid = !domain.is_empty()
+            && domain.len() <= 253
+            && domain.trim_start_matches('~').split('.').all(|label| {
+                !label.is_empty()
+                    && label.len() <= 63
+                    && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+            });
+        if !valid && domain != "~" {
+            errors.push(ProfileError::new(
+                &field("dns_search"),
+                format!("'{}' is not a valid search domain", domain),
+            ));
+        }
+    }
+
+    for route in &settings.routes {
+        if parse_cidr(&route.destination, family).is_none() {
+            errors.push(ProfileError::new(
+                &field("routes"),
+                format!("'{}' is not a valid route destination", route.destination),
+            ));
+        }
+        if let Some(next_hop) = &route.next_hop {
+            if !next_hop.is_empty() && family.parse_addr(next_hop).is_none() {
+                errors.push(ProfileError::new(
+                    &field("routes"),
+                    format!("'{}' is not a valid next hop", next_hop),
+                ));
+            }
+        }
+    }
+}
+
+/// Validates both address families with `std::net` parsing.
+pub fn validate_ip_config(config: &IpConfig) -> Vec<ProfileError> {
+    let mut errors = Vec::new();
+    validate_settings(&config.ipv4, IpFamily::V4, &mut errors);
+    validate_settings(&config.ipv6, IpFamily::V6, &mut errors);
+    errors
+}
+
+/// Renders a family's settings as nmcli property changes.
+fn settings_changes(settings: &IpSettings, family: IpFamily) -> Vec<(String, String)> {
+    let prop = |key: &str| format!("{}.{}", family.setting(), key);
+    let mut changes = vec![(prop("method"), settings.method.clone())];
+
+    // Static addresses and gateway only apply to manual configuration; clear them otherwise
+    if settings.method == "manual" {
+        let addresses: Vec<String> = settings
+            .addresses
+            .iter()
+            .filter_map(|a| parse_cidr(a, family))
+            .map(|(addr, prefix)| format!("{}/{}", addr, prefix))
+            .collect();
+        changes.push((prop("addresses"), addresses.join(",")));
+        changes.push((prop("gateway"), settings.gateway.clone()));
+    } else {
+        changes.push((prop("addresses"), String::new()));
+        changes.push((prop("gateway"), String::new()));
+    }
+
+    // Explicit DNS servers replace the ones handed out by DHCP or router advertisements
+    changes.push((prop("dns"), settings.dns.join(",")));
+    let ignore_auto_dns = if settings.dns.is_empty() { "no" } else { "yes" };
+    changes.push((prop("ignore-auto-dns"), ignore_auto_dns.to_string()));
+    changes.push((prop("dns-search"), settings.dns_search.join(",")));
+
+    let metric = settings
+        .route_metric
+        .map(|m| m.to_string())
+        .unwrap_or_else(|| "-1".to_string());
+    changes.push((prop("route-metric"), metric));
+
+    let routes: Vec<String> = settings
+        .routes
+        .iter()
+        .map(|r| {
+            let mut route = r.destination.trim().to_string();
+            if let Some(next_hop) = r.next_hop.as_deref().filter(|h| !h.is_empty()) {
+                route.push(' ');
+                route.push_str(next_hop);
+            }
+            if let Some(metric) = r.metric {
+                route.push_str(&format!(" {}", metric));
+            }
+            route
+        })
+        .collect();
+    changes.push((prop("routes"), routes.join(",")));
+    changes
+}
+
+/// Renders a validated configuration as nmcli property changes.
+pub fn ip_config_changes(config: &IpConfig) -> Vec<(String, String)> {
+    let mut changes = settings_changes(&config.ipv4, IpFamily::V4);
+    changes.extend(settings_changes(&config.ipv6, IpFamily::V6));
+
+    let privacy = match config.ipv6_privacy {
+        Ipv6Privacy::Default => "-1",
+        Ipv6Privacy::Disabled => "0",
+        Ipv6Privacy::PreferPublic => "1",
+        Ipv6Privacy::PreferTemporary => "2",
+    };
+    changes.push(("ipv6.ip6-privacy".to_string(), privacy.to_string()));
+    changes
+}
+
+/// Reads the configured and active IP settings of a connection.
+pub async fn read_ip_config(nm: &dyn NetworkManager, id: &str) -> Result<IpConfigReport, String> {
+    let properties = ip_config_properties();
+    let properties: Vec<&str> = properties.iter().map(String::as_str).collect();
+    let settings = nm.connection_settings(id, &properties).await?;
+    Ok(parse_ip_config(&settings))
+}
+
+/// Validates and saves the IP settings of a connection.
+pub async fn write_ip_config(
+    nm: &dyn NetworkManager,
+    id: &str,
+    config: &IpConfig,
+) -> Result<(), ProfileUpdateError> {
+    let errors = validate_ip_config(config);
+    if !errors.is_empty() {
+        return Err(ProfileUpdateError::Invalid { errors });
+    }
+
+    nm.modify_connection(id, &ip_config_changes(config))
+        .await
+        .map_err(|message| ProfileUpdateError::Failed { message })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::systems::nm::client::ConnectionProfile;
+    use crate::modules::systems::nm::fake::{FakeNetworkManager, FakeState};
+
+    fn settings(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn reads_an_inactive_connection() {
+        let report = parse_ip_config(&settings(&[
+            ("ipv4.method", "manual"),
+            ("ipv4.addresses", "192.168.1.50/24"),
+            ("ipv4.gateway", "192.168.1.1"),
+            ("ipv4.dns", "1.1.1.1,9.9.9.9"),
+            ("ipv6.method", "ignore"),
+            ("ipv6.ip6-privacy", "2 (enabled, prefer temporary IP)"),
+            ("IP4.ADDRESS", ""),
+        ]));
+        assert_eq!(report.configured.ipv4.method, "manual");
+        assert_eq!(report.configured.ipv4.addresses, vec!["192.168.1.50/24"]);
+        assert_eq!(report.configured.ipv4.gateway, "192.168.1.1");
+        assert_eq!(report.configured.ipv4.dns, vec!["1.1.1.1", "9.9.9.9"]);
+        assert_eq!(report.configured.ipv6.method, "ignore");
+        assert_eq!(report.configured.ipv6_privacy, Ipv6Privacy::PreferTemporary);
+        assert!(report.active.is_none());
+    }
+
+    #[test]
+    fn reads_runtime_values() {
+        let report = parse_ip_config(&settings(&[
+            ("ipv4.method", "auto"),
+            ("IP4.ADDRESS", "192.168.1.7/24 | 10.0.0.2/8"),
+            ("IP4.GATEWAY", "192.168.1.1"),
+            ("IP4.DNS", "192.168.1.1"),
+        ]));
+        let active = report.active.unwrap();
+        assert_eq!(active.ipv4.addresses, vec!["192.168.1.7/24", "10.0.0.2/8"]);
+        assert_eq!(active.ipv4.gateway, "192.168.1.1");
+        assert!(active.ipv6.addresses.is_empty());
+        assert_eq!(report.configured.ipv6.method, "auto");
+    }
+
+    #[test]
+    fn parses_routes() {
+        assert_eq!(
+            parse_routes("10.0.0.0/8 192.168.1.1 100, 172.16.0.0/12"),
+            vec![
+                StaticRoute {
+                    destination: "10.0.0.0/8".to_string(),
+                    next_hop: Some("192.168.1.1".to_string()),
+                    metric: Some(100),
+                },
+                StaticRoute {
+                    destination: "172.16.0.0/12".to_string(),
+                    next_hop: None,
+                    metric: None,
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn reports_unknown_connections() {
+        let nm = FakeNetworkManager::default();
+        assert!(read_ip_config(&nm, "Missing").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn reads_saved_settings() {
+        let nm = FakeNetworkManager::new(FakeState {
+            connections: vec![ConnectionProfile {
+                uuid: "uuid-1".to_string(),
+                name: "Wired".to_string(),
+                conn_type: "802-3-ethernet".to_string(),
+                ..Default::default()
+            }],
+            settings: HashMap::from([(
+                "uuid-1".to_string(),
+                settings(&[("ipv4.method", "link-local")]),
+            )]),
+            ..Default::default()
+        });
+        let report = read_ip_config(&nm, "Wired").await.unwrap();
+        assert_eq!(report.configured.ipv4.method, "link-local");
+        assert_eq!(report.configured.ipv6.method, "auto");
+    }
+}
@@ -0,0 +1,9 @@
+pub mod cli;
+pub mod client;
+pub mod dbus;
+// In-memory backend for exercising the Wi-Fi and VPN logic in tests
+#[cfg(test)]
+pub mod fake;
+pub mod ip_config;
+pub mod profile;
+pub mod properties;
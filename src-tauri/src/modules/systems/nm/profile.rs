@@ -0,0 +1,220 @@
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// EAP methods supported for 802.1X authentication.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum EapMethod {
+    Peap,
+    Ttls,
+    Tls,
+}
+
+/// Inner authentication used inside a PEAP or TTLS tunnel.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Phase2Auth {
+    Mschapv2,
+    Mschap,
+    Chap,
+    Pap,
+    Gtc,
+    Md5,
+}
+
+impl Phase2Auth {
+    fn as_str(self) -> &'static str {
+        match self {
+            Phase2Auth::Mschapv2 => "mschapv2",
+            Phase2Auth::Mschap => "mschap",
+            Phase2Auth::Chap => "chap",
+            Phase2Auth::Pap => "pap",
+            Phase2Auth::Gtc => "gtc",
+            Phase2Auth::Md5 => "md5",
+        }
+    }
+}
+
+/// 802.1X credentials, shared by WPA-Enterprise Wi-Fi and wired ports.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Eap8021x {
+    pub method: EapMethod,
+    pub identity: String,
+    #[serde(default)]
+    pub anonymous_identity: Option<String>,
+    #[serde(default)]
+    pub password: Option<String>, // PEAP and TTLS
+    #[serde(default)]
+    pub phase2_auth: Option<Phase2Auth>, // PEAP and TTLS, defaults to MSCHAPv2
+    #[serde(default)]
+    pub ca_cert: Option<String>,
+    #[serde(default)]
+    pub client_cert: Option<String>, // TLS
+    #[serde(default)]
+    pub private_key: Option<String>, // TLS
+    #[serde(default)]
+    pub private_key_password: Option<String>, // TLS
+}
+
+/// A profile field that was missing or invalid.
+#[derive(Debug, Serialize, Clone)]
+pub struct ProfileError {
+    pub field: String,
+    pub message: String,
+}
+
+impl ProfileError {
+    pub fn new(field: &str, message: impl Into<String>) -> Self {
+        Self {
+            field: field.to_string(),
+            message: message.into(),
+        }
+    }
+}
+
+/// Reasons changes to an existing connection profile could not be applied.
+#[derive(Debug, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ProfileUpdateError {
+    /// One or more fields failed validation.
+    Invalid { errors: Vec<ProfileError> },
+    /// NetworkManager rejected the settings.
+    Failed { message: String },
+    /// The settings were saved but re-activating the connection failed.
+    ActivationFailed { message: String },
+}
+
+/// Collects connection settings as nmcli property/value pairs, validating as it goes.
+pub struct ProfileBuilder {
+    settings: Vec<(String, String)>,
+    errors: Vec<ProfileError>,
+}
+
+/// Returns the trimmed value when it is non-empty.
+fn non_empty(value: &Option<String>) -> Option<&str> {
+    value.as_deref().map(str::trim).filter(|v| !v.is_empty())
+}
+
+impl ProfileBuilder {
+    /// Starts a profile of the given NM type (e.g. `802-11-wireless`) and name.
+    pub fn new(conn_type: &str, name: &str) -> Self {
+        let mut builder = Self {
+            settings: Vec::new(),
+            errors: Vec::new(),
+        };
+        if name.trim().is_empty() {
+            builder.error("name", "A connection name is required");
+        }
+        builder.set("connection.type", conn_type);
+        builder.set("connection.id", name.trim());
+        builder
+    }
+
+    /// Starts a set of changes to an existing profile.
+    pub fn modify() -> Self {
+        Self {
+            settings: Vec::new(),
+            errors: Vec::new(),
+        }
+    }
+
+    /// Sets a property, replacing an earlier value of the same property.
+    pub fn set(&mut self, property: &str, value: impl Into<String>) -> &mut Self {
+        let value = value.into();
+        match self.settings.iter_mut().find(|(p, _)| p == property) {
+            Some(entry) => entry.1 = value,
+            None => self.settings.push((property.to_string(), value)),
+        }
+        self
+    }
+
+    /// Records a validation error.
+    pub fn error(&mut self, field: &str, message: impl Into<String>) -> &mut Self {
+        self.errors.push(ProfileError::new(field, message));
+        self
+    }
+
+    /// Sets a certificate or key path after checking that the file exists.
+    fn set_file(&mut self, field: &str, property: &str, path: &str) {
+        if Path::new(path).is_file() {
+            self.set(property, path);
+        } else {
+            self.error(field, format!("File not found: {}", path));
+        }
+    }
+
+    /// Adds the `802-1x` setting for PEAP, TTLS or TLS.
+    pub fn eap(&mut self, eap: &Eap8021x) -> &mut Self {
+        let method = match eap.method {
+            EapMethod::Peap => "peap",
+            EapMethod::Ttls => "ttls",
+            EapMethod::Tls => "tls",
+        };
+        self.set("802-1x.eap", method);
+
+        if eap.identity.trim().is_empty() {
+            self.error("identity", "An identity is required");
+        } else {
+            self.set("802-1x.identity", eap.identity.trim());
+        }
+        if let Some(anonymous) = non_empty(&eap.anonymous_identity) {
+            self.set("802-1x.anonymous-identity", anonymous);
+        }
+        if let Some(ca_cert) = non_empty(&eap.ca_cert) {
+            self.set_file("ca_cert", "802-1x.ca-cert", ca_cert);
+        }
+
+        match eap.method {
+            EapMethod::Peap | EapMethod::Ttls => {
+                match eap.password.as_deref().filter(|p| !p.is_empty()) {
+                    Some(password) => {
+                        self.set("802-1x.password", password);
+                    }
+                    None => {
+                        self.error("password", "A password is required for PEAP and TTLS");
+                    }
+                }
+                let phase2 = eap.phase2_auth.unwrap_or(Phase2Auth::Mschapv2);
+                if eap.method == EapMethod::Peap
+                    && !matches!(
+                        phase2,
+                        Phase2Auth::Mschapv2 | Phase2Auth::Gtc | Phase2Auth::Md5
+                    )
+                {
+                    self.error(
+                        "phase2_auth",
+                        "PEAP supports MSCHAPv2, GTC and MD5 inner authentication",
+                    );
+                }
+                self.set("802-1x.phase2-auth", phase2.as_str());
+            }
+            EapMethod::Tls => {
+                match non_empty(&eap.client_cert) {
+                    Some(cert) => self.set_file("client_cert", "802-1x.client-cert", cert),
+                    None => {
+                        self.error("client_cert", "A client certificate is required for TLS");
+                    }
+                }
+                match non_empty(&eap.private_key) {
+                    Some(key) => self.set_file("private_key", "802-1x.private-key", key),
+                    None => {
+                        self.error("private_key", "A private key is required for TLS");
+                    }
+                }
+                if let Some(password) = eap.private_key_password.as_deref() {
+                    self.set("802-1x.private-key-password", password);
+                }
+            }
+        }
+        self
+    }
+
+    /// Returns the settings, or every validation error found.
+    pub fn build(self) -> Result<Vec<(String, String)>, Vec<ProfileError>> {
+        if self.errors.is_empty() {
+            Ok(self.settings)
+        } else {
+            Err(self.errors)
+        }
+    }
+}
@@ -0,0 +1,749 @@
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use zbus::zvariant::{OwnedValue, Value};
+
+/// Connection settings in NetworkManager's D-Bus form (`a{sa{sv}}`), as returned by `GetSettings`.
+pub type Settings = HashMap<String, HashMap<String, OwnedValue>>;
+
+/// A dictionary such as one `address-data` entry or one WireGuard peer (`a{sv}`).
+type Dict = HashMap<String, OwnedValue>;
+
+/// Settings that can hold secrets, which `GetSettings` leaves out.
+pub const SECRET_SETTINGS: [&str; 4] = ["802-11-wireless-security", "802-1x", "vpn", "wireguard"];
+
+/// Keys superseded by the ones this module writes. NetworkManager ignores the
+/// new key when the old one is sent as well, so they are dropped before saving.
+const DEPRECATED_KEYS: [(&str, &str); 6] = [
+    ("ipv4", "addresses"),
+    ("ipv4", "routes"),
+    ("ipv6", "addresses"),
+    ("ipv6", "routes"),
+    ("802-11-wireless", "cloned-mac-address"),
+    ("802-3-ethernet", "cloned-mac-address"),
+];
+
+/// `connection.metered` values.
+const METERED: [(i32, &str); 5] = [
+    (0, "unknown"),
+    (1, "yes"),
+    (2, "no"),
+    (3, "guess-yes"),
+    (4, "guess-no"),
+];
+
+/// `proxy.method` values.
+const PROXY_METHODS: [(i32, &str); 2] = [(0, "none"), (1, "auto")];
+
+/// How a property's D-Bus value is represented.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Kind {
+    Str,
+    Bool,
+    U32,
+    I32,
+    I64,
+    U64,
+    /// An integer nmcli shows by name.
+    Enum(&'static [(i32, &'static str)]),
+    /// Bytes holding text, e.g. the SSID.
+    Text,
+    /// Bytes holding a hardware address.
+    Mac,
+    /// Bytes holding a `file://` URI, used for 802.1X certificates and keys.
+    Path,
+    StrList,
+    /// IPv4 DNS servers as integers in network byte order.
+    Ipv4Dns,
+    /// IPv6 DNS servers as 16-byte arrays.
+    Ipv6Dns,
+    /// `address-data`: `address` and `prefix` per entry.
+    Addresses,
+    /// `route-data`: `dest`, `prefix`, `next-hop` and `metric` per entry.
+    Routes,
+    /// `vpn.data` and `vpn.secrets`.
+    StrDict,
+    /// `wireguard.peers`.
+    Peers,
+}
+
+/// Looks up where an nmcli property lives on D-Bus: its setting, key and value kind.
+fn property(name: &str) -> Result<(&str, &str, Kind), String> {
+    let (setting, key) = name
+        .split_once('.')
+        .ok_or_else(|| format!("Invalid property '{}'", name))?;
+
+    let mapped = match (setting, key) {
+        ("ipv4" | "ipv6", "addresses") => ("address-data", Kind::Addresses),
+        ("ipv4" | "ipv6", "routes") => ("route-data", Kind::Routes),
+        ("802-11-wireless" | "802-3-ethernet", "cloned-mac-address") => {
+            ("assigned-mac-address", Kind::Str)
+        }
+        _ => (key, kind_of(setting, key)),
+    };
+    Ok((setting, mapped.0, mapped.1))
+}
+
+/// Value kind of a property that keeps its nmcli name on D-Bus.
+fn kind_of(setting: &str, key: &str) -> Kind {
+    match (setting, key) {
+        ("connection", "autoconnect")
+        | ("802-11-wireless", "hidden")
+        | ("proxy", "browser-only")
+        | (_, "ignore-auto-dns") => Kind::Bool,
+        ("connection", "autoconnect-priority") | ("ipv6", "ip6-privacy") => Kind::I32,
+        ("connection", "metered") => Kind::Enum(&METERED),
+        ("proxy", "method") => Kind::Enum(&PROXY_METHODS),
+        ("connection", "timestamp") => Kind::U64,
+        (_, "route-metric") => Kind::I64,
+        ("802-11-wireless", "channel") | ("wireguard", "listen-port") | (_, "mtu") => Kind::U32,
+        (_, key) if key.ends_with("-flags") => Kind::U32,
+        ("802-11-wireless", "ssid") => Kind::Text,
+        ("802-11-wireless", "bssid") | (_, "mac-address") => Kind::Mac,
+        ("802-1x", "ca-cert" | "client-cert" | "private-key") => Kind::Path,
+        ("802-1x", "eap")
+        | ("802-11-wireless-security", "proto" | "pairwise" | "group")
+        | (_, "dns-search") => Kind::StrList,
+        ("ipv4", "dns") => Kind::Ipv4Dns,
+        ("ipv6", "dns") => Kind::Ipv6Dns,
+        ("vpn", "data" | "secrets") => Kind::StrDict,
+        ("wireguard", "peers") => Kind::Peers,
+        _ => Kind::Str,
+    }
+}
+
+/// Converts a value for storing in settings.
+fn owned<'a>(value: impl Into<Value<'a>>) -> Result<OwnedValue, String> {
+    OwnedValue::try_from(value.into()).map_err(|e| e.to_string())
+}
+
+/// Takes a typed copy of a value.
+fn get<T>(value: &OwnedValue) -> Option<T>
+where
+    T: TryFrom<OwnedValue>,
+{
+    value.try_clone().ok().and_then(|v| T::try_from(v).ok())
+}
+
+/// Takes a typed copy of a dictionary entry.
+fn field<T>(dict: &Dict, key: &str) -> Option<T>
+where
+    T: TryFrom<OwnedValue>,
+{
+    dict.get(key).and_then(get)
+}
+
+/// Formats a hardware address the way nmcli prints it.
+fn format_mac(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|b| format!("{:02X}", b))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+fn parse_mac(text: &str) -> Option<Vec<u8>> {
+    let bytes: Vec<u8> = text
+        .split(':')
+        .map(|part| (part.len() == 2).then(|| u8::from_str_radix(part, 16).ok())?)
+        .collect::<Option<_>>()?;
+    (bytes.len() == 6).then_some(bytes)
+}
+
+/// Parses `address[/prefix]`; the prefix defaults to a single host.
+fn parse_cidr(text: &str) -> Option<(IpAddr, u32)> {
+    let (addr, prefix) = match text.split_once('/') {
+        Some((addr, prefix)) => (addr.parse::<IpAddr>().ok()?, Some(prefix.parse().ok()?)),
+        None => (text.parse::<IpAddr>().ok()?, None),
+    };
+    let max = if addr.is_ipv4() { 32 } else { 128 };
+    let prefix = prefix.unwrap_or(max);
+    (prefix <= max).then_some((addr, prefix))
+}
+
+/// Splits a list on commas that are not escaped with a backslash, removing the escaping.
+fn split_items(value: &str) -> Vec<String> {
+    let mut items = vec![String::new()];
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                if let Some(next) = chars.next() {
+                    items.last_mut().unwrap().push(next);
+                }
+            }
+            ',' => items.push(String::new()),
+            _ => items.last_mut().unwrap().push(c),
+        }
+    }
+    items
+        .into_iter()
+        .map(|item| item.trim().to_string())
+        .filter(|item| !item.is_empty())
+        .collect()
+}
+
+/// Formats `address-data` entries as `address/prefix`, joined with `separator`.
+pub fn format_addresses(entries: &[Dict], separator: &str) -> String {
+    entries
+        .iter()
+        .filter_map(|entry| {
+            let address: String = field(entry, "address")?;
+            let prefix: u32 = field(entry, "prefix")?;
+            Some(format!("{}/{}", address, prefix))
+        })
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
+/// Formats `route-data` entries in nmcli's `dest/prefix [next-hop] [metric]` syntax.
+fn format_routes(entries: &[Dict]) -> String {
+    entries
+        .iter()
+        .filter_map(|entry| {
+            let dest: String = field(entry, "dest")?;
+            let prefix: u32 = field(entry, "prefix")?;
+            let mut route = format!("{}/{}", dest, prefix);
+            if let Some(next_hop) = field::<String>(entry, "next-hop") {
+                route.push(' ');
+                route.push_str(&next_hop);
+            }
+            if let Some(metric) = field::<u32>(entry, "metric") {
+                route.push_str(&format!(" {}", metric));
+            }
+            Some(route)
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Formats peers in nmcli's syntax: the public key followed by `attribute=value` pairs.
+fn format_peers(entries: &[Dict]) -> String {
+    entries
+        .iter()
+        .filter_map(|entry| {
+            let mut parts = vec![field::<String>(entry, "public-key")?];
+            if let Some(ips) = field::<Vec<String>>(entry, "allowed-ips") {
+                parts.push(format!("allowed-ips={}", ips.join(";")));
+            }
+            for key in ["endpoint", "preshared-key"] {
+                if let Some(value) = field::<String>(entry, key) {
+                    parts.push(format!("{}={}", key, value));
+                }
+            }
+            for key in ["preshared-key-flags", "persistent-keepalive"] {
+                if let Some(value) = field::<u32>(entry, key) {
+                    parts.push(format!("{}={}", key, value));
+                }
+            }
+            Some(parts.join(" "))
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn parse_addresses(value: &str) -> Result<Vec<Dict>, String> {
+    split_items(value)
+        .iter()
+        .map(|item| {
+            let (addr, prefix) =
+                parse_cidr(item).ok_or_else(|| format!("'{}' is not a valid address", item))?;
+            Ok(HashMap::from([
+                ("address".to_string(), owned(addr.to_string())?),
+                ("prefix".to_string(), OwnedValue::from(prefix)),
+            ]))
+        })
+        .collect()
+}
+
+fn parse_routes(value: &str) -> Result<Vec<Dict>, String> {
+    split_items(value)
+        .iter()
+        .map(|item| {
+            let invalid = || format!("'{}' is not a valid route", item);
+            let mut parts = item.split_whitespace();
+            let (dest, prefix) = parts.next().and_then(parse_cidr).ok_or_else(invalid)?;
+            let mut route = HashMap::from([
+                ("dest".to_string(), owned(dest.to_string())?),
+                ("prefix".to_string(), OwnedValue::from(prefix)),
+            ]);
+            for part in parts {
+                if let Ok(metric) = part.parse::<u32>() {
+                    route.insert("metric".to_string(), OwnedValue::from(metric));
+                } else if part.parse::<IpAddr>().is_ok() {
+                    route.insert("next-hop".to_string(), owned(part.to_string())?);
+                } else {
+                    return Err(invalid());
+                }
+            }
+            Ok(route)
+        })
+        .collect()
+}
+
+fn parse_peers(value: &str) -> Result<Vec<Dict>, String> {
+    split_items(value)
+        .iter()
+        .map(|item| {
+            let mut parts = item.split_whitespace();
+            let public_key = parts.next().unwrap_or_default().to_string();
+            let mut peer = HashMap::from([("public-key".to_string(), owned(public_key)?)]);
+            for part in parts {
+                let (key, value) = part
+                    .split_once('=')
+                    .ok_or_else(|| format!("Invalid peer attribute '{}'", part))?;
+                let value = match key {
+                    "allowed-ips" => owned(
+                        value
+                            .split(';')
+                            .filter(|ip| !ip.is_empty())
+                            .map(String::from)
+                            .collect::<Vec<_>>(),
+                    )?,
+                    "endpoint" | "preshared-key" => owned(value.to_string())?,
+                    "preshared-key-flags" | "persistent-keepalive" => OwnedValue::from(
+                        value
+                            .parse::<u32>()
+                            .map_err(|_| format!("Invalid value for peer {}", key))?,
+                    ),
+                    _ => return Err(format!("Unknown peer attribute '{}'", key)),
+                };
+                peer.insert(key.to_string(), value);
+            }
+            Ok(peer)
+        })
+        .collect()
+}
+
+/// Parses `key = value` items; commas inside values are escaped with a backslash.
+fn parse_str_dict(value: &str) -> Result<HashMap<String, String>, String> {
+    split_items(value)
+        .iter()
+        .map(|item| {
+            let (key, value) = item
+                .split_once('=')
+                .ok_or_else(|| format!("'{}' is not a key = value item", item))?;
+            Ok((key.trim().to_string(), value.trim().to_string()))
+        })
+        .collect()
+}
+
+/// Renders a value the way `nmcli -g` prints it.
+fn to_text(kind: Kind, value: &OwnedValue) -> Option<String> {
+    let text = match kind {
+        Kind::Str => get::<String>(value)?,
+        Kind::Bool => (if get::<bool>(value)? { "yes" } else { "no" }).to_string(),
+        Kind::U32 => get::<u32>(value)?.to_string(),
+        Kind::I32 => get::<i32>(value)?.to_string(),
+        Kind::I64 => get::<i64>(value)?.to_string(),
+        Kind::U64 => get::<u64>(value)?.to_string(),
+        Kind::Enum(names) => {
+            let number = get::<i32>(value)?;
+            names
+                .iter()
+                .find(|(n, _)| *n == number)
+                .map_or_else(|| number.to_string(), |(_, name)| name.to_string())
+        }
+        Kind::Text => String::from_utf8_lossy(&get::<Vec<u8>>(value)?).to_string(),
+        Kind::Mac => format_mac(&get::<Vec<u8>>(value)?),
+        Kind::Path => {
+            let bytes = get::<Vec<u8>>(value)?;
+            let uri = String::from_utf8_lossy(&bytes);
+            let uri = uri.trim_end_matches('\0');
+            uri.strip_prefix("file://").unwrap_or(uri).to_string()
+        }
+        Kind::StrList => get::<Vec<String>>(value)?.join(","),
+        Kind::Ipv4Dns => get::<Vec<u32>>(value)?
+            .into_iter()
+            .map(|n| Ipv4Addr::from(n.to_ne_bytes()).to_string())
+            .collect::<Vec<_>>()
+            .join(","),
+        Kind::Ipv6Dns => get::<Vec<Vec<u8>>>(value)?
+            .into_iter()
+            .filter_map(|bytes| <[u8; 16]>::try_from(bytes).ok())
+            .map(|bytes| Ipv6Addr::from(bytes).to_string())
+            .collect::<Vec<_>>()
+            .join(","),
+        Kind::Addresses => format_addresses(&get::<Vec<Dict>>(value)?, ","),
+        Kind::Routes => format_routes(&get::<Vec<Dict>>(value)?),
+        Kind::StrDict => {
+            let mut items: Vec<(String, String)> =
+                get::<HashMap<String, String>>(value)?.into_iter().collect();
+            items.sort();
+            items
+                .into_iter()
+                .map(|(k, v)| format!("{} = {}", k, v.replace(',', "\\,")))
+                .collect::<Vec<_>>()
+                .join(", ")
+        }
+        Kind::Peers => format_peers(&get::<Vec<Dict>>(value)?),
+    };
+    Some(text)
+}
+
+/// Parses an nmcli value into its D-Bus form.
+fn to_value(kind: Kind, name: &str, text: &str) -> Result<OwnedValue, String> {
+    let invalid = || format!("'{}' is not a valid value for {}", text, name);
+    let value = match kind {
+        Kind::Str => owned(text.to_string())?,
+        Kind::Bool => OwnedValue::from(match text {
+            "yes" | "true" | "on" | "1" => true,
+            "no" | "false" | "off" | "0" => false,
+            _ => return Err(invalid()),
+        }),
+        Kind::U32 => OwnedValue::from(text.parse::<u32>().map_err(|_| invalid())?),
+        Kind::I32 => OwnedValue::from(text.parse::<i32>().map_err(|_| invalid())?),
+        Kind::I64 => OwnedValue::from(text.parse::<i64>().map_err(|_| invalid())?),
+        Kind::U64 => OwnedValue::from(text.parse::<u64>().map_err(|_| invalid())?),
+        Kind::Enum(names) => OwnedValue::from(
+            names
+                .iter()
+                .find(|(_, n)| *n == text)
+                .map(|(number, _)| *number)
+                .or_else(|| text.parse().ok())
+                .ok_or_else(invalid)?,
+        ),
+        Kind::Text => owned(text.as_bytes().to_vec())?,
+        Kind::Mac => owned(parse_mac(text).ok_or_else(invalid)?)?,
+        Kind::Path => owned(format!("file://{}\0", text).into_bytes())?,
+        Kind::StrList => owned(split_items(text))?,
+        Kind::Ipv4Dns => owned(
+            split_items(text)
+                .iter()
+                .map(|s| {
+                    s.parse::<Ipv4Addr>()
+                        .map(|ip| u32::from_ne_bytes(ip.octets()))
+                })
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|_| invalid())?,
+        )?,
+        Kind::Ipv6Dns => owned(
+            split_items(text)
+                .iter()
+                .map(|s| s.parse::<Ipv6Addr>().map(|ip| ip.octets().to_vec()))
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|_| invalid())?,
+        )?,
+        Kind::Addresses => owned(parse_addresses(text)?)?,
+        Kind::Routes => owned(parse_routes(text)?)?,
+        Kind::StrDict => owned(parse_str_dict(text)?)?,
+        Kind::Peers => owned(parse_peers(text)?)?,
+    };
+    Ok(value)
+}
+
+/// Reads a property in nmcli's string form. Unset properties read as empty strings.
+pub fn read_property(settings: &Settings, name: &str) -> Result<String, String> {
+    let (setting, key, kind) = property(name)?;
+    Ok(settings
+        .get(setting)
+        .and_then(|s| s.get(key))
+        .and_then(|value| to_text(kind, value))
+        .unwrap_or_default())
+}
+
+/// Applies an nmcli property change. An empty value resets the property to its default;
+/// a `+` or `-` prefix adds items to or removes them from list and dictionary properties.
+pub fn write_property(settings: &mut Settings, name: &str, text: &str) -> Result<(), String> {
+    let (plain, op) = match name.chars().next() {
+        Some(op @ ('+' | '-')) => (&name[1..], Some(op)),
+        _ => (name, None),
+    };
+    let (setting, key, kind) = property(plain)?;
+    if op.is_none() && text.is_empty() {
+        if let Some(group) = settings.get_mut(setting) {
+            group.remove(key);
+        }
+        return Ok(());
+    }
+
+    let group = settings.entry(setting.to_string()).or_default();
+    let value = match (op, kind) {
+        (Some(op), Kind::StrDict) => {
+            let mut items: HashMap<String, String> =
+                group.get(key).and_then(get).unwrap_or_default();
+            if op == '+' {
+                items.extend(parse_str_dict(text)?);
+            } else {
+                for item in split_items(text) {
+                    let item = item.split_once('=').map_or(item.as_str(), |(k, _)| k);
+                    items.remove(item.trim());
+                }
+            }
+            owned(items)?
+        }
+        (Some(op), Kind::StrList) => {
+            let mut items: Vec<String> = group.get(key).and_then(get).unwrap_or_default();
+            let changes = split_items(text);
+            if op == '+' {
+                items.extend(
+                    changes
+                        .into_iter()
+                        .filter(|c| !items.contains(c))
+                        .collect::<Vec<_>>(),
+                );
+            } else {
+                items.retain(|item| !changes.contains(item));
+            }
+            owned(items)?
+        }
+        (Some(_), _) => return Err(format!("{} is not a list property", plain)),
+        (None, kind) => to_value(kind, plain, text)?,
+    };
+    group.insert(key.to_string(), value);
+    Ok(())
+}
+
+/// Applies nmcli property changes. `remove` with a setting name as its value drops the
+/// whole setting, like `nmcli connection modify <id> remove 802-1x`.
+pub fn apply_changes(settings: &mut Settings, changes: &[(String, String)]) -> Result<(), String> {
+    for (property, value) in changes {
+        if property == "remove" {
+            settings.remove(value.as_str());
+        } else {
+            write_property(settings, property, value)?;
+        }
+    }
+    strip_deprecated(settings);
+    Ok(())
+}
+
+/// Drops superseded keys that `GetSettings` returns alongside their replacements.
+fn strip_deprecated(settings: &mut Settings) {
+    for (setting, key) in DEPRECATED_KEYS {
+        if let Some(group) = settings.get_mut(setting) {
+            group.remove(key);
+        }
+    }
+}
+
+/// Merges the result of `GetSecrets` into settings. WireGuard peer secrets are
+/// matched to the existing peers by public key.
+pub fn merge_secrets(settings: &mut Settings, secrets: Settings) {
+    for (setting, values) in secrets {
+        let group = settings.entry(setting).or_default();
+        for (key, value) in values {
+            let peers = (key == "peers")
+                .then(|| group.get(&key).and_then(get::<Vec<Dict>>))
+                .flatten();
+            let Some(mut peers) = peers else {
+                group.insert(key, value);
+                continue;
+            };
+
+            for secret in get::<Vec<Dict>>(&value).unwrap_or_default() {
+                let public_key = field::<String>(&secret, "public-key");
+                if let Some(peer) = peers.iter_mut().find(|p| {
+                    public_key.is_some() && field::<String>(p, "public-key") == public_key
+                }) {
+                    peer.extend(secret);
+                }
+            }
+            if let Ok(peers) = owned(peers) {
+                group.insert(key, peers);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Writes a property into empty settings and reads it back.
+    fn round_trip(name: &str, text: &str) -> String {
+        let mut settings = Settings::new();
+        write_property(&mut settings, name, text).unwrap();
+        read_property(&settings, name).unwrap()
+    }
+
+    #[test]
+    fn round_trips_scalar_properties() {
+        assert_eq!(round_trip("connection.id", "Home"), "Home");
+        assert_eq!(round_trip("connection.autoconnect", "no"), "no");
+        assert_eq!(round_trip("connection.autoconnect", "true"), "yes");
+        assert_eq!(round_trip("connection.autoconnect-priority", "-5"), "-5");
+        assert_eq!(round_trip("connection.metered", "guess-no"), "guess-no");
+        assert_eq!(round_trip("ipv4.route-metric", "-1"), "-1");
+        assert_eq!(round_trip("802-11-wireless.channel", "36"), "36");
+        assert_eq!(round_trip("proxy.method", "auto"), "auto");
+    }
+
+    #[test]
+    fn round_trips_byte_properties() {
+        assert_eq!(round_trip("802-11-wireless.ssid", "Café"), "Café");
+        assert_eq!(
+            round_trip("802-11-wireless.bssid", "AA:BB:CC:00:11:22"),
+            "AA:BB:CC:00:11:22"
+        );
+        assert_eq!(
+            round_trip("802-1x.ca-cert", "/etc/ssl/ca.pem"),
+            "/etc/ssl/ca.pem"
+        );
+    }
+
+    #[test]
+    fn stores_cloned_mac_as_assigned_mac() {
+        let mut settings = Settings::new();
+        write_property(
+            &mut settings,
+            "802-11-wireless.cloned-mac-address",
+            "random",
+        )
+        .unwrap();
+        assert!(settings["802-11-wireless"].contains_key("assigned-mac-address"));
+        assert_eq!(
+            read_property(&settings, "802-11-wireless.cloned-mac-address").unwrap(),
+            "random"
+        );
+    }
+
+    #[test]
+    fn round_trips_addressing() {
+        assert_eq!(
+            round_trip("ipv4.addresses", "192.168.1.50/24, 10.0.0.2"),
+            "192.168.1.50/24,10.0.0.2/32"
+        );
+        assert_eq!(round_trip("ipv6.addresses", "fd00::2/64"), "fd00::2/64");
+        assert_eq!(round_trip("ipv4.dns", "1.1.1.1,9.9.9.9"), "1.1.1.1,9.9.9.9");
+        assert_eq!(round_trip("ipv6.dns", "2606:4700::1111"), "2606:4700::1111");
+        assert_eq!(
+            round_trip("ipv4.routes", "10.0.0.0/8 192.168.1.1 100, 172.16.0.0/12"),
+            "10.0.0.0/8 192.168.1.1 100, 172.16.0.0/12"
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_values() {
+        let mut settings = Settings::new();
+        assert!(write_property(&mut settings, "connection.autoconnect", "maybe").is_err());
+        assert!(write_property(&mut settings, "ipv4.dns", "1.1.1").is_err());
+        assert!(write_property(&mut settings, "ipv4.addresses", "10.0.0.1/33").is_err());
+        assert!(write_property(&mut settings, "802-11-wireless.bssid", "AA:BB").is_err());
+        assert!(write_property(&mut settings, "no-dot", "x").is_err());
+    }
+
+    #[test]
+    fn empty_values_reset_properties() {
+        let mut settings = Settings::new();
+        write_property(&mut settings, "802-11-wireless.band", "a").unwrap();
+        write_property(&mut settings, "802-11-wireless.band", "").unwrap();
+        assert!(settings["802-11-wireless"].is_empty());
+        assert_eq!(
+            read_property(&settings, "802-11-wireless.band").unwrap(),
+            ""
+        );
+    }
+
+    #[test]
+    fn resetting_leaves_missing_settings_alone() {
+        let mut settings = Settings::new();
+        write_property(&mut settings, "802-11-wireless-security.psk", "").unwrap();
+        assert!(settings.is_empty());
+    }
+
+    #[test]
+    fn removes_whole_settings() {
+        let mut settings = Settings::new();
+        write_property(&mut settings, "802-1x.eap", "peap").unwrap();
+        apply_changes(
+            &mut settings,
+            &[
+                ("remove".to_string(), "802-1x".to_string()),
+                ("connection.autoconnect".to_string(), "yes".to_string()),
+            ],
+        )
+        .unwrap();
+        assert!(!settings.contains_key("802-1x"));
+        assert!(settings.contains_key("connection"));
+    }
+
+    #[test]
+    fn adds_and_removes_vpn_items() {
+        let mut settings = Settings::new();
+        write_property(
+            &mut settings,
+            "vpn.data",
+            "remote = vpn.example.com, cipher = AES\\,GCM",
+        )
+        .unwrap();
+        write_property(&mut settings, "+vpn.data", "username=alice").unwrap();
+        assert_eq!(
+            read_property(&settings, "vpn.data").unwrap(),
+            "cipher = AES\\,GCM, remote = vpn.example.com, username = alice"
+        );
+
+        write_property(&mut settings, "-vpn.data", "cipher").unwrap();
+        assert_eq!(
+            read_property(&settings, "vpn.data").unwrap(),
+            "remote = vpn.example.com, username = alice"
+        );
+    }
+
+    #[test]
+    fn adds_and_removes_list_items() {
+        let mut settings = Settings::new();
+        write_property(&mut settings, "ipv4.dns-search", "lan").unwrap();
+        write_property(&mut settings, "+ipv4.dns-search", "example.com,lan").unwrap();
+        assert_eq!(
+            read_property(&settings, "ipv4.dns-search").unwrap(),
+            "lan,example.com"
+        );
+        write_property(&mut settings, "-ipv4.dns-search", "lan").unwrap();
+        assert_eq!(
+            read_property(&settings, "ipv4.dns-search").unwrap(),
+            "example.com"
+        );
+        assert!(write_property(&mut settings, "+connection.id", "x").is_err());
+    }
+
+    #[test]
+    fn round_trips_wireguard_peers() {
+        let peers = "pub1= allowed-ips=0.0.0.0/0;::/0 endpoint=vpn.example.com:51820 \
+                     preshared-key=psk= preshared-key-flags=0 persistent-keepalive=25, \
+                     pub2= allowed-ips=10.0.0.2/32";
+        assert_eq!(round_trip("wireguard.peers", peers), peers);
+    }
+
+    #[test]
+    fn merges_peer_secrets_by_public_key() {
+        let mut settings = Settings::new();
+        write_property(
+            &mut settings,
+            "wireguard.peers",
+            "pub1= allowed-ips=10.0.0.0/8, pub2= allowed-ips=10.0.0.2/32",
+        )
+        .unwrap();
+        write_property(&mut settings, "wireguard.listen-port", "51820").unwrap();
+
+        let mut secrets = Settings::new();
+        write_property(&mut secrets, "wireguard.peers", "pub2= preshared-key=psk=").unwrap();
+        write_property(&mut secrets, "wireguard.private-key", "key=").unwrap();
+        merge_secrets(&mut settings, secrets);
+
+        assert_eq!(
+            read_property(&settings, "wireguard.peers").unwrap(),
+            "pub1= allowed-ips=10.0.0.0/8, pub2= allowed-ips=10.0.0.2/32 preshared-key=psk="
+        );
+        assert_eq!(
+            read_property(&settings, "wireguard.private-key").unwrap(),
+            "key="
+        );
+        assert_eq!(
+            read_property(&settings, "wireguard.listen-port").unwrap(),
+            "51820"
+        );
+    }
+
+    #[test]
+    fn strips_superseded_keys() {
+        let mut settings = Settings::new();
+        write_property(&mut settings, "ipv4.addresses", "10.0.0.2/24").unwrap();
+        settings
+            .get_mut("ipv4")
+            .unwrap()
+            .insert("addresses".to_string(), OwnedValue::from(0u32));
+        apply_changes(&mut settings, &[]).unwrap();
+        assert!(!settings["ipv4"].contains_key("addresses"));
+        assert!(settings["ipv4"].contains_key("address-data"));
+    }
+}
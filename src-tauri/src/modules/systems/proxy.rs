@@ -0,0 +1,468 @@
+use crate::modules::hyprland::env::{
+    add_env_var, delete_env_var, get_env_vars, update_env_var, EnvVar,
+};
+use crate::modules::systems::nm::client::network_manager;
+use crate::modules::systems::nm::profile::{ProfileBuilder, ProfileUpdateError};
+use serde::{Deserialize, Serialize};
+use std::process::Command;
+
+/// GSettings schema read by GTK/GIO applications.
+const PROXY_SCHEMA: &str = "org.gnome.system.proxy";
+
+/// Variables written to `env.conf` for command line tools.
+const PROXY_ENV_VARS: [&str; 3] = ["http_proxy", "https_proxy", "no_proxy"];
+
+/// How applications find their proxy.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProxyMode {
+    /// Direct connections.
+    None,
+    /// Fixed proxy servers.
+    Manual,
+    /// A proxy auto-config (PAC) script.
+    Pac,
+}
+
+/// A proxy server; an empty host means the protocol is not proxied.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProxyServer {
+    pub host: String,
+    pub port: u16,
+}
+
+/// The desktop-wide proxy configuration.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SystemProxy {
+    pub mode: ProxyMode,
+    pub http: ProxyServer,
+    pub https: ProxyServer,
+    pub socks: ProxyServer,
+    pub pac_url: String,
+    pub ignore_hosts: Vec<String>, // hosts and networks that bypass the proxy
+}
+
+/// The system proxy together with the proxy variables currently in `env.conf`.
+#[derive(Debug, Serialize)]
+pub struct SystemProxyReport {
+    pub proxy: SystemProxy,
+    pub env: Vec<EnvVar>,
+}
+
+/// NetworkManager's per-connection proxy methods; NM has no manual mode.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConnectionProxyMethod {
+    None,
+    /// Use the PAC URL, or WPAD discovery when it is empty.
+    Auto,
+}
+
+/// Proxy settings of a single NetworkManager connection.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ConnectionProxy {
+    pub method: ConnectionProxyMethod,
+    pub pac_url: String,
+    pub browser_only: bool, // only browsers should use the proxy
+}
+
+/// Reads a GSettings key in GVariant text format.
+fn gsettings_get(schema: &str, key: &str) -> Result<String, String> {
+    let output = Command::new("gsettings")
+        .args(["get", schema, key])
+        .output()
+        .map_err(|e| e.to_string())?;
+
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    } else {
+        Err(format!(
+            "Failed to read {} {}: {}",
+            schema,
+            key,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
+
+/// Reads a GSettings string key, without the quoting GVariant adds.
+fn gsettings_get_string(schema: &str, key: &str) -> Result<String, String> {
+    Ok(gvariant_strings(&gsettings_get(schema, key)?)
+        .into_iter()
+        .next()
+        .unwrap_or_default())
+}
+
+/// Writes a GSettings key; `value` is in GVariant text format.
+fn gsettings_set(schema: &str, key: &str, value: &str) -> Result<(), String> {
+    let output = Command::new("gsettings")
+        .args(["set", schema, key, value])
+        .output()
+        .map_err(|e| e.to_string())?;
+
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
+
+/// Quotes a string as a GVariant string literal.
+fn gvariant_string(value: &str) -> String {
+    format!("'{}'", value.replace('\\', "\\\\").replace('\'', "\\'"))
+}
+
+/// Extracts every string literal from GVariant text, in order.
+/// Handles both quote styles, so commas and quotes inside a string stay part of it.
+fn gvariant_strings(value: &str) -> Vec<String> {
+    let mut strings = Vec::new();
+    let mut chars = value.chars();
+
+    while let Some(quote) = chars.next() {
+        if quote != '\'' && quote != '"' {
+            continue;
+        }
+        let mut item = String::new();
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => match chars.next() {
+                    Some('n') => item.push('\n'),
+                    Some('t') => item.push('\t'),
+                    Some(escaped) => item.push(escaped),
+                    None => {}
+                },
+                c if c == quote => break,
+                c => item.push(c),
+            }
+        }
+        strings.push(item);
+    }
+    strings
+}
+
+/// Parses a GVariant string array such as `['localhost', '127.0.0.0/8']` or `@as []`.
+fn parse_string_array(value: &str) -> Vec<String> {
+    gvariant_strings(value)
+        .into_iter()
+        .filter(|item| !item.is_empty())
+        .collect()
+}
+
+/// Reads the proxy server of one protocol sub-schema (`http`, `https`, `socks`).
+fn read_server(protocol: &str) -> Result<ProxyServer, String> {
+    let schema = format!("{}.{}", PROXY_SCHEMA, protocol);
+    Ok(ProxyServer {
+        host: gsettings_get_string(&schema, "host")?,
+        port: gsettings_get(&schema, "port")?.parse().unwrap_or(0),
+    })
+}
+
+/// Writes the proxy server of one protocol sub-schema.
+fn write_server(protocol: &str, server: &ProxyServer) -> Result<(), String> {
+    let schema = format!("{}.{}", PROXY_SCHEMA, protocol);
+    gsettings_set(&schema, "host", &gvariant_string(server.host.trim()))?;
+    gsettings_set(&schema, "port", &server.port.to_string())
+}
+
+/// Checks a PAC URL; GIO and NetworkManager accept http, https and file URLs.
+fn validate_pac_url(url: &str) -> Result<(), String> {
+    if ["http://", "https://", "file://"]
+        .iter()
+        .any(|scheme| url.starts_with(scheme))
+    {
+        Ok(())
+    } else {
+        Err("The PAC URL must start with http://, https:// or file://".to_string())
+    }
+}
+
+/// Checks the servers and PAC URL needed by the selected mode.
+fn validate_system_proxy(proxy: &SystemProxy) -> Result<(), String> {
+    match proxy.mode {
+        ProxyMode::None => Ok(()),
+        ProxyMode::Pac => validate_pac_url(proxy.pac_url.trim()),
+        ProxyMode::Manual => {
+            let servers = [
+                ("HTTP", &proxy.http),
+                ("HTTPS", &proxy.https),
+                ("SOCKS", &proxy.socks),
+            ];
+            for (name, server) in servers {
+                let host = server.host.trim();
+                if host.contains("://") || host.contains(char::is_whitespace) {
+                    return Err(format!(
+                        "The {} proxy host must be a plain host name or address",
+                        name
+                    ));
+                }
+                if !host.is_empty() && server.port == 0 {
+                    return Err(format!("The {} proxy needs a port", name));
+                }
+            }
+            if servers.iter().all(|(_, s)| s.host.trim().is_empty()) {
+                return Err("Manual mode needs at least one proxy server".to_string());
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Formats a server as the URL command line tools expect in `*_proxy` variables.
+fn proxy_url(server: &ProxyServer) -> Option<String> {
+    let host = server.host.trim();
+    if host.is_empty() {
+        return None;
+    }
+    // IPv6 addresses need brackets inside a URL
+    if host.contains(':') {
+        Some(format!("http://[{}]:{}", host, server.port))
+    } else {
+        Some(format!("http://{}:{}", host, server.port))
+    }
+}
+
+/// Sets or removes the proxy variables in `env.conf` to match the system proxy.
+/// Command line tools have no PAC support, so only manual mode sets them.
+fn write_proxy_env(proxy: &SystemProxy) -> Result<(), String> {
+    let (http, https) = match proxy.mode {
+        ProxyMode::Manual => (
+            proxy_url(&proxy.http),
+            // HTTPS traffic commonly goes through the HTTP proxy when no other is set
+            proxy_url(&proxy.https).or_else(|| proxy_url(&proxy.http)),
+        ),
+        _ => (None, None),
+    };
+    let no_proxy = Some(proxy.ignore_hosts.join(","))
+        .filter(|hosts| !hosts.is_empty() && (http.is_some() || https.is_some()));
+
+    for (key, value) in PROXY_ENV_VARS.into_iter().zip([http, https, no_proxy]) {
+        let existing = get_env_vars()?.into_iter().find(|v| v.key == key);
+        match (existing, value) {
+            (Some(var), Some(value)) => {
+                update_env_var(var.id.clone(), EnvVar { value, ..var })?;
+            }
+            (None, Some(value)) => {
+                add_env_var(EnvVar {
+                    id: String::new(),
+                    key: key.to_string(),
+                    value,
+                })?;
+            }
+            (Some(var), None) => delete_env_var(var.id)?,
+            (None, None) => {}
+        }
+    }
+    Ok(())
+}
+
+/// Reads the desktop proxy from GSettings and the proxy variables from `env.conf`.
+#[tauri::command]
+pub fn get_system_proxy() -> Result<SystemProxyReport, String> {
+    let mode = match gsettings_get_string(PROXY_SCHEMA, "mode")?.as_str() {
+        "manual" => ProxyMode::Manual,
+        "auto" => ProxyMode::Pac,
+        _ => ProxyMode::None,
+    };
+
+    Ok(SystemProxyReport {
+        proxy: SystemProxy {
+            mode,
+            http: read_server("http")?,
+            https: read_server("https")?,
+            socks: read_server("socks")?,
+            pac_url: gsettings_get_string(PROXY_SCHEMA, "autoconfig-url")?,
+            ignore_hosts: parse_string_array(&gsettings_get(PROXY_SCHEMA, "ignore-hosts")?),
+        },
+        env: get_env_vars()?
+            .into_iter()
+            .filter(|v| PROXY_ENV_VARS.contains(&v.key.as_str()))
+            .collect(),
+    })
+}
+
+/// Saves the desktop proxy to GSettings. With `update_env`, the `http_proxy`,
+/// `https_proxy` and `no_proxy` variables in `env.conf` are updated to match.
+#[tauri::command]
+pub fn set_system_proxy(proxy: SystemProxy, update_env: bool) -> Result<(), String> {
+    validate_system_proxy(&proxy)?;
+
+    // Servers and exceptions are kept when switching modes so they can be restored later
+    write_server("http", &proxy.http)?;
+    write_server("https", &proxy.https)?;
+    write_server("socks", &proxy.socks)?;
+    gsettings_set(
+        PROXY_SCHEMA,
+        "autoconfig-url",
+        &gvariant_string(proxy.pac_url.trim()),
+    )?;
+    let hosts: Vec<String> = proxy
+        .ignore_hosts
+        .iter()
+        .map(|h| h.trim())
+        .filter(|h| !h.is_empty())
+        .map(gvariant_string)
+        .collect();
+    let hosts = if hosts.is_empty() {
+        "@as []".to_string()
+    } else {
+        format!("[{}]", hosts.join(", "))
+    };
+    gsettings_set(PROXY_SCHEMA, "ignore-hosts", &hosts)?;
+
+    let mode = match proxy.mode {
+        ProxyMode::None => "none",
+        ProxyMode::Manual => "manual",
+        ProxyMode::Pac => "auto",
+    };
+    gsettings_set(PROXY_SCHEMA, "mode", &gvariant_string(mode))?;
+
+    if update_env {
+        write_proxy_env(&proxy)?;
+    }
+    Ok(())
+}
+
+/// Reads the proxy settings of a NetworkManager connection.
+#[tauri::command]
+pub async fn get_connection_proxy(id: String) -> Result<ConnectionProxy, String> {
+    let settings = network_manager()
+        .await
+        .connection_settings(
+            &id,
+            &["proxy.method", "proxy.pac-url", "proxy.browser-only"],
+        )
+        .await?;
+    let get = |key: &str| settings.get(key).map(|v| v.trim()).unwrap_or("");
+
+    Ok(ConnectionProxy {
+        method: match get("proxy.method") {
+            "auto" => ConnectionProxyMethod::Auto,
+            _ => ConnectionProxyMethod::None,
+        },
+        pac_url: get("proxy.pac-url").to_string(),
+        browser_only: get("proxy.browser-only") == "yes",
+    })
+}
+
+/// Saves the proxy settings of a NetworkManager connection. They apply the next time it is activated.
+#[tauri::command]
+pub async fn set_connection_proxy(
+    id: String,
+    proxy: ConnectionProxy,
+) -> Result<(), ProfileUpdateError> {
+    let pac_url = proxy.pac_url.trim();
+    let mut profile = ProfileBuilder::modify();
+
+    match proxy.method {
+        ConnectionProxyMethod::None => {
+            profile.set("proxy.method", "none");
+            profile.set("proxy.pac-url", "");
+        }
+        ConnectionProxyMethod::Auto => {
+            if let Err(message) = Some(pac_url)
+                .filter(|url| !url.is_empty())
+                .map_or(Ok(()), validate_pac_url)
+            {
+                profile.error("pac_url", message);
+            }
+            profile.set("proxy.method", "auto");
+            profile.set("proxy.pac-url", pac_url);
+        }
+    }
+    profile.set(
+        "proxy.browser-only",
+        if proxy.browser_only { "yes" } else { "no" },
+    );
+
+    let changes = profile
+        .build()
+        .map_err(|errors| ProfileUpdateError::Invalid { errors })?;
+    network_manager()
+        .await
+        .modify_connection(&id, &changes)
+        .await
+        .map_err(|message| ProfileUpdateError::Failed { message })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn server(host: &str, port: u16) -> ProxyServer {
+        ProxyServer {
+            host: host.to_string(),
+            port,
+        }
+    }
+
+    fn proxy(mode: ProxyMode) -> SystemProxy {
+        SystemProxy {
+            mode,
+            http: ProxyServer::default(),
+            https: ProxyServer::default(),
+            socks: ProxyServer::default(),
+            pac_url: String::new(),
+            ignore_hosts: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn parses_string_arrays() {
+        assert_eq!(
+            parse_string_array("['localhost', '127.0.0.0/8', '::1']"),
+            ["localhost", "127.0.0.0/8", "::1"]
+        );
+        assert!(parse_string_array("@as []").is_empty());
+        assert_eq!(
+            parse_string_array(r#"['a,b', 'it\'s', "say \"hi\"", 'back\\slash']"#),
+            ["a,b", "it's", "say \"hi\"", "back\\slash"]
+        );
+    }
+
+    #[test]
+    fn round_trips_gvariant_strings() {
+        for value in ["plain", "it's", r"C:\proxy", "a, b"] {
+            assert_eq!(gvariant_strings(&gvariant_string(value)), [value]);
+        }
+        assert_eq!(gvariant_strings("'manual'"), ["manual"]);
+        assert!(gvariant_strings("8080").is_empty());
+    }
+
+    #[test]
+    fn formats_proxy_urls() {
+        assert_eq!(
+            proxy_url(&server("proxy.lan", 3128)).as_deref(),
+            Some("http://proxy.lan:3128")
+        );
+        assert_eq!(
+            proxy_url(&server(" fd00::1 ", 8080)).as_deref(),
+            Some("http://[fd00::1]:8080")
+        );
+        assert_eq!(proxy_url(&server("  ", 8080)), None);
+    }
+
+    #[test]
+    fn validates_system_proxies() {
+        assert!(validate_system_proxy(&proxy(ProxyMode::None)).is_ok());
+
+        let mut pac = proxy(ProxyMode::Pac);
+        assert!(validate_system_proxy(&pac).is_err());
+        pac.pac_url = "https://example.com/proxy.pac".to_string();
+        assert!(validate_system_proxy(&pac).is_ok());
+
+        let mut manual = proxy(ProxyMode::Manual);
+        assert!(validate_system_proxy(&manual)
+            .unwrap_err()
+            .contains("at least one"));
+        manual.http = server("proxy.lan", 0);
+        assert!(validate_system_proxy(&manual)
+            .unwrap_err()
+            .contains("needs a port"));
+        manual.http = server("http://proxy.lan", 3128);
+        assert!(validate_system_proxy(&manual)
+            .unwrap_err()
+            .contains("plain host"));
+        manual.http = server("proxy.lan", 3128);
+        assert!(validate_system_proxy(&manual).is_ok());
+    }
+}
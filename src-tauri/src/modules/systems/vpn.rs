@@ -1,5 +1,9 @@
+use crate::modules::settings::{get_app_settings, write_app_settings};
+use crate::modules::systems::nm::client::{network_manager, NetworkManager};
+use crate::modules::systems::vpn_plugins::{installed_vpn_plugins, VpnItems};
+use crate::modules::systems::wifi::read_wifi_link;
+use crate::modules::systems::wireguard::{export_wireguard_config, get_wireguard_connection};
 use serde::{Deserialize, Serialize};
-use tokio::process::Command;
 
 /// Represents a VPN connection with its metadata.
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -8,42 +12,42 @@ pub struct VpnConnection {
     pub name: String,
     pub active: bool,
     pub type_name: String, // e.g. "wireguard", "openvpn"
+    pub autoconnect: bool,
 }
 
-/// Retrieves a list of configured VPN connections using nmcli.
-#[tauri::command]
-pub async fn get_vpn_connections() -> Result<Vec<VpnConnection>, String> {
-    // nmcli -t -f UUID,NAME,TYPE,ACTIVE connection show
-    let output = Command::new("nmcli")
-        .args(&["-t", "-f", "UUID,NAME,TYPE,ACTIVE", "connection", "show"])
-        .output()
-        .await
-        .map_err(|e| e.to_string())?;
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let mut vpns = Vec::new();
-
-    for line in stdout.lines() {
-        let parts: Vec<&str> = line.split(':').collect();
-        if parts.len() >= 4 {
-            let uuid = parts[0].to_string();
-            let name = parts[1].to_string();
-            let conn_type = parts[2].to_string();
-            let active_str = parts[3];
-
-            // Filter for VPN and WireGuard connections
-            if conn_type == "vpn" || conn_type == "wireguard" {
-                // Determine active status: active_str is usually "--" or "no" if inactive
-                let active = active_str != "no" && active_str != "--" && !active_str.is_empty();
-
-                vpns.push(VpnConnection {
-                    uuid,
-                    name,
-                    active,
-                    type_name: conn_type,
-                });
-            }
-        }
+/// Brings a VPN up whenever Wi-Fi connects to a network that is not trusted.
+/// Stored in `AppSettings`.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct VpnAutoConnectSettings {
+    pub enabled: bool,
+    pub vpn_uuid: String,
+    pub trusted_ssids: Vec<String>,
+}
+
+/// Returns the VPN and WireGuard profiles known to NetworkManager, active ones first.
+pub async fn list_vpn_connections(nm: &dyn NetworkManager) -> Result<Vec<VpnConnection>, String> {
+    let mut vpns: Vec<VpnConnection> = nm
+        .connections()
+        .await?
+        .into_iter()
+        // Filter for VPN and WireGuard connections
+        .filter(|c| c.conn_type == "vpn" || c.conn_type == "wireguard")
+        .map(|c| VpnConnection {
+            uuid: c.uuid,
+            name: c.name,
+            active: c.active,
+            type_name: c.conn_type,
+            autoconnect: false,
+        })
+        .collect();
+
+    for vpn in vpns.iter_mut() {
+        let settings = nm
+            .connection_settings(&vpn.uuid, &["connection.autoconnect"])
+            .await?;
+        vpn.autoconnect = settings
+            .get("connection.autoconnect")
+            .is_some_and(|v| v.trim() != "no");
     }
 
     // Sort active connections to the top
@@ -52,36 +56,50 @@ pub async fn get_vpn_connections() -> Result<Vec<VpnConnection>, String> {
     Ok(vpns)
 }
 
+/// Retrieves a list of configured VPN connections.
+#[tauri::command]
+pub async fn get_vpn_connections() -> Result<Vec<VpnConnection>, String> {
+    list_vpn_connections(network_manager().await.as_ref()).await
+}
+
 /// Activates a VPN connection by its UUID.
 #[tauri::command]
 pub async fn connect_vpn(uuid: String) -> Result<(), String> {
-    let output = Command::new("nmcli")
-        .args(&["connection", "up", &uuid])
-        .output()
-        .await
-        .map_err(|e| e.to_string())?;
-
-    if output.status.success() {
-        Ok(())
-    } else {
-        Err(String::from_utf8_lossy(&output.stderr).to_string())
-    }
+    network_manager().await.activate_connection(&uuid).await
 }
 
 /// Deactivates an active VPN connection by its UUID.
 #[tauri::command]
 pub async fn disconnect_vpn(uuid: String) -> Result<(), String> {
-    let output = Command::new("nmcli")
-        .args(&["connection", "down", &uuid])
-        .output()
-        .await
-        .map_err(|e| e.to_string())?;
+    network_manager().await.deactivate_connection(&uuid).await
+}
 
-    if output.status.success() {
-        Ok(())
-    } else {
-        Err(String::from_utf8_lossy(&output.stderr).to_string())
+/// Stores a username and password with an imported VPN connection, the same way
+/// `build_vpn_profile` does, so no secret agent is asked for them.
+async fn set_vpn_credentials(
+    nm: &dyn NetworkManager,
+    uuid: &str,
+    username: Option<&str>,
+    password: Option<&str>,
+) -> Result<(), String> {
+    if username.is_none() && password.is_none() {
+        return Ok(());
+    }
+    let mut data = VpnItems::default();
+    let mut secrets = VpnItems::default();
+    if let Some(user) = username {
+        data.add("username", user);
+    }
+    if let Some(pass) = password {
+        data.add("password-flags", "0");
+        secrets.add("password", pass);
     }
+
+    let mut changes = vec![("+vpn.data".to_string(), data.join())];
+    if password.is_some() {
+        changes.push(("+vpn.secrets".to_string(), secrets.join()));
+    }
+    nm.modify_connection(uuid, &changes).await
 }
 
 /// Imports a VPN configuration file and optionally sets credentials.
@@ -93,82 +111,200 @@ pub async fn import_vpn(
     password: Option<String>,
 ) -> Result<String, String> {
     // Detect VPN type if not provided
-    let type_str = if let Some(t) = vpn_type {
-        t
-    } else {
-        if file_path.ends_with(".ovpn") {
-            "openvpn".to_string()
-        } else if file_path.ends_with(".conf") || file_path.ends_with(".wg") {
+    let type_str = match vpn_type {
+        Some(t) => t,
+        None if file_path.ends_with(".ovpn") => "openvpn".to_string(),
+        None if file_path.ends_with(".conf") || file_path.ends_with(".wg") => {
             "wireguard".to_string()
-        } else {
-            return Err("VPN type required and could not be detected from extension.".to_string());
+        }
+        None => {
+            return Err("VPN type required and could not be detected from extension.".to_string())
         }
     };
 
-    // Import the configuration using nmcli
-    let output = Command::new("nmcli")
-        .args(&[
-            "connection",
-            "import",
-            "type",
-            &type_str,
-            "file",
-            &file_path,
-        ])
-        .output()
-        .await
-        .map_err(|e| e.to_string())?;
-
-    if !output.status.success() {
-        return Err(String::from_utf8_lossy(&output.stderr).to_string());
-    }
-
-    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-
-    // If credentials are provided for OpenVPN, modify the connection properties
-    if (username.is_some() || password.is_some()) && type_str == "openvpn" {
-        // Extract UUID from nmcli output: "Connection '...' (UUID) successfully added."
-        if let Some(start_idx) = stdout.rfind('(') {
-            if let Some(end_idx) = stdout.rfind(')') {
-                if start_idx < end_idx {
-                    let uuid = &stdout[start_idx + 1..end_idx];
-
-                    // Set Username
-                    if let Some(user) = username {
-                        let _ = Command::new("nmcli")
-                            .args(&[
-                                "connection",
-                                "modify",
-                                uuid,
-                                "+vpn.data",
-                                &format!("username={}", user),
-                            ])
-                            .output()
-                            .await;
-                    }
-
-                    // Set Password and fix secret storage flags
-                    if let Some(pass) = password {
-                        let _ = Command::new("nmcli")
-                            .args(&[
-                                "connection",
-                                "modify",
-                                uuid,
-                                "+vpn.secrets",
-                                &format!("password={}", pass),
-                            ])
-                            .output()
-                            .await;
-
-                        let _ = Command::new("nmcli")
-                            .args(&["connection", "modify", uuid, "vpn.secrets-flags", "0"])
-                            .output()
-                            .await;
-                    }
-                }
-            }
-        }
+    // WireGuard is built into NetworkManager, everything else needs its plugin
+    if type_str != "wireguard" && !installed_vpn_plugins().iter().any(|p| p.name == type_str) {
+        return Err(format!(
+            "The NetworkManager {} plugin is not installed",
+            type_str
+        ));
+    }
+
+    // Import the configuration through NetworkManager's VPN plugins
+    let nm = network_manager().await;
+    let uuid = nm.import_connection(&type_str, &file_path).await?;
+
+    if type_str == "openvpn" {
+        set_vpn_credentials(nm.as_ref(), &uuid, username.as_deref(), password.as_deref()).await?;
     }
 
     Ok("Imported successfully".to_string())
 }
+
+/// Deletes a VPN connection profile.
+#[tauri::command]
+pub async fn delete_vpn(uuid: String) -> Result<(), String> {
+    network_manager().await.delete_connection(&uuid).await
+}
+
+/// Renames a VPN connection.
+#[tauri::command]
+pub async fn rename_vpn(uuid: String, name: String) -> Result<(), String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("A connection name is required".to_string());
+    }
+    network_manager()
+        .await
+        .modify_connection(&uuid, &[("connection.id".to_string(), name.to_string())])
+        .await
+}
+
+/// Enables or disables automatic activation of a VPN connection.
+#[tauri::command]
+pub async fn set_vpn_autoconnect(uuid: String, enabled: bool) -> Result<(), String> {
+    let value = if enabled { "yes" } else { "no" };
+    network_manager()
+        .await
+        .modify_connection(
+            &uuid,
+            &[("connection.autoconnect".to_string(), value.to_string())],
+        )
+        .await
+}
+
+/// Exports a VPN connection to a file: a regenerated `wg-quick` file for WireGuard,
+/// the VPN plugin's own format (e.g. `.ovpn` for OpenVPN) otherwise.
+#[tauri::command]
+pub async fn export_vpn(uuid: String, file_path: String) -> Result<(), String> {
+    let nm = network_manager().await;
+    let conn = nm
+        .connections()
+        .await?
+        .into_iter()
+        .find(|c| c.uuid == uuid)
+        .ok_or_else(|| format!("Connection '{}' not found", uuid))?;
+
+    if conn.conn_type == "wireguard" {
+        let config = get_wireguard_connection(uuid).await?;
+        export_wireguard_config(config, file_path)
+    } else {
+        nm.export_connection(&uuid, &file_path).await
+    }
+}
+
+/// Activates the configured VPN when `ssid` is not trusted. Does nothing when the
+/// setting is off, Wi-Fi is disconnected (`ssid` is None) or the VPN is already up.
+pub async fn apply_untrusted_network_policy(
+    nm: &dyn NetworkManager,
+    ssid: Option<&str>,
+) -> Result<(), String> {
+    let settings = get_app_settings().vpn_auto_connect;
+    let Some(ssid) = ssid else {
+        return Ok(());
+    };
+    if !settings.enabled || settings.trusted_ssids.iter().any(|t| t == ssid) {
+        return Ok(());
+    }
+
+    let vpn_active = nm
+        .connections()
+        .await?
+        .into_iter()
+        .any(|c| c.uuid == settings.vpn_uuid && c.active);
+    if vpn_active {
+        return Ok(());
+    }
+    nm.activate_connection(&settings.vpn_uuid).await
+}
+
+/// Returns the settings for bringing a VPN up on untrusted Wi-Fi networks.
+#[tauri::command]
+pub fn get_vpn_auto_connect() -> VpnAutoConnectSettings {
+    get_app_settings().vpn_auto_connect
+}
+
+/// Saves the untrusted network settings and applies them to the current Wi-Fi connection.
+#[tauri::command]
+pub async fn set_vpn_auto_connect(settings: VpnAutoConnectSettings) -> Result<(), String> {
+    let nm = network_manager().await;
+    if settings.enabled
+        && !list_vpn_connections(nm.as_ref())
+            .await?
+            .iter()
+            .any(|v| v.uuid == settings.vpn_uuid)
+    {
+        return Err("Select the VPN to bring up on untrusted networks".to_string());
+    }
+
+    let mut app_settings = get_app_settings();
+    app_settings.vpn_auto_connect = VpnAutoConnectSettings {
+        trusted_ssids: settings
+            .trusted_ssids
+            .iter()
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect(),
+        ..settings
+    };
+    write_app_settings(&app_settings)?;
+
+    let link = read_wifi_link(nm.as_ref()).await?;
+    apply_untrusted_network_policy(nm.as_ref(), link.as_ref().map(|l| l.ssid.as_str())).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::systems::nm::client::ConnectionProfile;
+    use crate::modules::systems::nm::fake::{FakeNetworkManager, FakeState};
+
+    fn fake() -> FakeNetworkManager {
+        let mut state = FakeState::default();
+        state.connections.push(ConnectionProfile {
+            uuid: "office".to_string(),
+            name: "office".to_string(),
+            conn_type: "vpn".to_string(),
+            active: false,
+            device: String::new(),
+        });
+        FakeNetworkManager::new(state)
+    }
+
+    #[tokio::test]
+    async fn stores_credentials_with_the_connection() {
+        let nm = fake();
+        set_vpn_credentials(&nm, "office", Some("alice"), Some("pa,ss"))
+            .await
+            .unwrap();
+
+        let state = nm.state();
+        let settings = &state.settings["office"];
+        assert_eq!(settings["vpn.data"], "username = alice, password-flags = 0");
+        assert_eq!(settings["vpn.secrets"], "password = pa\\,ss");
+    }
+
+    #[tokio::test]
+    async fn skips_missing_credentials() {
+        let nm = fake();
+        set_vpn_credentials(&nm, "office", Some("alice"), None)
+            .await
+            .unwrap();
+        set_vpn_credentials(&nm, "office", None, None)
+            .await
+            .unwrap();
+
+        let state = nm.state();
+        let settings = &state.settings["office"];
+        assert_eq!(settings["vpn.data"], "username = alice");
+        assert!(!settings.contains_key("vpn.secrets"));
+    }
+
+    #[tokio::test]
+    async fn reports_failures() {
+        let nm = fake();
+        assert!(set_vpn_credentials(&nm, "missing", Some("alice"), None)
+            .await
+            .is_err());
+    }
+}
@@ -0,0 +1,698 @@
+use crate::modules::systems::nm::client::network_manager;
+use crate::modules::systems::nm::profile::{ProfileBuilder, ProfileError, ProfileUpdateError};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+/// Directories NetworkManager reads VPN plugin descriptions from.
+const PLUGIN_DIRS: [&str; 2] = ["/usr/lib/NetworkManager/VPN", "/etc/NetworkManager/VPN"];
+
+/// Display names of common plugins; others are listed by their plugin name.
+const KNOWN_PLUGINS: [(&str, &str); 6] = [
+    ("openvpn", "OpenVPN"),
+    (
+        "openconnect",
+        "OpenConnect (AnyConnect, GlobalProtect, ...)",
+    ),
+    ("l2tp", "L2TP/IPsec"),
+    ("strongswan", "IPsec/IKEv2 (strongSwan)"),
+    ("pptp", "PPTP"),
+    ("vpnc", "Cisco VPN (vpnc)"),
+];
+
+/// An installed NetworkManager VPN plugin.
+#[derive(Debug, Serialize, Clone)]
+pub struct VpnPlugin {
+    pub name: String,    // e.g. "openconnect", also the `nmcli connection import type`
+    pub service: String, // D-Bus service, used as `vpn.service-type`
+    pub label: String,
+    pub can_create: bool, // `create_vpn_connection` has a form for it
+}
+
+/// Server protocols spoken by OpenConnect.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OpenConnectProtocol {
+    /// Cisco AnyConnect and ocserv.
+    Anyconnect,
+    /// Palo Alto GlobalProtect.
+    Gp,
+    /// Juniper Network Connect.
+    Nc,
+    /// Pulse Connect Secure.
+    Pulse,
+    /// Fortinet FortiGate.
+    Fortinet,
+}
+
+/// How strongSwan authenticates the client.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StrongswanMethod {
+    /// Username and password (EAP).
+    Eap,
+    /// Client certificate and private key.
+    Key,
+    /// Pre-shared key.
+    Psk,
+}
+
+/// Fields of a new VPN connection, by plugin.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum VpnCreateRequest {
+    Openconnect {
+        name: String,
+        gateway: String,
+        protocol: OpenConnectProtocol,
+        #[serde(default)]
+        ca_cert: Option<String>,
+        #[serde(default)]
+        user_cert: Option<String>,
+        #[serde(default)]
+        private_key: Option<String>,
+    },
+    L2tp {
+        name: String,
+        gateway: String,
+        username: String,
+        #[serde(default)]
+        password: Option<String>,
+        #[serde(default)]
+        ipsec_psk: Option<String>, // IPsec is enabled when a pre-shared key is given
+    },
+    Strongswan {
+        name: String,
+        gateway: String,
+        method: StrongswanMethod,
+        #[serde(default)]
+        username: Option<String>,
+        #[serde(default)]
+        password: Option<String>, // EAP password, or the PSK for `psk`
+        #[serde(default)]
+        ca_cert: Option<String>, // server certificate or its CA
+        #[serde(default)]
+        user_cert: Option<String>,
+        #[serde(default)]
+        private_key: Option<String>,
+    },
+    Pptp {
+        name: String,
+        gateway: String,
+        username: String,
+        #[serde(default)]
+        password: Option<String>,
+    },
+}
+
+impl VpnCreateRequest {
+    /// The plugin that handles the connection.
+    fn plugin(&self) -> &'static str {
+        match self {
+            VpnCreateRequest::Openconnect { .. } => "openconnect",
+            VpnCreateRequest::L2tp { .. } => "l2tp",
+            VpnCreateRequest::Strongswan { .. } => "strongswan",
+            VpnCreateRequest::Pptp { .. } => "pptp",
+        }
+    }
+}
+
+/// Reads `name` and `service` from the `[VPN Connection]` group of a `.name` file.
+fn parse_plugin_file(content: &str) -> Option<(String, String)> {
+    let mut in_group = false;
+    let (mut name, mut service) = (None, None);
+
+    for line in content.lines() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            in_group = line == "[VPN Connection]";
+            continue;
+        }
+        if !in_group {
+            continue;
+        }
+        match line
+            .split_once('=')
+            .map(|(key, value)| (key.trim_end(), value))
+        {
+            Some(("name", value)) => name = Some(value.trim().to_string()),
+            Some(("service", value)) => service = Some(value.trim().to_string()),
+            _ => {}
+        }
+    }
+    Some((name?, service?))
+}
+
+/// Discovers the installed VPN plugins from their `.name` files.
+pub fn installed_vpn_plugins() -> Vec<VpnPlugin> {
+    let mut plugins: Vec<VpnPlugin> = Vec::new();
+
+    for dir in PLUGIN_DIRS {
+        let Ok(entries) = fs::read_dir(Path::new(dir)) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("name") {
+                continue;
+            }
+            let Some((name, service)) = fs::read_to_string(&path)
+                .ok()
+                .and_then(|content| parse_plugin_file(&content))
+            else {
+                continue;
+            };
+            // The first directory wins, like NetworkManager's lookup
+            if plugins.iter().any(|p| p.name == name) {
+                continue;
+            }
+
+            let known = KNOWN_PLUGINS.iter().find(|(n, _)| *n == name);
+            plugins.push(VpnPlugin {
+                label: known.map_or_else(|| name.clone(), |(_, label)| label.to_string()),
+                can_create: matches!(
+                    name.as_str(),
+                    "openconnect" | "l2tp" | "strongswan" | "pptp"
+                ),
+                name,
+                service,
+            });
+        }
+    }
+
+    plugins.sort_by(|a, b| a.label.cmp(&b.label));
+    plugins
+}
+
+/// Lists the VPN plugins installed on this system.
+#[tauri::command]
+pub fn get_vpn_plugins() -> Vec<VpnPlugin> {
+    installed_vpn_plugins()
+}
+
+/// Returns the trimmed value when it is non-empty.
+fn non_empty(value: &Option<String>) -> Option<&str> {
+    value.as_deref().map(str::trim).filter(|v| !v.is_empty())
+}
+
+/// Collects `vpn.data` or `vpn.secrets` items in nmcli's `key = value, key = value` form.
+#[derive(Default)]
+pub(crate) struct VpnItems(Vec<String>);
+
+impl VpnItems {
+    pub(crate) fn add(&mut self, key: &str, value: &str) {
+        // Commas separate items, so they are escaped inside values
+        self.0
+            .push(format!("{} = {}", key, value.replace(',', "\\,")));
+    }
+
+    pub(crate) fn join(&self) -> String {
+        self.0.join(", ")
+    }
+}
+
+/// Checks that a certificate or key file exists before storing its path.
+fn add_file(
+    profile: &mut ProfileBuilder,
+    data: &mut VpnItems,
+    field: &str,
+    key: &str,
+    path: Option<&str>,
+) {
+    if let Some(path) = path {
+        if Path::new(path).is_file() {
+            data.add(key, path);
+        } else {
+            profile.error(field, format!("File not found: {}", path));
+        }
+    }
+}
+
+/// Validates a create request and turns it into NetworkManager settings.
+pub fn build_vpn_profile(
+    request: &VpnCreateRequest,
+    plugin: &VpnPlugin,
+) -> Result<Vec<(String, String)>, Vec<ProfileError>> {
+    let mut data = VpnItems::default();
+    let mut secrets = VpnItems::default();
+
+    let (name, gateway) = match request {
+        VpnCreateRequest::Openconnect { name, gateway, .. }
+        | VpnCreateRequest::L2tp { name, gateway, .. }
+        | VpnCreateRequest::Strongswan { name, gateway, .. }
+        | VpnCreateRequest::Pptp { name, gateway, .. } => (name, gateway.trim()),
+    };
+    let mut profile = ProfileBuilder::new("vpn", name);
+    profile.set("vpn.service-type", plugin.service.as_str());
+    if gateway.is_empty() {
+        profile.error("gateway", "A server address is required");
+    }
+
+    match request {
+        VpnCreateRequest::Openconnect {
+            protocol,
+            ca_cert,
+            user_cert,
+            private_key,
+            ..
+        } => {
+            data.add("gateway", gateway);
+            let protocol = match protocol {
+                OpenConnectProtocol::Anyconnect => "anyconnect",
+                OpenConnectProtocol::Gp => "gp",
+                OpenConnectProtocol::Nc => "nc",
+                OpenConnectProtocol::Pulse => "pulse",
+                OpenConnectProtocol::Fortinet => "fortinet",
+            };
+            data.add("protocol", protocol);
+            add_file(
+                &mut profile,
+                &mut data,
+                "ca_cert",
+                "cacert",
+                non_empty(ca_cert),
+            );
+            add_file(
+                &mut profile,
+                &mut data,
+                "user_cert",
+                "usercert",
+                non_empty(user_cert),
+            );
+            add_file(
+                &mut profile,
+                &mut data,
+                "private_key",
+                "userkey",
+                non_empty(private_key),
+            );
+            // The server is asked for credentials interactively on each connect
+            data.add("cookie-flags", "2");
+        }
+        VpnCreateRequest::L2tp {
+            username,
+            password,
+            ipsec_psk,
+            ..
+        } => {
+            if username.trim().is_empty() {
+                profile.error("username", "A username is required");
+            }
+            data.add("gateway", gateway);
+            data.add("user", username.trim());
+            if let Some(password) = non_empty(password) {
+                data.add("password-flags", "0");
+                secrets.add("password", password);
+            }
+            if let Some(psk) = non_empty(ipsec_psk) {
+                data.add("ipsec-enabled", "yes");
+                data.add("ipsec-psk-flags", "0");
+                secrets.add("ipsec-psk", psk);
+            }
+        }
+        VpnCreateRequest::Strongswan {
+            method,
+            username,
+            password,
+            ca_cert,
+            user_cert,
+            private_key,
+            ..
+        } => {
+            data.add("address", gateway);
+            // Request an inner IP address from the server
+            data.add("virtual", "yes");
+            add_file(
+                &mut profile,
+                &mut data,
+                "ca_cert",
+                "certificate",
+                non_empty(ca_cert),
+            );
+            match method {
+                StrongswanMethod::Eap => {
+                    data.add("method", "eap");
+                    match non_empty(username) {
+                        Some(user) => data.add("user", user),
+                        None => {
+                            profile.error("username", "A username is required");
+                        }
+                    }
+                    if let Some(password) = non_empty(password) {
+                        data.add("password-flags", "0");
+                        secrets.add("password", password);
+                    }
+                }
+                StrongswanMethod::Key => {
+                    data.add("method", "key");
+                    if non_empty(user_cert).is_none() {
+                        profile.error("user_cert", "A client certificate is required");
+                    }
+                    if non_empty(private_key).is_none() {
+                        profile.error("private_key", "A private key is required");
+                    }
+                    add_file(
+                        &mut profile,
+                        &mut data,
+                        "user_cert",
+                        "usercert",
+                        non_empty(user_cert),
+                    );
+                    add_file(
+                        &mut profile,
+                        &mut data,
+                        "private_key",
+                        "userkey",
+                        non_empty(private_key),
+                    );
+                }
+                StrongswanMethod::Psk => {
+                    data.add("method", "psk");
+                    match non_empty(password) {
+                        Some(psk) => {
+                            data.add("password-flags", "0");
+                            secrets.add("password", psk);
+                        }
+                        None => {
+                            profile.error("password", "A pre-shared key is required");
+                        }
+                    }
+                }
+            }
+        }
+        VpnCreateRequest::Pptp {
+            username, password, ..
+        } => {
+            if username.trim().is_empty() {
+                profile.error("username", "A username is required");
+            }
+            data.add("gateway", gateway);
+            data.add("user", username.trim());
+            data.add("require-mppe", "yes");
+            if let Some(password) = non_empty(password) {
+                data.add("password-flags", "0");
+                secrets.add("password", password);
+            }
+        }
+    }
+
+    profile.set("vpn.data", data.join());
+    if !secrets.0.is_empty() {
+        profile.set("vpn.secrets", secrets.join());
+    }
+    profile.build()
+}
+
+/// Creates a VPN connection through an installed plugin and returns its UUID.
+#[tauri::command]
+pub async fn create_vpn_connection(
+    request: VpnCreateRequest,
+) -> Result<String, ProfileUpdateError> {
+    let plugin = installed_vpn_plugins()
+        .into_iter()
+        .find(|p| p.name == request.plugin())
+        .ok_or_else(|| ProfileUpdateError::Failed {
+            message: format!(
+                "The NetworkManager {} plugin is not installed",
+                request.plugin()
+            ),
+        })?;
+
+    let settings = build_vpn_profile(&request, &plugin)
+        .map_err(|errors| ProfileUpdateError::Invalid { errors })?;
+    network_manager()
+        .await
+        .add_connection(&settings)
+        .await
+        .map_err(|message| ProfileUpdateError::Failed { message })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    fn plugin(name: &str) -> VpnPlugin {
+        VpnPlugin {
+            name: name.to_string(),
+            service: format!("org.freedesktop.NetworkManager.{}", name),
+            label: name.to_string(),
+            can_create: true,
+        }
+    }
+
+    fn setting<'a>(settings: &'a [(String, String)], prop: &str) -> Option<&'a str> {
+        settings
+            .iter()
+            .find(|(p, _)| p == prop)
+            .map(|(_, v)| v.as_str())
+    }
+
+    fn error_fields(request: &VpnCreateRequest) -> Vec<String> {
+        build_vpn_profile(request, &plugin(request.plugin()))
+            .unwrap_err()
+            .into_iter()
+            .map(|e| e.field)
+            .collect()
+    }
+
+    #[test]
+    fn parses_plugin_files() {
+        let cases = [
+            (
+                "[VPN Connection]\nname=openconnect\nservice=org.freedesktop.NetworkManager.openconnect\nprogram=/usr/lib/nm-openconnect-service\n",
+                Some(("openconnect", "org.freedesktop.NetworkManager.openconnect")),
+            ),
+            (
+                "# comment\n[libnm]\nname=ignored\n\n[VPN Connection]\n  name = l2tp \nservice=org.freedesktop.NetworkManager.l2tp\n[GNOME]\nname=also-ignored\n",
+                Some(("l2tp", "org.freedesktop.NetworkManager.l2tp")),
+            ),
+            ("[VPN Connection]\nname=pptp\n", None),
+            (
+                "[GNOME]\nname=pptp\nservice=org.freedesktop.NetworkManager.pptp\n",
+                None,
+            ),
+            ("", None),
+        ];
+        for (content, expected) in cases {
+            assert_eq!(
+                parse_plugin_file(content),
+                expected.map(|(n, s)| (n.to_string(), s.to_string())),
+                "{}",
+                content
+            );
+        }
+    }
+
+    #[test]
+    fn escapes_commas_in_items() {
+        let mut items = VpnItems::default();
+        items.add("user", "doe, john");
+        items.add("password-flags", "0");
+        assert_eq!(items.join(), "user = doe\\, john, password-flags = 0");
+    }
+
+    #[test]
+    fn builds_plugin_data_and_secrets() {
+        let ca_cert = env::temp_dir().join(format!("vpn-plugins-ca-{}.pem", std::process::id()));
+        fs::write(&ca_cert, "cert").unwrap();
+        let ca_path = ca_cert.to_string_lossy().into_owned();
+
+        let cases = [
+            (
+                VpnCreateRequest::Openconnect {
+                    name: "Work".to_string(),
+                    gateway: " vpn.example.com ".to_string(),
+                    protocol: OpenConnectProtocol::Gp,
+                    ca_cert: Some(ca_path.clone()),
+                    user_cert: None,
+                    private_key: Some(" ".to_string()),
+                },
+                format!(
+                    "gateway = vpn.example.com, protocol = gp, cacert = {}, cookie-flags = 2",
+                    ca_path
+                ),
+                None,
+            ),
+            (
+                VpnCreateRequest::L2tp {
+                    name: "Work".to_string(),
+                    gateway: "vpn.example.com".to_string(),
+                    username: "alice".to_string(),
+                    password: Some("secret".to_string()),
+                    ipsec_psk: Some("shared,key".to_string()),
+                },
+                "gateway = vpn.example.com, user = alice, password-flags = 0, \
+                 ipsec-enabled = yes, ipsec-psk-flags = 0"
+                    .to_string(),
+                Some("password = secret, ipsec-psk = shared\\,key"),
+            ),
+            (
+                VpnCreateRequest::L2tp {
+                    name: "Work".to_string(),
+                    gateway: "vpn.example.com".to_string(),
+                    username: "alice".to_string(),
+                    password: None,
+                    ipsec_psk: None,
+                },
+                "gateway = vpn.example.com, user = alice".to_string(),
+                None,
+            ),
+            (
+                VpnCreateRequest::Strongswan {
+                    name: "Work".to_string(),
+                    gateway: "ipsec.example.com".to_string(),
+                    method: StrongswanMethod::Eap,
+                    username: Some("alice".to_string()),
+                    password: Some("secret".to_string()),
+                    ca_cert: Some(ca_path.clone()),
+                    user_cert: None,
+                    private_key: None,
+                },
+                format!(
+                    "address = ipsec.example.com, virtual = yes, certificate = {}, \
+                     method = eap, user = alice, password-flags = 0",
+                    ca_path
+                ),
+                Some("password = secret"),
+            ),
+            (
+                VpnCreateRequest::Strongswan {
+                    name: "Work".to_string(),
+                    gateway: "ipsec.example.com".to_string(),
+                    method: StrongswanMethod::Key,
+                    username: None,
+                    password: None,
+                    ca_cert: None,
+                    user_cert: Some(ca_path.clone()),
+                    private_key: Some(ca_path.clone()),
+                },
+                format!(
+                    "address = ipsec.example.com, virtual = yes, method = key, \
+                     usercert = {0}, userkey = {0}",
+                    ca_path
+                ),
+                None,
+            ),
+            (
+                VpnCreateRequest::Strongswan {
+                    name: "Work".to_string(),
+                    gateway: "ipsec.example.com".to_string(),
+                    method: StrongswanMethod::Psk,
+                    username: None,
+                    password: Some("psk".to_string()),
+                    ca_cert: None,
+                    user_cert: None,
+                    private_key: None,
+                },
+                "address = ipsec.example.com, virtual = yes, method = psk, password-flags = 0"
+                    .to_string(),
+                Some("password = psk"),
+            ),
+            (
+                VpnCreateRequest::Pptp {
+                    name: "Work".to_string(),
+                    gateway: "pptp.example.com".to_string(),
+                    username: "alice".to_string(),
+                    password: Some("secret".to_string()),
+                },
+                "gateway = pptp.example.com, user = alice, require-mppe = yes, password-flags = 0"
+                    .to_string(),
+                Some("password = secret"),
+            ),
+        ];
+
+        let results: Vec<_> = cases
+            .iter()
+            .map(|(request, _, _)| build_vpn_profile(request, &plugin(request.plugin())))
+            .collect();
+        fs::remove_file(&ca_cert).unwrap();
+
+        for ((request, data, secrets), result) in cases.iter().zip(results) {
+            let settings = result.unwrap_or_else(|e| panic!("{:?}: {:?}", request, e));
+            let service = format!("org.freedesktop.NetworkManager.{}", request.plugin());
+            assert_eq!(setting(&settings, "connection.type"), Some("vpn"));
+            assert_eq!(
+                setting(&settings, "vpn.service-type"),
+                Some(service.as_str())
+            );
+            assert_eq!(
+                setting(&settings, "vpn.data"),
+                Some(data.as_str()),
+                "{:?}",
+                request
+            );
+            assert_eq!(setting(&settings, "vpn.secrets"), *secrets, "{:?}", request);
+        }
+    }
+
+    #[test]
+    fn rejects_missing_fields() {
+        let missing = "/nonexistent/vpn-plugins-test.pem".to_string();
+        let cases = [
+            (
+                VpnCreateRequest::Openconnect {
+                    name: "Work".to_string(),
+                    gateway: " ".to_string(),
+                    protocol: OpenConnectProtocol::Anyconnect,
+                    ca_cert: Some(missing.clone()),
+                    user_cert: None,
+                    private_key: None,
+                },
+                vec!["gateway", "ca_cert"],
+            ),
+            (
+                VpnCreateRequest::L2tp {
+                    name: "Work".to_string(),
+                    gateway: "vpn.example.com".to_string(),
+                    username: " ".to_string(),
+                    password: None,
+                    ipsec_psk: None,
+                },
+                vec!["username"],
+            ),
+            (
+                VpnCreateRequest::Strongswan {
+                    name: "Work".to_string(),
+                    gateway: "ipsec.example.com".to_string(),
+                    method: StrongswanMethod::Eap,
+                    username: None,
+                    password: None,
+                    ca_cert: None,
+                    user_cert: None,
+                    private_key: None,
+                },
+                vec!["username"],
+            ),
+            (
+                VpnCreateRequest::Strongswan {
+                    name: "Work".to_string(),
+                    gateway: "ipsec.example.com".to_string(),
+                    method: StrongswanMethod::Key,
+                    username: None,
+                    password: None,
+                    ca_cert: None,
+                    user_cert: None,
+                    private_key: Some(missing),
+                },
+                vec!["user_cert", "private_key"],
+            ),
+            (
+                VpnCreateRequest::Strongswan {
+                    name: "Work".to_string(),
+                    gateway: "ipsec.example.com".to_string(),
+                    method: StrongswanMethod::Psk,
+                    username: None,
+                    password: Some(" ".to_string()),
+                    ca_cert: None,
+                    user_cert: None,
+                    private_key: None,
+                },
+                vec!["password"],
+            ),
+        ];
+        for (request, expected) in cases {
+            assert_eq!(error_fields(&request), expected, "{:?}", request);
+        }
+    }
+}
@@ -1,310 +1,978 @@
+use crate::modules::systems::nm::client::{
+    frequency_band, frequency_channel, network_manager, signal_bars, AccessPoint, NetworkManager,
+};
+use crate::modules::systems::nm::ip_config::{
+    read_ip_config, write_ip_config, ActiveIpConfig, IpConfig, IpConfigReport,
+};
+use crate::modules::systems::nm::profile::{
+    Eap8021x, ProfileBuilder, ProfileError, ProfileUpdateError,
+};
+use qrcode::render::svg;
+use qrcode::QrCode;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use tokio::process::Command;
 
 /// Represents a Wi-Fi network with its signal strength and status.
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct WifiNetwork {
-    ssid: String,
-    security: String,
-    bars: String,
-    signal: u8,
-    active: bool,
+    pub ssid: String,
+    pub security: String,
+    pub bars: String,
+    pub signal: u8,
+    pub active: bool,
 }
 
-/// Configuration for a Wi-Fi connection (IPv4 settings).
-#[derive(Debug, Serialize, Deserialize)]
-pub struct WifiConfig {
-    method: String,
-    ip_address: String,
-    prefix: i32,
-    gateway: String,
-    dns: String,
+/// One access point (BSSID) of a network.
+#[derive(Debug, Serialize, Clone)]
+pub struct BssidInfo {
+    pub bssid: String,
+    pub signal: u8,
+    pub bars: String,
+    pub frequency: u32, // MHz
+    pub band: String,   // "2.4 GHz", "5 GHz" or "6 GHz"
+    pub channel: u32,
+    pub max_bitrate: u32, // kbit/s
+    pub security: String,
+    pub wpa_flags: String,
+    pub rsn_flags: String,
+    pub last_seen: Option<u32>, // seconds ago, None when unknown
+    pub device: String,
+    pub active: bool,
 }
 
-/// Checks if Wi-Fi radio is currently enabled.
-#[tauri::command]
-pub async fn get_wifi_status() -> bool {
-    let output = Command::new("nmcli")
-        .args(&["radio", "wifi"])
-        .output()
-        .await;
-
-    match output {
-        Ok(o) => {
-            let stdout = String::from_utf8_lossy(&o.stdout);
-            stdout.trim() == "enabled"
+/// A network with every access point that broadcasts it.
+#[derive(Debug, Serialize)]
+pub struct WifiNetworkDetails {
+    pub ssid: String,
+    pub security: String,
+    pub active: bool,
+    pub access_points: Vec<BssidInfo>, // strongest first
+}
+
+/// The current Wi-Fi link with its radio details and addressing.
+#[derive(Debug, Serialize, Clone)]
+pub struct WifiLinkInfo {
+    pub uuid: String,
+    pub name: String,
+    pub ssid: String,
+    pub interface: String,
+    pub access_point: Option<BssidInfo>,
+    pub bitrate: Option<u32>, // current transmit rate in kbit/s
+    pub ip: ActiveIpConfig,
+}
+
+/// Frequency band of a Wi-Fi connection, using NetworkManager's names.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WifiBand {
+    /// Any band.
+    Auto,
+    /// 2.4 GHz.
+    Bg,
+    /// 5 GHz.
+    A,
+}
+
+impl WifiBand {
+    /// The `802-11-wireless.band` value; empty clears the setting.
+    pub fn as_setting(self) -> &'static str {
+        match self {
+            WifiBand::Auto => "",
+            WifiBand::Bg => "bg",
+            WifiBand::A => "a",
+        }
+    }
+
+    pub fn from_setting(value: &str) -> Self {
+        match value {
+            "bg" => WifiBand::Bg,
+            "a" => WifiBand::A,
+            _ => WifiBand::Auto,
         }
-        Err(_) => false,
     }
 }
 
-/// Toggles Wi-Fi radio power state.
+/// Configuration for a Wi-Fi connection (IPv4 and IPv6 settings).
+pub type WifiConfig = IpConfig;
+
+/// A saved Wi-Fi profile with the settings that control how it is used.
+#[derive(Debug, Serialize)]
+pub struct SavedNetwork {
+    pub uuid: String,
+    pub name: String,
+    pub ssid: String,
+    pub active: bool,
+    pub last_used: Option<i64>, // Unix timestamp of the last successful activation
+    pub autoconnect: bool,
+    pub autoconnect_priority: i32,
+    pub metered: String, // "yes", "no", "guess-yes", "guess-no" or "unknown"
+    pub mac_randomization: String, // cloned-mac-address: "preserve", "permanent", "random", "stable" or a MAC
+    pub bssid: String,             // access point the profile is pinned to, empty for any
+    pub band: WifiBand,
+}
+
+/// Whether NetworkManager should treat a connection as metered.
+#[derive(Debug, Deserialize, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum Metered {
+    Yes,
+    No,
+    Unknown,
+}
+
+/// The MAC address a connection presents to the network.
+#[derive(Debug, Deserialize, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum MacRandomization {
+    /// Keep whatever address the device currently has.
+    Preserve,
+    /// Use the hardware address.
+    Permanent,
+    /// A new random address on every connection.
+    Random,
+    /// A random address that stays the same for this network.
+    Stable,
+}
+
+/// How a network is secured, with the credentials needed to join it.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum WifiSecurity {
+    Open,
+    /// WPA/WPA2-Personal.
+    WpaPsk {
+        password: String,
+    },
+    /// WPA3-Personal (SAE).
+    Sae {
+        password: String,
+    },
+    /// WPA2/WPA3-Enterprise.
+    Enterprise {
+        eap: Eap8021x,
+    },
+}
+
+/// Everything needed to create a Wi-Fi profile, including for networks not in the scan list.
+#[derive(Debug, Deserialize)]
+pub struct WifiConnectRequest {
+    pub ssid: String,
+    #[serde(default)]
+    pub hidden: bool,
+    pub security: WifiSecurity,
+}
+
+/// Reasons a Wi-Fi profile could not be created or activated.
+#[derive(Debug, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum WifiConnectError {
+    /// One or more fields of the request are missing or invalid.
+    Invalid { errors: Vec<ProfileError> },
+    /// The network is not in range (or, for hidden networks, did not answer).
+    NotFound { ssid: String },
+    /// The credentials were rejected or missing.
+    AuthFailed { message: String },
+    /// Activation did not finish in time.
+    Timeout { message: String },
+    /// NetworkManager reported another error.
+    Failed { message: String },
+}
+
+impl WifiConnectError {
+    /// Sorts a NetworkManager error message into a category the UI can act on.
+    fn from_message(ssid: &str, message: String) -> Self {
+        let lower = message.to_lowercase();
+        if lower.contains("no network with") || lower.contains("not found") {
+            WifiConnectError::NotFound {
+                ssid: ssid.to_string(),
+            }
+        } else if lower.contains("secrets were required")
+            || lower.contains("no-secrets")
+            || lower.contains("password")
+            || lower.contains("802-1x")
+            || lower.contains("authentication")
+        {
+            WifiConnectError::AuthFailed { message }
+        } else if lower.contains("timed out") || lower.contains("timeout") {
+            WifiConnectError::Timeout { message }
+        } else {
+            WifiConnectError::Failed { message }
+        }
+    }
+}
+
+/// Checks if Wi-Fi radio is currently enabled.
 #[tauri::command]
-pub async fn toggle_wifi(enable: bool) -> Result<(), String> {
-    let state = if enable { "on" } else { "off" };
-    Command::new("nmcli")
-        .args(&["radio", "wifi", state])
-        .output()
+pub async fn get_wifi_status() -> bool {
+    network_manager()
+        .await
+        .wireless_enabled()
         .await
-        .map_err(|e| e.to_string())?;
-    Ok(())
+        .unwrap_or(false)
 }
 
-/// Scans for available Wi-Fi networks and returns a deduplicated list.
+/// Toggles Wi-Fi radio power state.
 #[tauri::command]
-pub async fn scan_wifi() -> Result<Vec<WifiNetwork>, String> {
-    // nmcli -t -f ACTIVE,SSID,SECURITY,BARS,SIGNAL dev wifi list
-    let output = Command::new("nmcli")
-        .args(&[
-            "-t",
-            "-f",
-            "ACTIVE,SSID,SECURITY,BARS,SIGNAL",
-            "dev",
-            "wifi",
-            "list",
-        ])
-        .output()
-        .await
-        .map_err(|e| e.to_string())?;
+pub async fn toggle_wifi(enable: bool) -> Result<(), String> {
+    network_manager().await.set_wireless_enabled(enable).await
+}
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
+/// Collapses access points into one network per SSID, preferring the active
+/// access point, then the strongest signal. Hidden networks are skipped.
+pub fn dedup_networks(access_points: Vec<AccessPoint>) -> Vec<WifiNetwork> {
     let mut map: HashMap<String, WifiNetwork> = HashMap::new();
 
-    for line in stdout.lines() {
-        // Parse colon-separated output carefully (SSID may contain colons)
-        let parts: Vec<&str> = line.split(':').collect();
-
-        if parts.len() >= 5 {
-            let active = parts[0] == "yes";
-            let signal: u8 = parts.last().unwrap_or(&"0").parse().unwrap_or(0);
-            let bars = parts[parts.len() - 2].to_string();
-            let security = parts[parts.len() - 3].to_string();
-
-            // SSID is between the 'active' field and the 'security' field
-            let ssid_parts = &parts[1..parts.len() - 3];
-            let ssid = ssid_parts.join(":");
-
-            if !ssid.is_empty() {
-                let network = WifiNetwork {
-                    ssid: ssid.clone(),
-                    security,
-                    bars,
-                    signal,
-                    active,
+    for ap in access_points {
+        if ap.ssid.is_empty() {
+            continue;
+        }
+
+        let network = WifiNetwork {
+            ssid: ap.ssid.clone(),
+            security: ap.security,
+            bars: signal_bars(ap.signal),
+            signal: ap.signal,
+            active: ap.active,
+        };
+
+        // Deduplicate by SSID, preferring the active connection or the strongest signal
+        match map.get(&ap.ssid) {
+            Some(existing) => {
+                let is_better = if network.active && !existing.active {
+                    true
+                } else if network.active == existing.active {
+                    network.signal > existing.signal
+                } else {
+                    false
                 };
 
-                // Deduplicate by SSID, preferring the active connection or the strongest signal
-                match map.get(&ssid) {
-                    Some(existing) => {
-                        let is_better = if network.active && !existing.active {
-                            true
-                        } else if network.active == existing.active {
-                            network.signal > existing.signal
-                        } else {
-                            false
-                        };
-
-                        if is_better {
-                            map.insert(ssid, network);
-                        }
-                    }
-                    None => {
-                        map.insert(ssid, network);
-                    }
+                if is_better {
+                    map.insert(ap.ssid, network);
                 }
             }
+            None => {
+                map.insert(ap.ssid, network);
+            }
         }
     }
 
     let mut networks: Vec<WifiNetwork> = map.into_values().collect();
     // Sort: Active first, then Signal strength descending
     networks.sort_by(|a, b| b.active.cmp(&a.active).then(b.signal.cmp(&a.signal)));
+    networks
+}
 
-    Ok(networks)
+/// Requests a rescan and lists the visible networks, deduplicated by SSID.
+pub async fn list_wifi_networks(nm: &dyn NetworkManager) -> Result<Vec<WifiNetwork>, String> {
+    // Scans requested too often are rejected; the cached results are still valid
+    let _ = nm.request_scan().await;
+    Ok(dedup_networks(nm.access_points().await?))
+}
+
+/// Scans for available Wi-Fi networks and returns a deduplicated list.
+#[tauri::command]
+pub async fn scan_wifi() -> Result<Vec<WifiNetwork>, String> {
+    list_wifi_networks(network_manager().await.as_ref()).await
+}
+
+/// Converts a scanned access point for the detailed listing.
+fn bssid_info(ap: AccessPoint) -> BssidInfo {
+    BssidInfo {
+        bars: signal_bars(ap.signal),
+        band: frequency_band(ap.frequency).to_string(),
+        channel: frequency_channel(ap.frequency),
+        bssid: ap.bssid,
+        signal: ap.signal,
+        frequency: ap.frequency,
+        max_bitrate: ap.max_bitrate,
+        security: ap.security,
+        wpa_flags: ap.wpa_flags,
+        rsn_flags: ap.rsn_flags,
+        last_seen: ap.last_seen,
+        device: ap.device,
+        active: ap.active,
+    }
+}
+
+/// Groups access points by SSID, keeping every BSSID.
+pub fn group_access_points(access_points: Vec<AccessPoint>) -> Vec<WifiNetworkDetails> {
+    let mut map: HashMap<String, WifiNetworkDetails> = HashMap::new();
+
+    for ap in access_points {
+        if ap.ssid.is_empty() {
+            continue;
+        }
+        let network = map
+            .entry(ap.ssid.clone())
+            .or_insert_with(|| WifiNetworkDetails {
+                ssid: ap.ssid.clone(),
+                security: ap.security.clone(),
+                active: false,
+                access_points: Vec::new(),
+            });
+        network.active |= ap.active;
+        network.access_points.push(bssid_info(ap));
+    }
+
+    let mut networks: Vec<WifiNetworkDetails> = map.into_values().collect();
+    for network in networks.iter_mut() {
+        network
+            .access_points
+            .sort_by(|a, b| b.active.cmp(&a.active).then(b.signal.cmp(&a.signal)));
+    }
+    let best = |n: &WifiNetworkDetails| n.access_points.first().map_or(0, |ap| ap.signal);
+    networks.sort_by(|a, b| b.active.cmp(&a.active).then(best(b).cmp(&best(a))));
+    networks
+}
+
+/// Lists visible networks with every access point, its band, channel, rate and security flags.
+/// Uses the results of the last scan; `scan_wifi` triggers a new one.
+#[tauri::command]
+pub async fn get_wifi_access_points() -> Result<Vec<WifiNetworkDetails>, String> {
+    let nm = network_manager().await;
+    Ok(group_access_points(nm.access_points().await?))
+}
+
+/// Reads the details of the active Wi-Fi connection, None when Wi-Fi is not connected.
+pub async fn read_wifi_link(nm: &dyn NetworkManager) -> Result<Option<WifiLinkInfo>, String> {
+    let Some(conn) = nm
+        .connections()
+        .await?
+        .into_iter()
+        .find(|c| c.conn_type == "802-11-wireless" && c.active)
+    else {
+        return Ok(None);
+    };
+
+    let access_point = nm
+        .access_points()
+        .await?
+        .into_iter()
+        .find(|ap| ap.active && ap.device == conn.device);
+    let ssid = access_point
+        .as_ref()
+        .map_or_else(|| conn.name.clone(), |ap| ap.ssid.clone());
+    let bitrate = nm.wifi_bitrate(&conn.device).await.unwrap_or(None);
+    let ip = read_ip_config(nm, &conn.uuid)
+        .await?
+        .active
+        .unwrap_or_default();
+
+    Ok(Some(WifiLinkInfo {
+        uuid: conn.uuid,
+        name: conn.name,
+        ssid,
+        interface: conn.device,
+        access_point: access_point.map(bssid_info),
+        bitrate,
+        ip,
+    }))
+}
+
+/// Shows the active Wi-Fi link: access point, band, channel, bitrate, addresses, gateway and DNS.
+#[tauri::command]
+pub async fn get_active_wifi_link() -> Result<Option<WifiLinkInfo>, String> {
+    read_wifi_link(network_manager().await.as_ref()).await
+}
+
+/// Checks a BSSID of the form `AA:BB:CC:DD:EE:FF`.
+fn is_valid_bssid(bssid: &str) -> bool {
+    let parts: Vec<&str> = bssid.split(':').collect();
+    parts.len() == 6
+        && parts
+            .iter()
+            .all(|p| p.len() == 2 && p.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+/// Pins a saved network to one access point and/or band. None and `auto` remove the restriction.
+#[tauri::command]
+pub async fn set_wifi_pin(
+    uuid: String,
+    bssid: Option<String>,
+    band: WifiBand,
+) -> Result<(), String> {
+    let bssid = bssid
+        .map(|b| b.trim().to_uppercase())
+        .filter(|b| !b.is_empty())
+        .unwrap_or_default();
+    if !bssid.is_empty() && !is_valid_bssid(&bssid) {
+        return Err(format!("'{}' is not a valid BSSID", bssid));
+    }
+
+    let mut changes = vec![
+        ("802-11-wireless.bssid".to_string(), bssid),
+        (
+            "802-11-wireless.band".to_string(),
+            band.as_setting().to_string(),
+        ),
+    ];
+    // A channel only makes sense together with a band
+    if band == WifiBand::Auto {
+        changes.push(("802-11-wireless.channel".to_string(), "0".to_string()));
+    }
+    network_manager()
+        .await
+        .modify_connection(&uuid, &changes)
+        .await
 }
 
 /// Connects to a Wi-Fi network using the provided SSID and optional password.
 #[tauri::command]
 pub async fn connect_wifi(ssid: String, password: Option<String>) -> Result<String, String> {
-    let mut cmd = Command::new("nmcli");
-    cmd.args(&["dev", "wifi", "connect", &ssid]);
+    network_manager()
+        .await
+        .connect_wifi(&ssid, password.as_deref())
+        .await?;
+    Ok("Connected successfully".to_string())
+}
+
+/// Checks a WPA passphrase: 8-63 characters, or a 64-digit hex key.
+pub fn is_valid_passphrase(password: &str) -> bool {
+    (8..=63).contains(&password.len())
+        || (password.len() == 64 && password.chars().all(|c| c.is_ascii_hexdigit()))
+}
 
-    if let Some(pwd) = password {
-        cmd.args(&["password", &pwd]);
+/// Validates a connect request and turns it into Wi-Fi profile settings.
+pub fn build_wifi_profile(
+    request: &WifiConnectRequest,
+) -> Result<Vec<(String, String)>, Vec<ProfileError>> {
+    let ssid = request.ssid.trim();
+    let mut profile = ProfileBuilder::new("802-11-wireless", ssid);
+    if ssid.len() > 32 {
+        profile.error("ssid", "An SSID is at most 32 bytes long");
+    }
+    profile.set("802-11-wireless.ssid", ssid);
+    profile.set("802-11-wireless.mode", "infrastructure");
+    profile.set(
+        "802-11-wireless.hidden",
+        if request.hidden { "yes" } else { "no" },
+    );
+
+    match &request.security {
+        WifiSecurity::Open => {}
+        WifiSecurity::WpaPsk { password } | WifiSecurity::Sae { password } => {
+            let key_mgmt = if matches!(request.security, WifiSecurity::Sae { .. }) {
+                "sae"
+            } else {
+                "wpa-psk"
+            };
+            if !is_valid_passphrase(password) {
+                profile.error("password", "The password must be 8 to 63 characters long");
+            }
+            profile.set("802-11-wireless-security.key-mgmt", key_mgmt);
+            profile.set("802-11-wireless-security.psk", password.as_str());
+        }
+        WifiSecurity::Enterprise { eap } => {
+            profile.set("802-11-wireless-security.key-mgmt", "wpa-eap");
+            profile.eap(eap);
+        }
     }
+    profile.build()
+}
 
-    let output = cmd.output().await.map_err(|e| e.to_string())?;
+/// Finds the saved client profile for an SSID, whatever the profile is called.
+/// Access point profiles broadcasting the same SSID are skipped.
+async fn wifi_profile_uuid(nm: &dyn NetworkManager, ssid: &str) -> Result<Option<String>, String> {
+    for conn in nm.connections().await? {
+        if conn.conn_type != "802-11-wireless" {
+            continue;
+        }
+        let settings = nm
+            .connection_settings(
+                &conn.uuid,
+                &["802-11-wireless.ssid", "802-11-wireless.mode"],
+            )
+            .await?;
+        let get = |prop: &str| settings.get(prop).map(|v| v.trim()).unwrap_or_default();
+        if get("802-11-wireless.ssid") == ssid && get("802-11-wireless.mode") != "ap" {
+            return Ok(Some(conn.uuid));
+        }
+    }
+    Ok(None)
+}
 
-    if output.status.success() {
-        Ok("Connected successfully".to_string())
-    } else {
-        Err(String::from_utf8_lossy(&output.stderr).to_string())
+/// Creates or updates the profile for a network and activates it.
+/// A newly created profile is removed again when activation fails.
+pub async fn connect_wifi_profile(
+    nm: &dyn NetworkManager,
+    request: &WifiConnectRequest,
+) -> Result<String, WifiConnectError> {
+    let settings =
+        build_wifi_profile(request).map_err(|errors| WifiConnectError::Invalid { errors })?;
+    let ssid = request.ssid.trim();
+    let fail = |message: String| WifiConnectError::from_message(ssid, message);
+
+    let existing = wifi_profile_uuid(nm, ssid).await.map_err(fail)?;
+
+    let (uuid, created) = match existing {
+        Some(uuid) => {
+            // The existing profile keeps its name
+            let changes: Vec<(String, String)> = settings
+                .into_iter()
+                .filter(|(prop, _)| prop != "connection.type" && prop != "connection.id")
+                .collect();
+            nm.modify_connection(&uuid, &changes).await.map_err(fail)?;
+            (uuid, false)
+        }
+        None => (nm.add_connection(&settings).await.map_err(fail)?, true),
+    };
+
+    if let Err(e) = nm.activate_connection(&uuid).await {
+        if created {
+            let _ = nm.delete_connection(&uuid).await;
+        }
+        return Err(fail(e));
     }
+    Ok(uuid)
+}
+
+/// Joins a network described in full: hidden SSIDs, WPA3-SAE and 802.1X (PEAP, TTLS, TLS).
+/// Returns the UUID of the activated profile.
+#[tauri::command]
+pub async fn connect_wifi_network(request: WifiConnectRequest) -> Result<String, WifiConnectError> {
+    connect_wifi_profile(network_manager().await.as_ref(), &request).await
 }
 
-/// Retrieves the connection configuration (IPv4) for a given SSID.
+/// Retrieves the IPv4/IPv6 configuration for a given SSID, with the configured
+/// values and the values currently in effect reported separately.
 #[tauri::command]
-pub async fn get_wifi_config(ssid: String) -> Result<WifiConfig, String> {
-    // Fetch persistent configuration and current active values from nmcli
-    let output = Command::new("nmcli")
-        .args(&[
-            "-g",
-            "ipv4.method,ipv4.addresses,ipv4.gateway,ipv4.dns,IP4.ADDRESS,IP4.GATEWAY,IP4.DNS",
-            "connection",
-            "show",
-            &ssid,
-        ])
-        .output()
+pub async fn get_wifi_config(ssid: String) -> Result<IpConfigReport, String> {
+    read_ip_config(network_manager().await.as_ref(), &ssid).await
+}
+
+/// Validates and saves the configuration for a given SSID, then re-activates it.
+#[tauri::command]
+pub async fn set_wifi_config(ssid: String, config: WifiConfig) -> Result<(), ProfileUpdateError> {
+    let nm = network_manager().await;
+    write_ip_config(nm.as_ref(), &ssid, &config).await?;
+
+    // Re-activate connection to apply settings immediately
+    nm.activate_connection(&ssid)
         .await
-        .map_err(|e| e.to_string())?;
-
-    if !output.status.success() {
-        // Return default DHCP settings if no profile exists
-        return Ok(WifiConfig {
-            method: "auto".to_string(),
-            ip_address: "".to_string(),
-            prefix: 24,
-            gateway: "".to_string(),
-            dns: "".to_string(),
-        });
-    }
+        .map_err(|message| ProfileUpdateError::ActivationFailed { message })
+}
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let lines: Vec<&str> = stdout.lines().collect();
-
-    // Map output lines to specific fields
-    if lines.len() < 4 {
-        return Ok(WifiConfig {
-            method: "auto".to_string(),
-            ip_address: "".to_string(),
-            prefix: 24,
-            gateway: "".to_string(),
-            dns: "".to_string(),
+/// Settings read for every saved Wi-Fi profile.
+const SAVED_NETWORK_PROPERTIES: [&str; 8] = [
+    "802-11-wireless.ssid",
+    "connection.timestamp",
+    "connection.autoconnect",
+    "connection.autoconnect-priority",
+    "connection.metered",
+    "802-11-wireless.cloned-mac-address",
+    "802-11-wireless.bssid",
+    "802-11-wireless.band",
+];
+
+/// Reads the saved Wi-Fi profiles, most recently used first.
+pub async fn list_saved_networks(nm: &dyn NetworkManager) -> Result<Vec<SavedNetwork>, String> {
+    let mut networks = Vec::new();
+
+    for conn in nm.connections().await? {
+        if conn.conn_type != "802-11-wireless" {
+            continue;
+        }
+        let settings = nm
+            .connection_settings(&conn.uuid, &SAVED_NETWORK_PROPERTIES)
+            .await?;
+        let get = |key: &str| settings.get(key).map(|v| v.trim()).unwrap_or("");
+
+        let ssid = match get("802-11-wireless.ssid") {
+            "" => conn.name.clone(),
+            ssid => ssid.to_string(),
+        };
+        networks.push(SavedNetwork {
+            uuid: conn.uuid,
+            name: conn.name,
+            ssid,
+            active: conn.active,
+            last_used: get("connection.timestamp")
+                .parse()
+                .ok()
+                .filter(|t: &i64| *t > 0),
+            autoconnect: get("connection.autoconnect") != "no",
+            autoconnect_priority: get("connection.autoconnect-priority").parse().unwrap_or(0),
+            metered: match get("connection.metered") {
+                "" => "unknown".to_string(),
+                metered => metered.to_string(),
+            },
+            mac_randomization: match get("802-11-wireless.cloned-mac-address") {
+                "" => "preserve".to_string(),
+                mode => mode.to_string(),
+            },
+            bssid: get("802-11-wireless.bssid").to_string(),
+            band: WifiBand::from_setting(get("802-11-wireless.band")),
         });
     }
 
-    let conf_method = lines.get(0).unwrap_or(&"");
-    let conf_addr = lines.get(1).unwrap_or(&"");
-    let conf_gw = lines.get(2).unwrap_or(&"");
-    let conf_dns = lines.get(3).unwrap_or(&"");
+    networks.sort_by(|a, b| b.last_used.cmp(&a.last_used).then(a.name.cmp(&b.name)));
+    Ok(networks)
+}
 
-    let active_addr = lines.get(4).unwrap_or(&"");
-    let active_gw = lines.get(5).unwrap_or(&"");
-    let active_dns = lines.get(6).unwrap_or(&"");
+/// Lists saved Wi-Fi networks with their last-used time and connection preferences.
+#[tauri::command]
+pub async fn get_saved_networks() -> Result<Vec<SavedNetwork>, String> {
+    list_saved_networks(network_manager().await.as_ref()).await
+}
 
-    let method = if conf_method.is_empty() {
-        "auto".to_string()
-    } else {
-        conf_method.to_string()
-    };
+/// Changes a single setting of a saved Wi-Fi profile.
+async fn modify_saved_network(uuid: &str, property: &str, value: String) -> Result<(), String> {
+    network_manager()
+        .await
+        .modify_connection(uuid, &[(property.to_string(), value)])
+        .await
+}
 
-    // Resolve IP address and prefix (preferring persistent config over active values)
-    let full_address = if !conf_addr.is_empty() {
-        conf_addr.to_string()
-    } else if !active_addr.is_empty() {
-        active_addr.to_string()
-    } else {
-        String::new()
-    };
+/// Forgets a saved Wi-Fi network, deleting its profile and stored password.
+#[tauri::command]
+pub async fn forget_wifi_network(uuid: String) -> Result<(), String> {
+    network_manager().await.delete_connection(&uuid).await
+}
+
+/// Enables or disables automatic connection to a saved network.
+#[tauri::command]
+pub async fn set_wifi_autoconnect(uuid: String, enabled: bool) -> Result<(), String> {
+    let value = if enabled { "yes" } else { "no" };
+    modify_saved_network(&uuid, "connection.autoconnect", value.to_string()).await
+}
+
+/// Sets the autoconnect priority; higher values are preferred when several networks are in range.
+#[tauri::command]
+pub async fn set_wifi_priority(uuid: String, priority: i32) -> Result<(), String> {
+    // NetworkManager accepts priorities from -999 to 999
+    if !(-999..=999).contains(&priority) {
+        return Err("Priority must be between -999 and 999".to_string());
+    }
+    modify_saved_network(
+        &uuid,
+        "connection.autoconnect-priority",
+        priority.to_string(),
+    )
+    .await
+}
 
-    let (ip_address, prefix) = if let Some((ip, p)) = full_address.split_once('/') {
-        (ip.to_string(), p.parse().unwrap_or(24))
-    } else {
-        (full_address, 24)
+/// Marks a saved network as metered, unmetered or lets NetworkManager guess.
+#[tauri::command]
+pub async fn set_wifi_metered(uuid: String, metered: Metered) -> Result<(), String> {
+    let value = match metered {
+        Metered::Yes => "yes",
+        Metered::No => "no",
+        Metered::Unknown => "unknown",
     };
+    modify_saved_network(&uuid, "connection.metered", value.to_string()).await
+}
 
-    let gateway = if !conf_gw.is_empty() {
-        conf_gw.to_string()
-    } else if !active_gw.is_empty() {
-        active_gw.to_string()
-    } else {
-        String::new()
+/// Sets which MAC address is used when connecting to a saved network.
+#[tauri::command]
+pub async fn set_wifi_mac_randomization(
+    uuid: String,
+    mode: MacRandomization,
+) -> Result<(), String> {
+    let value = match mode {
+        MacRandomization::Preserve => "preserve",
+        MacRandomization::Permanent => "permanent",
+        MacRandomization::Random => "random",
+        MacRandomization::Stable => "stable",
     };
+    modify_saved_network(
+        &uuid,
+        "802-11-wireless.cloned-mac-address",
+        value.to_string(),
+    )
+    .await
+}
 
-    let dns = if !conf_dns.is_empty() {
-        conf_dns.to_string()
-    } else if !active_dns.is_empty() {
-        active_dns.to_string()
-    } else {
-        String::new()
+/// Escapes the characters with a special meaning in `WIFI:` QR payloads.
+fn escape_qr_field(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, '\\' | ';' | ',' | ':' | '"') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Security and password encoded in a Wi-Fi QR code.
+#[derive(Debug, PartialEq)]
+pub enum QrSecurity<'a> {
+    Open,
+    Wpa(&'a str),
+    Sae(&'a str), // WPA3-only networks, which phones will not join as `T:WPA`
+}
+
+impl<'a> QrSecurity<'a> {
+    /// Picks the QR security for a saved profile's key management and stored PSK.
+    fn from_profile(key_mgmt: &str, psk: &'a str) -> Result<Self, String> {
+        let password = || match psk {
+            "" => Err("The password of this network is not stored".to_string()),
+            psk => Ok(psk),
+        };
+        match key_mgmt {
+            "" => Ok(QrSecurity::Open),
+            "wpa-psk" => Ok(QrSecurity::Wpa(password()?)),
+            "sae" => Ok(QrSecurity::Sae(password()?)),
+            "none" => Err("WEP networks cannot be shared as a QR code".to_string()),
+            _ => Err("Enterprise networks cannot be shared as a QR code".to_string()),
+        }
+    }
+}
+
+/// Builds the `WIFI:T:WPA;S:...;P:...;;` payload phones understand when scanning a QR code.
+pub fn wifi_qr_payload(ssid: &str, security: QrSecurity, hidden: bool) -> String {
+    let ssid = escape_qr_field(ssid);
+    let mut payload = match security {
+        QrSecurity::Open => format!("WIFI:T:nopass;S:{};", ssid),
+        QrSecurity::Wpa(password) => {
+            format!("WIFI:T:WPA;S:{};P:{};", ssid, escape_qr_field(password))
+        }
+        QrSecurity::Sae(password) => {
+            format!("WIFI:T:SAE;S:{};P:{};", ssid, escape_qr_field(password))
+        }
     };
+    if hidden {
+        payload.push_str("H:true;");
+    }
+    payload.push(';');
+    payload
+}
 
-    Ok(WifiConfig {
-        method,
-        ip_address,
-        prefix,
-        gateway,
-        dns,
-    })
+/// Renders a QR code as a standalone SVG document.
+pub fn render_qr_svg(payload: &str) -> Result<String, String> {
+    let code = QrCode::new(payload.as_bytes()).map_err(|e| e.to_string())?;
+    Ok(code
+        .render::<svg::Color>()
+        .min_dimensions(256, 256)
+        .dark_color(svg::Color("#000000"))
+        .light_color(svg::Color("#ffffff"))
+        .build())
 }
 
-/// Updates the connection configuration for a given SSID and applies changes.
+/// Renders the credentials of a saved network as a QR code other devices can scan to join.
+/// Returns the SVG markup. Enterprise (802.1X) networks cannot be shared this way.
 #[tauri::command]
-pub async fn set_wifi_config(ssid: String, config: WifiConfig) -> Result<(), String> {
-    let mut args = vec!["connection".to_string(), "modify".to_string(), ssid.clone()];
+pub async fn get_wifi_share_qr(uuid: String) -> Result<String, String> {
+    let settings = network_manager()
+        .await
+        .connection_secrets(
+            &uuid,
+            &[
+                "802-11-wireless.ssid",
+                "802-11-wireless.hidden",
+                "802-11-wireless-security.key-mgmt",
+                "802-11-wireless-security.psk",
+            ],
+        )
+        .await?;
+    let get = |key: &str| settings.get(key).map(|v| v.trim()).unwrap_or("");
+
+    let security = QrSecurity::from_profile(
+        get("802-11-wireless-security.key-mgmt"),
+        get("802-11-wireless-security.psk"),
+    )?;
+    let payload = wifi_qr_payload(
+        get("802-11-wireless.ssid"),
+        security,
+        get("802-11-wireless.hidden") == "yes",
+    );
+    render_qr_svg(&payload)
+}
 
-    args.push("ipv4.method".to_string());
-    args.push(config.method.clone());
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::systems::nm::client::ConnectionProfile;
+    use crate::modules::systems::nm::fake::{FakeNetworkManager, FakeState};
+
+    fn ap(ssid: &str, bssid: &str, signal: u8, active: bool) -> AccessPoint {
+        AccessPoint {
+            ssid: ssid.to_string(),
+            bssid: bssid.to_string(),
+            security: "WPA2".to_string(),
+            signal,
+            frequency: 2437,
+            device: "wlan0".to_string(),
+            active,
+            ..Default::default()
+        }
+    }
 
-    if config.method == "manual" {
-        let full_address = format!("{}/{}", config.ip_address, config.prefix);
-        args.push("ipv4.addresses".to_string());
-        args.push(full_address);
+    #[test]
+    fn keeps_the_strongest_access_point_per_ssid() {
+        let networks = dedup_networks(vec![
+            ap("Home", "AA:00:00:00:00:01", 40, false),
+            ap("Home", "AA:00:00:00:00:02", 70, false),
+            ap("Cafe", "BB:00:00:00:00:01", 55, false),
+        ]);
+        let summary: Vec<(&str, u8)> = networks
+            .iter()
+            .map(|n| (n.ssid.as_str(), n.signal))
+            .collect();
+        assert_eq!(summary, vec![("Home", 70), ("Cafe", 55)]);
+        assert_eq!(networks[0].bars, signal_bars(70));
+    }
 
-        args.push("ipv4.gateway".to_string());
-        args.push(if !config.gateway.is_empty() {
-            config.gateway.clone()
-        } else {
-            "".to_string()
+    #[test]
+    fn prefers_the_active_access_point() {
+        let networks = dedup_networks(vec![
+            ap("Home", "AA:00:00:00:00:01", 90, false),
+            ap("Home", "AA:00:00:00:00:02", 30, true),
+            ap("Cafe", "BB:00:00:00:00:01", 80, false),
+        ]);
+        assert_eq!(networks[0].ssid, "Home");
+        assert!(networks[0].active);
+        assert_eq!(networks[0].signal, 30);
+        assert_eq!(networks[1].ssid, "Cafe");
+    }
+
+    #[test]
+    fn skips_hidden_networks() {
+        let networks = dedup_networks(vec![
+            ap("", "AA:00:00:00:00:01", 90, false),
+            ap("Home", "AA:00:00:00:00:02", 30, false),
+        ]);
+        assert_eq!(networks.len(), 1);
+        assert_eq!(networks[0].ssid, "Home");
+    }
+
+    #[tokio::test]
+    async fn lists_networks_after_requesting_a_scan() {
+        let nm = FakeNetworkManager::new(FakeState {
+            wireless_enabled: true,
+            access_points: vec![
+                ap("Home", "AA:00:00:00:00:01", 40, false),
+                ap("Home", "AA:00:00:00:00:02", 60, false),
+                ap("Office", "CC:00:00:00:00:01", 75, true),
+            ],
+            ..Default::default()
         });
-    } else {
-        // Clear static settings when switching back to auto
-        args.push("ipv4.addresses".to_string());
-        args.push("".to_string());
-        args.push("ipv4.gateway".to_string());
-        args.push("".to_string());
+
+        let networks = list_wifi_networks(&nm).await.unwrap();
+        assert_eq!(nm.state().scans_requested, 1);
+        assert_eq!(
+            networks,
+            vec![
+                WifiNetwork {
+                    ssid: "Office".to_string(),
+                    security: "WPA2".to_string(),
+                    bars: signal_bars(75),
+                    signal: 75,
+                    active: true,
+                },
+                WifiNetwork {
+                    ssid: "Home".to_string(),
+                    security: "WPA2".to_string(),
+                    bars: signal_bars(60),
+                    signal: 60,
+                    active: false,
+                },
+            ]
+        );
     }
 
-    // Configure DNS settings and handle 'ignore-auto-dns'
-    args.push("ipv4.dns".to_string());
-    if !config.dns.is_empty() {
-        args.push(config.dns.clone());
-        args.push("ipv4.ignore-auto-dns".to_string());
-        args.push("yes".to_string());
-    } else {
-        args.push("".to_string());
-        args.push("ipv4.ignore-auto-dns".to_string());
-        args.push("no".to_string());
+    #[tokio::test]
+    async fn lists_nothing_with_the_radio_off() {
+        let nm = FakeNetworkManager::new(FakeState {
+            wireless_enabled: true,
+            access_points: vec![ap("Home", "AA:00:00:00:00:01", 40, false)],
+            ..Default::default()
+        });
+        nm.set_wireless_enabled(false).await.unwrap();
+        assert!(list_wifi_networks(&nm).await.unwrap().is_empty());
     }
 
-    // Apply configuration changes
-    let output = Command::new("nmcli")
-        .args(&args)
-        .output()
-        .await
-        .map_err(|e| e.to_string())?;
+    /// A saved Wi-Fi profile with the given name, SSID and mode.
+    fn saved_profile(state: &mut FakeState, uuid: &str, name: &str, ssid: &str, mode: &str) {
+        state.connections.push(ConnectionProfile {
+            uuid: uuid.to_string(),
+            name: name.to_string(),
+            conn_type: "802-11-wireless".to_string(),
+            active: false,
+            device: String::new(),
+        });
+        state.settings.insert(
+            uuid.to_string(),
+            HashMap::from([
+                ("connection.id".to_string(), name.to_string()),
+                ("802-11-wireless.ssid".to_string(), ssid.to_string()),
+                ("802-11-wireless.mode".to_string(), mode.to_string()),
+            ]),
+        );
+    }
 
-    if !output.status.success() {
-        return Err(format!(
-            "Failed to configure: {}",
-            String::from_utf8_lossy(&output.stderr)
-        ));
+    fn psk_request(ssid: &str) -> WifiConnectRequest {
+        WifiConnectRequest {
+            ssid: ssid.to_string(),
+            hidden: false,
+            security: WifiSecurity::WpaPsk {
+                password: "correct horse".to_string(),
+            },
+        }
     }
 
-    // Re-activate connection to apply settings immediately
-    let output = Command::new("nmcli")
-        .args(&["connection", "up", &ssid])
-        .output()
-        .await
-        .map_err(|e| e.to_string())?;
+    #[tokio::test]
+    async fn updates_the_profile_saved_for_the_ssid() {
+        let mut state = FakeState::default();
+        saved_profile(&mut state, "home", "Home (5 GHz)", "Home", "infrastructure");
+        let nm = FakeNetworkManager::new(state);
+
+        let uuid = connect_wifi_profile(&nm, &psk_request("Home"))
+            .await
+            .unwrap();
+
+        assert_eq!(uuid, "home");
+        let state = nm.state();
+        assert_eq!(state.connections.len(), 1);
+        assert!(state.connections[0].active);
+        assert_eq!(state.settings["home"]["connection.id"], "Home (5 GHz)");
+        assert_eq!(
+            state.settings["home"]["802-11-wireless-security.psk"],
+            "correct horse"
+        );
+    }
 
-    if !output.status.success() {
-        return Err(format!(
-            "Configuration saved but activation failed: {}",
-            String::from_utf8_lossy(&output.stderr)
-        ));
+    #[tokio::test]
+    async fn creates_a_profile_instead_of_reusing_an_access_point() {
+        let mut state = FakeState::default();
+        saved_profile(&mut state, "ap", "Home", "Home", "ap");
+        let nm = FakeNetworkManager::new(state);
+
+        let uuid = connect_wifi_profile(&nm, &psk_request("Home"))
+            .await
+            .unwrap();
+
+        assert_ne!(uuid, "ap");
+        let state = nm.state();
+        assert_eq!(state.connections.len(), 2);
+        assert_eq!(state.settings["ap"]["802-11-wireless.mode"], "ap");
+        assert!(!state.settings["ap"].contains_key("802-11-wireless-security.psk"));
     }
 
-    Ok(())
+    #[test]
+    fn builds_qr_payloads_per_security() {
+        let payload = |key_mgmt: &str, psk: &str| {
+            QrSecurity::from_profile(key_mgmt, psk).map(|s| wifi_qr_payload("Home", s, false))
+        };
+        assert_eq!(payload("", ""), Ok("WIFI:T:nopass;S:Home;;".to_string()));
+        assert_eq!(
+            payload("wpa-psk", "hunter22"),
+            Ok("WIFI:T:WPA;S:Home;P:hunter22;;".to_string())
+        );
+        assert_eq!(
+            payload("sae", "hunter22"),
+            Ok("WIFI:T:SAE;S:Home;P:hunter22;;".to_string())
+        );
+        assert!(payload("sae", "").is_err());
+        assert!(payload("none", "").is_err());
+        assert!(payload("wpa-eap", "").is_err());
+    }
+
+    #[test]
+    fn escapes_qr_fields_and_marks_hidden_networks() {
+        assert_eq!(
+            wifi_qr_payload(r#"My;"Net""#, QrSecurity::Wpa(r"a:b\c,d"), true),
+            r#"WIFI:T:WPA;S:My\;\"Net\";P:a\:b\\c\,d;H:true;;"#
+        );
+    }
 }
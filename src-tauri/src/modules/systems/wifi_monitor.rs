@@ -0,0 +1,322 @@
+use crate::modules::systems::nm::client::{network_manager, NetworkManager};
+use crate::modules::systems::vpn::apply_untrusted_network_policy;
+use crate::modules::systems::wifi::{dedup_networks, read_wifi_link, WifiLinkInfo, WifiNetwork};
+use serde::Serialize;
+use std::process::Stdio;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::process::Command;
+use tokio::sync::{mpsc, Notify};
+use tokio::time::{sleep, sleep_until, Instant};
+
+/// Event names emitted to the frontend.
+pub const RADIO_EVENT: &str = "wifi-radio-changed";
+pub const DEVICE_EVENT: &str = "wifi-device-state-changed";
+pub const NETWORKS_EVENT: &str = "wifi-networks-changed";
+pub const CONNECTION_EVENT: &str = "wifi-connection-changed";
+
+/// Changes reported in quick succession are handled together.
+const DEBOUNCE: Duration = Duration::from_millis(300);
+/// How often to rescan while the Wi-Fi page is open.
+const RESCAN_INTERVAL: Duration = Duration::from_secs(15);
+/// Time for scan results to arrive; `nmcli monitor` does not report them.
+const SCAN_SETTLE: Duration = Duration::from_secs(3);
+/// Wait before restarting `nmcli monitor` after it exits, e.g. when NetworkManager restarts.
+const MONITOR_RESTART_DELAY: Duration = Duration::from_secs(5);
+
+/// Tracks whether the Wi-Fi page is open, which enables periodic rescans.
+pub struct WifiMonitorState {
+    page_open: AtomicBool,
+    wake: Notify,
+}
+
+impl WifiMonitorState {
+    pub fn new() -> Self {
+        Self {
+            page_open: AtomicBool::new(false),
+            wake: Notify::new(),
+        }
+    }
+}
+
+/// Payload of `wifi-radio-changed`.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct RadioChanged {
+    pub enabled: bool,
+}
+
+/// Payload of `wifi-device-state-changed`.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct DeviceStateChanged {
+    pub interface: String,
+    pub state: String, // e.g. "connected", "disconnected", "unavailable"
+}
+
+/// Payload of `wifi-networks-changed`: the same list `scan_wifi` returns.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct NetworksChanged {
+    pub networks: Vec<WifiNetwork>,
+}
+
+/// Payload of `wifi-connection-changed`.
+#[derive(Debug, Serialize, Clone)]
+pub struct ConnectionChanged {
+    pub link: Option<WifiLinkInfo>, // None when Wi-Fi got disconnected
+}
+
+/// The Wi-Fi state the monitor compares between changes.
+#[derive(Debug, Default, PartialEq)]
+struct WifiSnapshot {
+    enabled: bool,
+    devices: Vec<(String, String)>, // (interface, state) of Wi-Fi devices
+    networks: Vec<WifiNetwork>,
+    active: Option<String>, // UUID of the active Wi-Fi connection
+}
+
+impl WifiSnapshot {
+    async fn read(nm: &dyn NetworkManager) -> Self {
+        let devices = nm
+            .devices()
+            .await
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|d| d.device_type == "wifi")
+            .map(|d| (d.interface, d.state))
+            .collect();
+        let active = nm
+            .connections()
+            .await
+            .unwrap_or_default()
+            .into_iter()
+            .find(|c| c.conn_type == "802-11-wireless" && c.active)
+            .map(|c| c.uuid);
+
+        Self {
+            enabled: nm.wireless_enabled().await.unwrap_or(false),
+            devices,
+            networks: dedup_networks(nm.access_points().await.unwrap_or_default()),
+            active,
+        }
+    }
+}
+
+/// A difference between two snapshots that the frontend is told about.
+#[derive(Debug, PartialEq)]
+enum WifiChange {
+    Radio(RadioChanged),
+    Device(DeviceStateChanged),
+    Networks(NetworksChanged),
+    /// The active connection changed; the new link is read when emitting.
+    Connection,
+}
+
+/// Lists the changes between two snapshots, in the order they are emitted.
+fn diff_snapshots(old: &WifiSnapshot, new: &WifiSnapshot) -> Vec<WifiChange> {
+    let mut changes = Vec::new();
+    if old.enabled != new.enabled {
+        changes.push(WifiChange::Radio(RadioChanged {
+            enabled: new.enabled,
+        }));
+    }
+    for (interface, state) in &new.devices {
+        let previous = old.devices.iter().find(|(i, _)| i == interface);
+        if previous.map(|(_, s)| s) != Some(state) {
+            changes.push(WifiChange::Device(DeviceStateChanged {
+                interface: interface.clone(),
+                state: state.clone(),
+            }));
+        }
+    }
+    if old.networks != new.networks {
+        changes.push(WifiChange::Networks(NetworksChanged {
+            networks: new.networks.clone(),
+        }));
+    }
+    if old.active != new.active {
+        changes.push(WifiChange::Connection);
+    }
+    changes
+}
+
+/// Emits an event for every part of the state that differs between two snapshots.
+async fn emit_changes(
+    app: &AppHandle,
+    nm: &dyn NetworkManager,
+    old: &WifiSnapshot,
+    new: &WifiSnapshot,
+) {
+    for change in diff_snapshots(old, new) {
+        match change {
+            WifiChange::Radio(payload) => {
+                let _ = app.emit(RADIO_EVENT, payload);
+            }
+            WifiChange::Device(payload) => {
+                let _ = app.emit(DEVICE_EVENT, payload);
+            }
+            WifiChange::Networks(payload) => {
+                let _ = app.emit(NETWORKS_EVENT, payload);
+            }
+            WifiChange::Connection => {
+                let link = read_wifi_link(nm).await.unwrap_or(None);
+                let ssid = link.as_ref().map(|l| l.ssid.clone());
+                let _ = app.emit(CONNECTION_EVENT, ConnectionChanged { link });
+                let _ = apply_untrusted_network_policy(nm, ssid.as_deref()).await;
+            }
+        }
+    }
+}
+
+/// Runs `nmcli monitor` and signals every line it prints, restarting it when it exits.
+async fn follow_nmcli_monitor(tx: mpsc::Sender<()>) {
+    loop {
+        let child = Command::new("nmcli")
+            .arg("monitor")
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .kill_on_drop(true)
+            .spawn();
+
+        if let Ok(mut child) = child {
+            if let Some(stdout) = child.stdout.take() {
+                let mut lines = BufReader::new(stdout).lines();
+                while let Ok(Some(_)) = lines.next_line().await {
+                    // A full channel already holds a pending change
+                    let _ = tx.try_send(());
+                }
+            }
+            let _ = child.wait().await;
+        }
+        sleep(MONITOR_RESTART_DELAY).await;
+    }
+}
+
+/// Spawns the background task that follows NetworkManager and emits Wi-Fi events.
+pub fn start_wifi_monitor(app: AppHandle) {
+    let (tx, mut rx) = mpsc::channel(1);
+    tauri::async_runtime::spawn(follow_nmcli_monitor(tx));
+
+    tauri::async_runtime::spawn(async move {
+        let state = app.state::<WifiMonitorState>();
+        let mut snapshot = WifiSnapshot::read(network_manager().await.as_ref()).await;
+        let mut next_scan = Instant::now();
+
+        loop {
+            let page_open = state.page_open.load(Ordering::Relaxed);
+            let nm = network_manager().await;
+
+            tokio::select! {
+                Some(()) = rx.recv() => sleep(DEBOUNCE).await,
+                _ = state.wake.notified() => {
+                    // The page was just opened; rescan right away
+                    next_scan = Instant::now();
+                    continue;
+                }
+                _ = sleep_until(next_scan), if page_open => {
+                    next_scan = Instant::now() + RESCAN_INTERVAL;
+                    // Scans requested too often are rejected; the cached results are still valid
+                    let _ = nm.request_scan().await;
+                    sleep(SCAN_SETTLE).await;
+                }
+            }
+            // Drop a change that arrived while waiting, it is covered by this read
+            let _ = rx.try_recv();
+
+            let current = WifiSnapshot::read(nm.as_ref()).await;
+            if current != snapshot {
+                emit_changes(&app, nm.as_ref(), &snapshot, &current).await;
+                snapshot = current;
+            }
+        }
+    });
+}
+
+/// Tells the monitor whether the Wi-Fi page is open. Periodic rescans only run while it is.
+#[tauri::command]
+pub async fn set_wifi_page_active(
+    state: tauri::State<'_, WifiMonitorState>,
+    active: bool,
+) -> Result<(), String> {
+    state.page_open.store(active, Ordering::Relaxed);
+    if active {
+        state.wake.notify_one();
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn network(ssid: &str, signal: u8) -> WifiNetwork {
+        WifiNetwork {
+            ssid: ssid.to_string(),
+            security: "WPA2".to_string(),
+            bars: String::new(),
+            signal,
+            active: false,
+        }
+    }
+
+    fn snapshot() -> WifiSnapshot {
+        WifiSnapshot {
+            enabled: true,
+            devices: vec![("wlan0".to_string(), "connected".to_string())],
+            networks: vec![network("Home", 70)],
+            active: Some("home-uuid".to_string()),
+        }
+    }
+
+    #[test]
+    fn reports_nothing_for_equal_snapshots() {
+        assert!(diff_snapshots(&snapshot(), &snapshot()).is_empty());
+    }
+
+    #[test]
+    fn reports_every_changed_part() {
+        let old = snapshot();
+        let new = WifiSnapshot {
+            enabled: false,
+            devices: vec![("wlan0".to_string(), "unavailable".to_string())],
+            networks: Vec::new(),
+            active: None,
+        };
+        assert_eq!(
+            diff_snapshots(&old, &new),
+            [
+                WifiChange::Radio(RadioChanged { enabled: false }),
+                WifiChange::Device(DeviceStateChanged {
+                    interface: "wlan0".to_string(),
+                    state: "unavailable".to_string(),
+                }),
+                WifiChange::Networks(NetworksChanged {
+                    networks: Vec::new()
+                }),
+                WifiChange::Connection,
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_only_devices_that_changed_or_appeared() {
+        let old = snapshot();
+        let mut new = snapshot();
+        new.devices
+            .push(("wlan1".to_string(), "disconnected".to_string()));
+        new.networks = vec![network("Home", 40)];
+
+        assert_eq!(
+            diff_snapshots(&old, &new),
+            [
+                WifiChange::Device(DeviceStateChanged {
+                    interface: "wlan1".to_string(),
+                    state: "disconnected".to_string(),
+                }),
+                WifiChange::Networks(NetworksChanged {
+                    networks: vec![network("Home", 40)]
+                }),
+            ]
+        );
+    }
+}
@@ -0,0 +1,604 @@
+use crate::modules::systems::nm::client::network_manager;
+use crate::modules::systems::nm::profile::{ProfileBuilder, ProfileError, ProfileUpdateError};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use rand_core::{OsRng, RngCore};
+use serde::{Deserialize, Serialize};
+use std::fs::{OpenOptions, Permissions};
+use std::io::Write;
+use std::net::IpAddr;
+use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+use x25519_dalek::{PublicKey, StaticSecret};
+
+/// Longest interface name the kernel accepts (IFNAMSIZ - 1).
+const MAX_INTERFACE_LEN: usize = 15;
+
+/// Settings read from a NetworkManager WireGuard connection.
+const WIREGUARD_PROPERTIES: [&str; 10] = [
+    "connection.id",
+    "connection.interface-name",
+    "wireguard.private-key",
+    "wireguard.listen-port",
+    "wireguard.mtu",
+    "wireguard.peers",
+    "ipv4.addresses",
+    "ipv6.addresses",
+    "ipv4.dns",
+    "ipv6.dns",
+];
+
+/// A Curve25519 keypair, base64 encoded as `wg genkey`/`wg pubkey` print them.
+#[derive(Debug, Serialize)]
+pub struct WireguardKeypair {
+    pub private_key: String,
+    pub public_key: String,
+}
+
+/// A remote end of the tunnel.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WireguardPeer {
+    pub public_key: String,
+    #[serde(default)]
+    pub preshared_key: String, // empty when not used
+    #[serde(default)]
+    pub endpoint: String, // host:port, empty for peers that connect to us
+    pub allowed_ips: Vec<String>,
+    pub persistent_keepalive: Option<u16>, // seconds
+}
+
+/// A WireGuard tunnel: the `[Interface]` section of a wg-quick file plus its peers.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WireguardConfig {
+    pub name: String,
+    pub interface: String, // e.g. "wg0"
+    pub private_key: String,
+    pub addresses: Vec<String>, // CIDR, IPv4 and IPv6
+    #[serde(default)]
+    pub dns: Vec<String>,
+    pub mtu: Option<u32>,
+    pub listen_port: Option<u16>,
+    pub peers: Vec<WireguardPeer>,
+}
+
+/// Encodes a key the way WireGuard tools do.
+fn encode_key(bytes: &[u8; 32]) -> String {
+    STANDARD.encode(bytes)
+}
+
+/// Decodes a base64 WireGuard key, which must be exactly 32 bytes.
+fn decode_key(key: &str) -> Option<[u8; 32]> {
+    STANDARD.decode(key.trim()).ok()?.try_into().ok()
+}
+
+/// Derives the public key of a base64 private key.
+fn public_key_of(private_key: &str) -> Option<String> {
+    let secret = StaticSecret::from(decode_key(private_key)?);
+    Some(encode_key(PublicKey::from(&secret).as_bytes()))
+}
+
+/// Generates a new private key and its public key.
+#[tauri::command]
+pub fn generate_wireguard_keypair() -> WireguardKeypair {
+    let secret = StaticSecret::random_from_rng(OsRng);
+    WireguardKeypair {
+        private_key: encode_key(secret.as_bytes()),
+        public_key: encode_key(PublicKey::from(&secret).as_bytes()),
+    }
+}
+
+/// Generates a random preshared key for a peer.
+#[tauri::command]
+pub fn generate_wireguard_preshared_key() -> String {
+    let mut key = [0u8; 32];
+    OsRng.fill_bytes(&mut key);
+    encode_key(&key)
+}
+
+/// Returns the public key for a private key, to show or hand to peers.
+#[tauri::command]
+pub fn get_wireguard_public_key(private_key: String) -> Result<String, String> {
+    public_key_of(&private_key)
+        .ok_or_else(|| "The private key is not a valid WireGuard key".to_string())
+}
+
+/// Suggests an interface name from a connection name, e.g. "Office VPN" -> "office-vpn".
+fn interface_from_name(name: &str) -> String {
+    let mut interface: String = name
+        .trim()
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect();
+    interface.truncate(MAX_INTERFACE_LEN);
+    match interface.trim_matches('-') {
+        "" => "wg0".to_string(),
+        trimmed => trimmed.to_string(),
+    }
+}
+
+/// Parses `address/prefix`, checking the prefix against the address family.
+fn parse_cidr(text: &str) -> Option<IpAddr> {
+    let (addr, prefix) = text.trim().split_once('/')?;
+    let addr: IpAddr = addr.parse().ok()?;
+    let prefix: u8 = prefix.parse().ok()?;
+    let max = if addr.is_ipv4() { 32 } else { 128 };
+    (prefix <= max).then_some(addr)
+}
+
+/// Checks a `host:port` endpoint; IPv6 hosts are written as `[addr]:port`.
+fn is_valid_endpoint(endpoint: &str) -> bool {
+    match endpoint.rsplit_once(':') {
+        Some((host, port)) => {
+            let host = host.trim_start_matches('[').trim_end_matches(']');
+            !host.is_empty() && port.parse::<u16>().is_ok_and(|p| p > 0)
+        }
+        None => false,
+    }
+}
+
+/// Collects the non-empty, trimmed entries of a list.
+fn clean_list(items: &[String]) -> Vec<&str> {
+    items
+        .iter()
+        .map(|i| i.trim())
+        .filter(|i| !i.is_empty())
+        .collect()
+}
+
+/// Formats peers for nmcli's `wireguard.peers`: comma separated, each the public key
+/// followed by `attribute=value` pairs, with allowed IPs separated by semicolons.
+fn format_peers(peers: &[WireguardPeer]) -> String {
+    peers
+        .iter()
+        .map(|peer| {
+            let mut parts = vec![peer.public_key.trim().to_string()];
+            let allowed_ips = clean_list(&peer.allowed_ips);
+            if !allowed_ips.is_empty() {
+                parts.push(format!("allowed-ips={}", allowed_ips.join(";")));
+            }
+            if !peer.endpoint.trim().is_empty() {
+                parts.push(format!("endpoint={}", peer.endpoint.trim()));
+            }
+            if !peer.preshared_key.trim().is_empty() {
+                parts.push(format!("preshared-key={}", peer.preshared_key.trim()));
+                // Store the key with the connection instead of asking an agent for it
+                parts.push("preshared-key-flags=0".to_string());
+            }
+            if let Some(keepalive) = peer.persistent_keepalive.filter(|k| *k > 0) {
+                parts.push(format!("persistent-keepalive={}", keepalive));
+            }
+            parts.join(" ")
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Parses nmcli's `wireguard.peers` value, the inverse of `format_peers`.
+fn parse_peers(value: &str) -> Vec<WireguardPeer> {
+    value
+        .split(',')
+        .filter_map(|entry| {
+            let mut parts = entry.split_whitespace();
+            let mut peer = WireguardPeer {
+                public_key: parts.next()?.to_string(),
+                ..Default::default()
+            };
+            for part in parts {
+                match part.split_once('=') {
+                    Some(("allowed-ips", ips)) => {
+                        peer.allowed_ips = ips
+                            .split(';')
+                            .filter(|ip| !ip.is_empty())
+                            .map(String::from)
+                            .collect();
+                    }
+                    Some(("endpoint", endpoint)) => peer.endpoint = endpoint.to_string(),
+                    Some(("preshared-key", key)) => peer.preshared_key = key.to_string(),
+                    Some(("persistent-keepalive", keepalive)) => {
+                        peer.persistent_keepalive = keepalive.parse().ok().filter(|k| *k > 0)
+                    }
+                    _ => {}
+                }
+            }
+            Some(peer)
+        })
+        .collect()
+}
+
+/// Validates a tunnel and turns it into NetworkManager WireGuard settings.
+pub fn build_wireguard_profile(
+    config: &WireguardConfig,
+) -> Result<Vec<(String, String)>, Vec<ProfileError>> {
+    let mut profile = ProfileBuilder::new("wireguard", &config.name);
+
+    let interface = match config.interface.trim() {
+        "" => interface_from_name(&config.name),
+        interface => interface.to_string(),
+    };
+    if interface.len() > MAX_INTERFACE_LEN
+        || !interface
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        profile.error(
+            "interface",
+            "Use at most 15 letters, digits, '-' or '_' for the interface name",
+        );
+    }
+    profile.set("connection.interface-name", interface);
+
+    if decode_key(&config.private_key).is_none() {
+        profile.error(
+            "private_key",
+            "The private key is not a valid WireGuard key",
+        );
+    }
+    profile.set("wireguard.private-key", config.private_key.trim());
+    profile.set("wireguard.private-key-flags", "0");
+    profile.set(
+        "wireguard.listen-port",
+        config.listen_port.unwrap_or(0).to_string(),
+    );
+    profile.set("wireguard.mtu", config.mtu.unwrap_or(0).to_string());
+
+    // Addresses and DNS servers go to the IP setting of their family
+    let mut v4 = (Vec::new(), Vec::new());
+    let mut v6 = (Vec::new(), Vec::new());
+    for address in clean_list(&config.addresses) {
+        match parse_cidr(address) {
+            Some(IpAddr::V4(_)) => v4.0.push(address),
+            Some(IpAddr::V6(_)) => v6.0.push(address),
+            None => {
+                profile.error(
+                    "addresses",
+                    format!(
+                        "'{}' is not an address with a prefix, e.g. 10.0.0.2/32",
+                        address
+                    ),
+                );
+            }
+        }
+    }
+    if v4.0.is_empty() && v6.0.is_empty() {
+        profile.error("addresses", "The tunnel needs at least one address");
+    }
+    for server in clean_list(&config.dns) {
+        match server.parse::<IpAddr>() {
+            Ok(IpAddr::V4(_)) => v4.1.push(server),
+            Ok(IpAddr::V6(_)) => v6.1.push(server),
+            Err(_) => {
+                profile.error("dns", format!("'{}' is not an IP address", server));
+            }
+        }
+    }
+    for (family, (addresses, dns), off) in [("ipv4", v4, "disabled"), ("ipv6", v6, "ignore")] {
+        let method = if addresses.is_empty() { off } else { "manual" };
+        profile.set(&format!("{}.method", family), method);
+        profile.set(&format!("{}.addresses", family), addresses.join(","));
+        profile.set(&format!("{}.dns", family), dns.join(","));
+    }
+
+    if config.peers.is_empty() {
+        profile.error("peers", "Add at least one peer");
+    }
+    for (i, peer) in config.peers.iter().enumerate() {
+        let field = |name: &str| format!("peers[{}].{}", i, name);
+        if decode_key(&peer.public_key).is_none() {
+            profile.error(
+                &field("public_key"),
+                "The public key is not a valid WireGuard key",
+            );
+        }
+        if !peer.preshared_key.trim().is_empty() && decode_key(&peer.preshared_key).is_none() {
+            profile.error(
+                &field("preshared_key"),
+                "The preshared key is not a valid WireGuard key",
+            );
+        }
+        if !peer.endpoint.trim().is_empty() && !is_valid_endpoint(peer.endpoint.trim()) {
+            profile.error(
+                &field("endpoint"),
+                "Use host:port, e.g. vpn.example.com:51820",
+            );
+        }
+        let allowed_ips = clean_list(&peer.allowed_ips);
+        if allowed_ips.is_empty() {
+            profile.error(&field("allowed_ips"), "Add at least one allowed IP range");
+        }
+        for ip in allowed_ips {
+            if parse_cidr(ip).is_none() {
+                profile.error(
+                    &field("allowed_ips"),
+                    format!("'{}' is not a range with a prefix, e.g. 0.0.0.0/0", ip),
+                );
+            }
+        }
+    }
+    profile.set("wireguard.peers", format_peers(&config.peers));
+    profile.build()
+}
+
+/// Reads a NetworkManager WireGuard connection, including its keys.
+#[tauri::command]
+pub async fn get_wireguard_connection(uuid: String) -> Result<WireguardConfig, String> {
+    let settings = network_manager()
+        .await
+        .connection_secrets(&uuid, &WIREGUARD_PROPERTIES)
+        .await?;
+    let get = |key: &str| settings.get(key).map(|v| v.trim()).unwrap_or("");
+    let list = |keys: [&str; 2]| -> Vec<String> {
+        keys.iter()
+            .flat_map(|key| get(key).split(','))
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .map(String::from)
+            .collect()
+    };
+
+    Ok(WireguardConfig {
+        name: get("connection.id").to_string(),
+        interface: get("connection.interface-name").to_string(),
+        private_key: get("wireguard.private-key").to_string(),
+        addresses: list(["ipv4.addresses", "ipv6.addresses"]),
+        dns: list(["ipv4.dns", "ipv6.dns"]),
+        mtu: get("wireguard.mtu").parse().ok().filter(|m| *m > 0),
+        listen_port: get("wireguard.listen-port").parse().ok().filter(|p| *p > 0),
+        peers: parse_peers(get("wireguard.peers")),
+    })
+}
+
+/// Creates a WireGuard connection, or updates it when `uuid` is given. Returns the UUID.
+#[tauri::command]
+pub async fn save_wireguard_connection(
+    uuid: Option<String>,
+    config: WireguardConfig,
+) -> Result<String, ProfileUpdateError> {
+    let settings = build_wireguard_profile(&config)
+        .map_err(|errors| ProfileUpdateError::Invalid { errors })?;
+    let nm = network_manager().await;
+    let failed = |message: String| ProfileUpdateError::Failed { message };
+
+    match uuid {
+        Some(uuid) => {
+            let changes: Vec<(String, String)> = settings
+                .into_iter()
+                .filter(|(prop, _)| prop != "connection.type")
+                .collect();
+            nm.modify_connection(&uuid, &changes)
+                .await
+                .map_err(failed)?;
+            Ok(uuid)
+        }
+        None => nm.add_connection(&settings).await.map_err(failed),
+    }
+}
+
+/// Formats a tunnel as a `wg-quick` configuration file.
+pub fn wireguard_conf(config: &WireguardConfig) -> String {
+    let mut conf = String::from("[Interface]\n");
+    conf.push_str(&format!("PrivateKey = {}\n", config.private_key.trim()));
+    let addresses = clean_list(&config.addresses);
+    if !addresses.is_empty() {
+        conf.push_str(&format!("Address = {}\n", addresses.join(", ")));
+    }
+    let dns = clean_list(&config.dns);
+    if !dns.is_empty() {
+        conf.push_str(&format!("DNS = {}\n", dns.join(", ")));
+    }
+    if let Some(mtu) = config.mtu {
+        conf.push_str(&format!("MTU = {}\n", mtu));
+    }
+    if let Some(port) = config.listen_port {
+        conf.push_str(&format!("ListenPort = {}\n", port));
+    }
+
+    for peer in &config.peers {
+        conf.push_str("\n[Peer]\n");
+        conf.push_str(&format!("PublicKey = {}\n", peer.public_key.trim()));
+        if !peer.preshared_key.trim().is_empty() {
+            conf.push_str(&format!("PresharedKey = {}\n", peer.preshared_key.trim()));
+        }
+        if !peer.endpoint.trim().is_empty() {
+            conf.push_str(&format!("Endpoint = {}\n", peer.endpoint.trim()));
+        }
+        conf.push_str(&format!(
+            "AllowedIPs = {}\n",
+            clean_list(&peer.allowed_ips).join(", ")
+        ));
+        if let Some(keepalive) = peer.persistent_keepalive {
+            conf.push_str(&format!("PersistentKeepalive = {}\n", keepalive));
+        }
+    }
+    conf
+}
+
+/// Writes a tunnel to a `wg-quick` file. The file holds the private key, so only the owner can read it.
+#[tauri::command]
+pub fn export_wireguard_config(config: WireguardConfig, file_path: String) -> Result<(), String> {
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(0o600)
+        .open(&file_path)
+        .map_err(|e| e.to_string())?;
+    // `mode` only applies to new files; tighten an existing one before writing the key
+    file.set_permissions(Permissions::from_mode(0o600))
+        .and_then(|_| file.write_all(wireguard_conf(&config).as_bytes()))
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+    use std::fs;
+
+    fn key(byte: u8) -> String {
+        encode_key(&[byte; 32])
+    }
+
+    fn peer() -> WireguardPeer {
+        WireguardPeer {
+            public_key: key(2),
+            preshared_key: key(3),
+            endpoint: "vpn.example.com:51820".to_string(),
+            allowed_ips: vec!["0.0.0.0/0".to_string(), "::/0".to_string()],
+            persistent_keepalive: Some(25),
+        }
+    }
+
+    fn config() -> WireguardConfig {
+        WireguardConfig {
+            name: "Office VPN".to_string(),
+            interface: String::new(),
+            private_key: key(1),
+            addresses: vec!["10.0.0.2/32".to_string(), "fd00::2/128".to_string()],
+            dns: vec!["10.0.0.1".to_string()],
+            mtu: Some(1420),
+            listen_port: None,
+            peers: vec![peer()],
+        }
+    }
+
+    fn error_fields(config: &WireguardConfig) -> Vec<String> {
+        build_wireguard_profile(config)
+            .unwrap_err()
+            .into_iter()
+            .map(|e| e.field)
+            .collect()
+    }
+
+    #[test]
+    fn peers_round_trip_through_nmcli_format() {
+        let roaming = WireguardPeer {
+            public_key: key(4),
+            allowed_ips: vec!["10.0.0.0/24".to_string()],
+            ..Default::default()
+        };
+        let formatted = format_peers(&[peer(), roaming]);
+        assert!(formatted.contains("preshared-key-flags=0"));
+
+        let parsed = parse_peers(&formatted);
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(parsed[0].public_key, key(2));
+        assert_eq!(parsed[0].preshared_key, key(3));
+        assert_eq!(parsed[0].endpoint, "vpn.example.com:51820");
+        assert_eq!(parsed[0].allowed_ips, ["0.0.0.0/0", "::/0"]);
+        assert_eq!(parsed[0].persistent_keepalive, Some(25));
+        assert_eq!(parsed[1].public_key, key(4));
+        assert_eq!(parsed[1].endpoint, "");
+        assert_eq!(parsed[1].allowed_ips, ["10.0.0.0/24"]);
+        assert_eq!(parsed[1].persistent_keepalive, None);
+    }
+
+    #[test]
+    fn builds_profile_with_split_address_families() {
+        let settings = build_wireguard_profile(&config()).unwrap();
+        let get = |prop: &str| {
+            settings
+                .iter()
+                .find(|(p, _)| p == prop)
+                .map(|(_, v)| v.as_str())
+        };
+        assert_eq!(get("connection.interface-name"), Some("office-vpn"));
+        assert_eq!(get("ipv4.addresses"), Some("10.0.0.2/32"));
+        assert_eq!(get("ipv6.addresses"), Some("fd00::2/128"));
+        assert_eq!(get("ipv4.dns"), Some("10.0.0.1"));
+        assert_eq!(get("wireguard.mtu"), Some("1420"));
+        assert_eq!(get("wireguard.listen-port"), Some("0"));
+    }
+
+    #[test]
+    fn rejects_invalid_keys() {
+        let mut config = config();
+        config.private_key = "not-a-key".to_string();
+        config.peers[0].public_key = STANDARD.encode([1u8; 16]);
+        config.peers[0].preshared_key = "bad".to_string();
+        assert_eq!(
+            error_fields(&config),
+            [
+                "private_key",
+                "peers[0].public_key",
+                "peers[0].preshared_key"
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_peer_without_allowed_ips() {
+        let mut config = config();
+        config.peers[0].allowed_ips = vec![" ".to_string()];
+        assert_eq!(error_fields(&config), ["peers[0].allowed_ips"]);
+    }
+
+    #[test]
+    fn rejects_endpoint_without_port() {
+        for endpoint in ["vpn.example.com", "vpn.example.com:", ":51820", "[fd00::1]"] {
+            let mut config = config();
+            config.peers[0].endpoint = endpoint.to_string();
+            assert_eq!(error_fields(&config), ["peers[0].endpoint"], "{}", endpoint);
+        }
+        assert!(is_valid_endpoint("[fd00::1]:51820"));
+    }
+
+    #[test]
+    fn rejects_long_interface_name() {
+        let mut config = config();
+        config.interface = "wireguard-office".to_string(); // 16 characters
+        assert_eq!(error_fields(&config), ["interface"]);
+
+        config.interface = "wireguard-offic".to_string();
+        assert!(build_wireguard_profile(&config).is_ok());
+    }
+
+    #[test]
+    fn formats_wg_quick_file() {
+        let mut config = config();
+        config.listen_port = Some(51820);
+        config.peers.push(WireguardPeer {
+            public_key: key(4),
+            allowed_ips: vec!["10.0.0.0/24".to_string()],
+            ..Default::default()
+        });
+        let expected = format!(
+            "[Interface]\n\
+             PrivateKey = {}\n\
+             Address = 10.0.0.2/32, fd00::2/128\n\
+             DNS = 10.0.0.1\n\
+             MTU = 1420\n\
+             ListenPort = 51820\n\
+             \n\
+             [Peer]\n\
+             PublicKey = {}\n\
+             PresharedKey = {}\n\
+             Endpoint = vpn.example.com:51820\n\
+             AllowedIPs = 0.0.0.0/0, ::/0\n\
+             PersistentKeepalive = 25\n\
+             \n\
+             [Peer]\n\
+             PublicKey = {}\n\
+             AllowedIPs = 10.0.0.0/24\n",
+            key(1),
+            key(2),
+            key(3),
+            key(4)
+        );
+        assert_eq!(wireguard_conf(&config), expected);
+    }
+
+    #[test]
+    fn export_restricts_existing_file_to_owner() {
+        let path = env::temp_dir().join(format!("wireguard-export-{}.conf", std::process::id()));
+        fs::write(&path, "old").unwrap();
+        fs::set_permissions(&path, Permissions::from_mode(0o644)).unwrap();
+
+        export_wireguard_config(config(), path.to_string_lossy().into_owned()).unwrap();
+        let mode = fs::metadata(&path).unwrap().permissions().mode() & 0o777;
+        let written = fs::read_to_string(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(mode, 0o600);
+        assert_eq!(written, wireguard_conf(&config()));
+    }
+}
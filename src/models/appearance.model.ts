@@ -3,7 +3,30 @@
  * Contains data structures for application appearance settings
  */
 
+import type { VpnAutoConnectSettings } from './vpn.model';
+
 export interface AppSettings {
     theme: string;
     waybar_position?: string;
+    night_light?: NightLightSettings;
+    vpn_auto_connect?: VpnAutoConnectSettings;
+}
+
+export interface NightLightSettings {
+    enabled: boolean;
+    temperature: number;
+    schedule: 'fixed' | 'sun';
+    start: string;
+    end: string;
+    latitude: number;
+    longitude: number;
+}
+
+export interface NightLightStatus {
+    active: boolean;
+    temperature: number | null;
+    /** Local "YYYY-MM-DD HH:MM" of the next switch-on, empty when there is none */
+    next_start: string;
+    /** Local "YYYY-MM-DD HH:MM" of the next switch-off, empty when there is none */
+    next_end: string;
 }
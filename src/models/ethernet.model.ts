@@ -0,0 +1,26 @@
+/**
+ * Ethernet Model
+ * Contains data structures for wired network management
+ */
+
+import type { EapMethod } from './wifi.model.ts';
+
+export interface DeviceInfo {
+    interface: string;
+    device_type: string;
+    state: string;
+    mac: string;
+    speed: number | null; // link speed in Mb/s
+    carrier: boolean;
+    connection: string;
+}
+
+export interface EthernetConnection {
+    uuid: string;
+    name: string;
+    active: boolean;
+    device: string;
+    interface_name: string;
+    autoconnect: boolean;
+    eap: EapMethod | null;
+}
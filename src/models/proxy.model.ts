@@ -0,0 +1,38 @@
+/**
+ * Proxy Model
+ * Contains data structures for system and per-connection proxy settings
+ */
+
+import type { EnvVar } from '../viewmodels/env.viewmodel.ts';
+
+export type ProxyMode = 'none' | 'manual' | 'pac';
+
+export interface ProxyServer {
+    host: string; // empty when the protocol is not proxied
+    port: number;
+}
+
+export interface SystemProxy {
+    mode: ProxyMode;
+    http: ProxyServer;
+    https: ProxyServer;
+    socks: ProxyServer;
+    pac_url: string;
+    ignore_hosts: string[];
+}
+
+export interface SystemProxyReport {
+    proxy: SystemProxy;
+    env: EnvVar[]; // http_proxy, https_proxy and no_proxy found in env.conf
+}
+
+// NetworkManager has no manual mode; 'auto' uses the PAC URL or WPAD
+export type ConnectionProxyMethod = 'none' | 'auto';
+
+export interface ConnectionProxy {
+    method: ConnectionProxyMethod;
+    pac_url: string;
+    browser_only: boolean;
+}
+
+export const emptyProxyServer = (): ProxyServer => ({ host: '', port: 0 });
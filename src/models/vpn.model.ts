@@ -7,4 +7,91 @@ export interface VpnConnection {
     name: string;
     active: boolean;
     type_name: string;
+    autoconnect: boolean;
 }
+
+export interface VpnPlugin {
+    name: string; // e.g. "openconnect", also the import type
+    service: string;
+    label: string;
+    can_create: boolean; // has a creation form
+}
+
+export type OpenConnectProtocol = 'anyconnect' | 'gp' | 'nc' | 'pulse' | 'fortinet';
+
+export type StrongswanMethod = 'eap' | 'key' | 'psk';
+
+export type VpnCreateRequest =
+    | {
+        type: 'openconnect';
+        name: string;
+        gateway: string;
+        protocol: OpenConnectProtocol;
+        ca_cert?: string | null;
+        user_cert?: string | null;
+        private_key?: string | null;
+    }
+    | {
+        type: 'l2tp';
+        name: string;
+        gateway: string;
+        username: string;
+        password?: string | null;
+        ipsec_psk?: string | null; // enables IPsec
+    }
+    | {
+        type: 'strongswan';
+        name: string;
+        gateway: string;
+        method: StrongswanMethod;
+        username?: string | null;
+        password?: string | null; // EAP password or PSK
+        ca_cert?: string | null;
+        user_cert?: string | null;
+        private_key?: string | null;
+    }
+    | {
+        type: 'pptp';
+        name: string;
+        gateway: string;
+        username: string;
+        password?: string | null;
+    };
+
+export interface VpnAutoConnectSettings {
+    enabled: boolean;
+    vpn_uuid: string; // VPN brought up on untrusted Wi-Fi
+    trusted_ssids: string[];
+}
+
+export interface WireguardKeypair {
+    private_key: string;
+    public_key: string;
+}
+
+export interface WireguardPeer {
+    public_key: string;
+    preshared_key: string; // empty when not used
+    endpoint: string; // host:port
+    allowed_ips: string[];
+    persistent_keepalive: number | null; // seconds
+}
+
+export interface WireguardConfig {
+    name: string;
+    interface: string; // e.g. "wg0", derived from the name when empty
+    private_key: string;
+    addresses: string[]; // CIDR
+    dns: string[];
+    mtu: number | null;
+    listen_port: number | null;
+    peers: WireguardPeer[];
+}
+
+export const emptyWireguardPeer = (): WireguardPeer => ({
+    public_key: '',
+    preshared_key: '',
+    endpoint: '',
+    allowed_ips: ['0.0.0.0/0', '::/0'],
+    persistent_keepalive: 25
+});
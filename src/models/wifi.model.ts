@@ -10,10 +10,182 @@ export interface WifiNetwork {
     active: boolean;
 }
 
-export interface WifiConfig {
+export interface BssidInfo {
+    bssid: string;
+    signal: number;
+    bars: string;
+    frequency: number; // MHz
+    band: string;
+    channel: number;
+    max_bitrate: number; // kbit/s
+    security: string;
+    wpa_flags: string;
+    rsn_flags: string;
+    last_seen: number | null; // seconds ago
+    device: string;
+    active: boolean;
+}
+
+export interface WifiNetworkDetails {
+    ssid: string;
+    security: string;
+    active: boolean;
+    access_points: BssidInfo[];
+}
+
+// 'bg' is 2.4 GHz, 'a' is 5 GHz
+export type WifiBand = 'auto' | 'bg' | 'a';
+
+export interface StaticRoute {
+    destination: string;
+    next_hop: string | null;
+    metric: number | null;
+}
+
+export interface IpSettings {
     method: string;
-    ip_address: string;
-    prefix: number;
+    addresses: string[];
+    gateway: string;
+    dns: string[];
+    dns_search: string[];
+    route_metric: number | null;
+    routes: StaticRoute[];
+}
+
+export type Ipv6Privacy = 'default' | 'disabled' | 'prefer-public' | 'prefer-temporary';
+
+export interface WifiConfig {
+    ipv4: IpSettings;
+    ipv6: IpSettings;
+    ipv6_privacy: Ipv6Privacy;
+}
+
+export interface ActiveIp {
+    addresses: string[];
     gateway: string;
-    dns: string;
+    dns: string[];
+}
+
+export interface WifiLinkInfo {
+    uuid: string;
+    name: string;
+    ssid: string;
+    interface: string;
+    access_point: BssidInfo | null;
+    bitrate: number | null; // kbit/s
+    ip: { ipv4: ActiveIp; ipv6: ActiveIp };
+}
+
+// Payloads of the events emitted by the backend Wi-Fi monitor
+export interface RadioChanged {
+    enabled: boolean;
+}
+
+export interface DeviceStateChanged {
+    interface: string;
+    state: string;
+}
+
+export interface NetworksChanged {
+    networks: WifiNetwork[];
 }
+
+export interface ConnectionChanged {
+    link: WifiLinkInfo | null;
+}
+
+export interface IpConfigReport {
+    configured: WifiConfig;
+    active: { ipv4: ActiveIp; ipv6: ActiveIp } | null;
+}
+
+export type Metered = 'yes' | 'no' | 'unknown';
+
+export type MacRandomization = 'preserve' | 'permanent' | 'random' | 'stable';
+
+export interface SavedNetwork {
+    uuid: string;
+    name: string;
+    ssid: string;
+    active: boolean;
+    last_used: number | null;
+    autoconnect: boolean;
+    autoconnect_priority: number;
+    metered: string;
+    mac_randomization: string;
+    bssid: string; // empty when not pinned
+    band: WifiBand;
+}
+
+export type EapMethod = 'peap' | 'ttls' | 'tls';
+
+export type Phase2Auth = 'mschapv2' | 'mschap' | 'chap' | 'pap' | 'gtc' | 'md5';
+
+export interface Eap8021x {
+    method: EapMethod;
+    identity: string;
+    anonymous_identity?: string | null;
+    password?: string | null;
+    phase2_auth?: Phase2Auth | null;
+    ca_cert?: string | null;
+    client_cert?: string | null;
+    private_key?: string | null;
+    private_key_password?: string | null;
+}
+
+export type WifiSecurity =
+    | { type: 'open' }
+    | { type: 'wpa_psk'; password: string }
+    | { type: 'sae'; password: string }
+    | { type: 'enterprise'; eap: Eap8021x };
+
+export interface WifiConnectRequest {
+    ssid: string;
+    hidden: boolean;
+    security: WifiSecurity;
+}
+
+export interface ProfileError {
+    field: string;
+    message: string;
+}
+
+export type WifiConnectError =
+    | { kind: 'invalid'; errors: ProfileError[] }
+    | { kind: 'not_found'; ssid: string }
+    | { kind: 'auth_failed'; message: string }
+    | { kind: 'timeout'; message: string }
+    | { kind: 'failed'; message: string };
+
+export type ProfileUpdateError =
+    | { kind: 'invalid'; errors: ProfileError[] }
+    | { kind: 'failed'; message: string }
+    | { kind: 'activation_failed'; message: string };
+
+export interface HotspotConfig {
+    ssid: string;
+    password: string;
+    band: WifiBand;
+    channel: number | null;
+    interface: string | null;
+}
+
+export interface HotspotStatus {
+    active: boolean;
+    uuid: string | null;
+    ssid: string;
+    password: string;
+    band: WifiBand;
+    channel: number | null;
+    interface: string;
+}
+
+export const emptyIpSettings = (): IpSettings => ({
+    method: 'auto',
+    addresses: [],
+    gateway: '',
+    dns: [],
+    dns_search: [],
+    route_metric: null,
+    routes: []
+});
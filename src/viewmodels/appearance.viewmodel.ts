@@ -1,12 +1,12 @@
 /**
  * Appearance ViewModel (Composable)
- * Contains business logic for appearance settings (theme, wallpaper, waybar)
+ * Contains business logic for appearance settings (theme, wallpaper, waybar, night light)
  */
 
 import { ref, onMounted } from 'vue';
 import { invoke } from '@tauri-apps/api/core';
 import { open } from '@tauri-apps/plugin-dialog';
-import type { AppSettings } from '../models/appearance.model';
+import type { AppSettings, NightLightSettings, NightLightStatus } from '../models/appearance.model';
 import { useToast } from '../composables/useToast';
 
 export function useAppearanceViewModel() {
@@ -45,6 +45,19 @@ export function useAppearanceViewModel() {
         disable_logo: true
     });
 
+    // --- Night Light State ---
+    const nightLight = ref<NightLightSettings>({
+        enabled: false,
+        temperature: 4000,
+        schedule: 'fixed',
+        start: '20:00',
+        end: '07:00',
+        latitude: 0,
+        longitude: 0
+    });
+    const nightLightStatus = ref<NightLightStatus | null>(null);
+    const savingNightLight = ref(false);
+
     // --- Actions ---
 
     // Load initial data
@@ -225,6 +238,55 @@ export function useAppearanceViewModel() {
         }
     };
 
+    // Night Light Logic
+    const loadNightLightStatus = async () => {
+        try {
+            nightLightStatus.value = await invoke<NightLightStatus>('get_night_light_status');
+        } catch (e) {
+            nightLightStatus.value = null;
+        }
+    };
+
+    const saveNightLight = async () => {
+        if (savingNightLight.value) return;
+
+        savingNightLight.value = true;
+        try {
+            if (nightLight.value.enabled) {
+                await invoke('enable_night_light', {
+                    settings: {
+                        ...nightLight.value,
+                        temperature: Number(nightLight.value.temperature),
+                        latitude: Number(nightLight.value.latitude),
+                        longitude: Number(nightLight.value.longitude)
+                    }
+                });
+                showToast('Night light schedule saved', 'success');
+            } else {
+                await invoke('disable_night_light');
+                showToast('Night light disabled', 'success');
+            }
+        } catch (e: any) {
+            showToast('Failed to save night light: ' + e, 'error');
+        } finally {
+            savingNightLight.value = false;
+            await loadNightLightStatus();
+        }
+    };
+
+    const toggleNightLight = async () => {
+        nightLight.value.enabled = !nightLight.value.enabled;
+        await saveNightLight();
+    };
+
+    const previewNightLight = async () => {
+        try {
+            await invoke('preview_night_light', { temperature: Number(nightLight.value.temperature) });
+        } catch (e: any) {
+            showToast('Failed to preview night light: ' + e, 'error');
+        }
+    };
+
     // App Availability State
     const isWaybarInstalled = ref(false);
     const isKittyInstalled = ref(false);
@@ -247,12 +309,16 @@ export function useAppearanceViewModel() {
             if (settings.waybar_position) {
                 waybarPosition.value = settings.waybar_position;
             }
+            if (settings.night_light) {
+                nightLight.value = settings.night_light;
+            }
         } catch (e) {
             showToast('Failed to load app settings: ' + e, 'error');
         }
 
         // Initial Preview Load
         updatePreview();
+        loadNightLightStatus();
     });
 
     return {
@@ -273,6 +339,9 @@ export function useAppearanceViewModel() {
         selectedGtkTheme,
         cursorSizes,
         hyprlandConfig,
+        nightLight,
+        nightLightStatus,
+        savingNightLight,
 
         // Actions
         toggleTheme,
@@ -280,6 +349,9 @@ export function useAppearanceViewModel() {
         handleImageError,
         setWaybarPosition,
         applyAppearanceSettings,
-        applyHyprlandConfig
+        applyHyprlandConfig,
+        saveNightLight,
+        toggleNightLight,
+        previewNightLight
     };
 }
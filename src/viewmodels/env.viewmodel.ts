@@ -13,9 +13,46 @@ export interface EnvVar {
     value: string;
 }
 
+export interface EnvIssue {
+    id: string;
+    key: string;
+    severity: 'error' | 'warning';
+    message: string;
+}
+
+export interface EnvPresetStatus {
+    id: string;
+    name: string;
+    description: string;
+    vars: [string, string][];
+    applied: boolean;
+    partial: boolean;
+}
+
+export interface EffectiveEnvVar {
+    key: string;
+    configured: string;
+    hyprland: string | null;
+    systemd: string | null;
+    uwsm: string | null;
+    mismatch: boolean;
+}
+
+export interface EffectiveEnvReport {
+    /** False when no Hyprland process could be read; `hyprland` values are then null */
+    hyprland_available: boolean;
+    vars: EffectiveEnvVar[];
+}
+
+export type EnvSyncTarget = 'environment_d' | 'uwsm';
+
 export function useEnvViewModel() {
     const loading = ref(false);
     const envVars = ref<EnvVar[]>([]);
+    const issues = ref<EnvIssue[]>([]);
+    const presets = ref<EnvPresetStatus[]>([]);
+    const effectiveEnv = ref<EffectiveEnvVar[]>([]);
+    const hyprlandAvailable = ref(false);
     const { showToast } = useToast();
 
     const loadEnvVars = async () => {
@@ -34,6 +71,8 @@ export function useEnvViewModel() {
             } else {
                 envVars.value = result;
             }
+            // Problems already in env.conf are shown but only block edits to those variables
+            await validateEnvVars();
         } catch (e: any) {
             showToast('Failed to load environment variables', 'error');
             // Fallback to empty row
@@ -57,12 +96,105 @@ export function useEnvViewModel() {
             await loadEnvVars();
             showToast('Environment variables saved successfully', 'success');
         } catch (e: any) {
-            showToast('Failed to save environment variables', 'error');
+            showToast(`Failed to save environment variables: ${e}`, 'error');
         } finally {
             loading.value = false;
         }
     };
 
+    /**
+     * Saves a single variable in place: new rows are appended, existing rows updated by ID.
+     */
+    const saveEnvVar = async (envVar: EnvVar) => {
+        try {
+            const saved = envVar.id.startsWith('new-')
+                ? await invoke<EnvVar>('add_env_var', { var: envVar })
+                : await invoke<EnvVar>('update_env_var', { id: envVar.id, var: envVar });
+            const index = envVars.value.findIndex(v => v.id === envVar.id);
+            if (index !== -1) envVars.value[index] = saved;
+            await validateEnvVars();
+        } catch (e: any) {
+            showToast(`Failed to save ${envVar.key}: ${e}`, 'error');
+            await loadEnvVars();
+        }
+    };
+
+    /**
+     * Deletes a single saved variable from env.conf.
+     */
+    const deleteEnvVar = async (id: string) => {
+        try {
+            if (!id.startsWith('new-')) {
+                await invoke('delete_env_var', { id });
+            }
+            envVars.value = envVars.value.filter(v => v.id !== id);
+        } catch (e: any) {
+            showToast(`Failed to delete variable: ${e}`, 'error');
+            await loadEnvVars();
+        }
+    };
+
+    /**
+     * Validates the current rows without saving.
+     */
+    const validateEnvVars = async () => {
+        try {
+            issues.value = await invoke<EnvIssue[]>('validate_env_vars', { vars: envVars.value });
+        } catch (e) {
+            console.error('Failed to validate environment variables:', e);
+        }
+    };
+
+    const loadPresets = async () => {
+        try {
+            presets.value = await invoke<EnvPresetStatus[]>('get_env_presets');
+        } catch (e) {
+            console.error('Failed to load presets:', e);
+        }
+    };
+
+    /**
+     * Applies or removes a preset group in env.conf.
+     */
+    const togglePreset = async (preset: EnvPresetStatus, overrides?: Record<string, string>) => {
+        try {
+            if (preset.applied) {
+                await invoke('remove_env_preset', { id: preset.id });
+            } else {
+                await invoke('apply_env_preset', { id: preset.id, overrides });
+            }
+            await Promise.all([loadEnvVars(), loadPresets()]);
+        } catch (e: any) {
+            showToast(`Failed to update preset: ${e}`, 'error');
+        }
+    };
+
+    /**
+     * Compares env.conf with the running Hyprland, systemd user and uwsm environments.
+     */
+    const loadEffectiveEnv = async () => {
+        try {
+            const report = await invoke<EffectiveEnvReport>('get_effective_env');
+            effectiveEnv.value = report.vars;
+            hyprlandAvailable.value = report.hyprland_available;
+        } catch (e) {
+            console.error('Failed to read effective environment:', e);
+        }
+    };
+
+    /**
+     * Copies env.conf variables to environment.d and/or the uwsm env file.
+     */
+    const syncEnvVars = async (targets: EnvSyncTarget[]) => {
+        try {
+            await invoke('sync_env_vars', { targets });
+            await loadEffectiveEnv();
+            showToast('Environment synced', 'success');
+        } catch (e: any) {
+            showToast(`Failed to sync environment: ${e}`, 'error');
+        }
+    };
+
     const addEnvVar = () => {
         envVars.value.unshift({
             id: `new-${Date.now()}`,
@@ -80,14 +212,25 @@ export function useEnvViewModel() {
 
     onMounted(() => {
         loadEnvVars();
+        loadPresets();
     });
 
     return {
         loading,
         envVars,
+        issues,
+        presets,
+        effectiveEnv,
+        hyprlandAvailable,
         loadEnvVars,
+        loadEffectiveEnv,
+        syncEnvVars,
+        validateEnvVars,
+        togglePreset,
         saveEnvVars,
         addEnvVar,
-        removeEnvVar
+        removeEnvVar,
+        saveEnvVar,
+        deleteEnvVar
     };
 }
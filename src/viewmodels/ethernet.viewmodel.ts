@@ -0,0 +1,175 @@
+import { ref, onMounted } from 'vue';
+import { invoke } from '@tauri-apps/api/core';
+import type { DeviceInfo, EthernetConnection } from '../models/ethernet.model.ts';
+import type {
+    WifiConfig,
+    ActiveIp,
+    Eap8021x,
+    IpConfigReport,
+    ProfileError,
+    ProfileUpdateError
+} from '../models/wifi.model.ts';
+import { emptyIpSettings } from '../models/wifi.model.ts';
+import { useToast } from '../composables/useToast';
+
+/**
+ * ViewModel for wired Ethernet ports and their connection profiles.
+ * Shares the IP configuration form and 802.1X fields with Wi-Fi.
+ */
+export function useEthernetViewModel() {
+    // --- State ---
+    const devices = ref<DeviceInfo[]>([]);
+    const connections = ref<EthernetConnection[]>([]);
+    const loading = ref(false);
+
+    const { showToast } = useToast();
+
+    // Configuration Modal state
+    const showConfigModal = ref(false);
+    const savingConfig = ref(false);
+    const selectedConnection = ref<EthernetConnection | null>(null);
+    const config = ref<WifiConfig>({
+        ipv4: emptyIpSettings(),
+        ipv6: emptyIpSettings(),
+        ipv6_privacy: 'default'
+    });
+    const activeConfig = ref<{ ipv4: ActiveIp; ipv6: ActiveIp } | null>(null);
+    const configErrors = ref<ProfileError[]>([]);
+
+    // --- Actions ---
+
+    /**
+     * Loads wired devices and saved wired profiles.
+     */
+    const refresh = async () => {
+        loading.value = true;
+        try {
+            [devices.value, connections.value] = await Promise.all([
+                invoke<DeviceInfo[]>('get_ethernet_devices'),
+                invoke<EthernetConnection[]>('get_ethernet_connections')
+            ]);
+        } catch (e) {
+            console.error("Failed to load Ethernet state:", e);
+        } finally {
+            loading.value = false;
+        }
+    };
+
+    /**
+     * Creates a profile for a port that has none yet.
+     */
+    const addConnection = async (device: DeviceInfo, name?: string) => {
+        try {
+            await invoke<string>('add_ethernet_connection', { interface: device.interface, name: name ?? null });
+            await refresh();
+        } catch (e) {
+            showToast(`Failed to create connection: ${e}`, 'error');
+        }
+    };
+
+    /**
+     * Opens the configuration modal for a wired connection.
+     */
+    const openConfig = async (conn: EthernetConnection) => {
+        selectedConnection.value = conn;
+        try {
+            const report = await invoke<IpConfigReport>('get_ethernet_config', { id: conn.uuid });
+            config.value = report.configured;
+            activeConfig.value = report.active;
+            configErrors.value = [];
+            showConfigModal.value = true;
+        } catch (e) {
+            showToast(`Failed to retrieve configuration: ${e}`, 'error');
+        }
+    };
+
+    /**
+     * Closes the configuration modal.
+     */
+    const closeConfig = () => {
+        showConfigModal.value = false;
+    };
+
+    /**
+     * Reports a failed profile update, keeping field errors for the form.
+     */
+    const reportUpdateError = (e: unknown) => {
+        const err = e as ProfileUpdateError;
+        if (err.kind === 'invalid') {
+            configErrors.value = err.errors;
+            showToast('Please check the highlighted fields', 'error');
+        } else {
+            showToast(`Failed to apply settings: ${err.message}`, 'error');
+        }
+    };
+
+    /**
+     * Saves the IP configuration of the selected connection.
+     */
+    const saveConfig = async () => {
+        if (!selectedConnection.value) return;
+
+        savingConfig.value = true;
+        configErrors.value = [];
+        try {
+            await invoke('set_ethernet_config', {
+                id: selectedConnection.value.uuid,
+                config: config.value
+            });
+            showConfigModal.value = false;
+            await refresh();
+            showToast('Network settings saved successfully', 'success');
+        } catch (e) {
+            reportUpdateError(e);
+        } finally {
+            savingConfig.value = false;
+        }
+    };
+
+    const setAutoconnect = async (conn: EthernetConnection, enabled: boolean) => {
+        try {
+            await invoke('set_ethernet_autoconnect', { uuid: conn.uuid, enabled });
+            await refresh();
+        } catch (e) {
+            showToast(`Failed to update autoconnect: ${e}`, 'error');
+        }
+    };
+
+    /**
+     * Enables 802.1X port authentication, or disables it when `eap` is null.
+     */
+    const set8021x = async (conn: EthernetConnection, eap: Eap8021x | null) => {
+        configErrors.value = [];
+        try {
+            await invoke('set_ethernet_8021x', { uuid: conn.uuid, eap });
+            await refresh();
+            showToast(eap ? '802.1X authentication enabled' : '802.1X authentication disabled', 'success');
+            return true;
+        } catch (e) {
+            reportUpdateError(e);
+            return false;
+        }
+    };
+
+    // --- Lifecycle ---
+    onMounted(refresh);
+
+    return {
+        devices,
+        connections,
+        loading,
+        showConfigModal,
+        savingConfig,
+        selectedConnection,
+        config,
+        activeConfig,
+        configErrors,
+        refresh,
+        addConnection,
+        openConfig,
+        closeConfig,
+        saveConfig,
+        setAutoconnect,
+        set8021x
+    };
+}
@@ -3,22 +3,91 @@
  * Logic for managing Hyprland Keybinds
  */
 
-import { ref, onMounted } from 'vue';
+import { ref, computed, onMounted } from 'vue';
 import { invoke } from '@tauri-apps/api/core';
+import { save } from '@tauri-apps/plugin-dialog';
 import { useToast } from '../composables/useToast';
 
 export interface Keybind {
     id: string;
-    bind_type: string;
+    flags: string[];
+    description: string | null;
     modifiers: string;
     key: string;
     dispatcher: string;
     args: string;
+    normalized_modifiers?: string[];
+    submap: string;
+}
+
+export interface Submap {
+    name: string;
+    bind_count: number;
+    entered_by: string[];
+}
+
+export interface KeybindConflict {
+    kind: 'duplicate' | 'shadowed';
+    combo: string;
+    submap: string;
+    ids: string[];
+    sources: string[];
+}
+
+export interface DispatcherArg {
+    name: string;
+    kind: string;
+    optional: boolean;
+}
+
+export interface DispatcherInfo {
+    name: string;
+    category: string;
+    description: string;
+    args: DispatcherArg[];
+}
+
+export type SaveKeybindsError =
+    | { kind: 'invalid'; errors: { id: string; message: string }[] }
+    | { kind: 'warnings'; warnings: { id: string; message: string }[] }
+    | { kind: 'conflicts'; conflicts: KeybindConflict[] }
+    | { kind: 'not_found'; id: string }
+    | { kind: 'io'; message: string };
+
+export interface RecordedKey {
+    modifiers: string;
+    key: string;
+    conflicts: Keybind[];
+}
+
+export interface KeybindsResult {
+    keybinds: Keybind[];
+    conflicts: KeybindConflict[];
+}
+
+/**
+ * Keybind as edited in the view. `bind_type` is the full keyword (e.g. `bindel`)
+ * and is converted to and from the backend flag set.
+ */
+export interface EditableKeybind extends Keybind {
+    bind_type: string;
 }
 
+const flagsToBindType = (flags: string[]) => 'bind' + flags.filter(f => f !== 'd').join('');
+
+const bindTypeToFlags = (bindType: string) => bindType.replace(/^bind/, '').split('').filter(f => f !== 'd');
+
 export function useKeybindsViewModel() {
     const loading = ref(false);
-    const keybinds = ref<Keybind[]>([]);
+    const keybinds = ref<EditableKeybind[]>([]);
+    const conflicts = ref<KeybindConflict[]>([]);
+    // Unknown dispatchers reported by the last save attempt
+    const warnings = ref<{ id: string; message: string }[]>([]);
+    // Set when a save was refused because of warnings or conflicts, so the view can offer "Save anyway"
+    const saveBlocked = ref(false);
+    const dispatchers = ref<DispatcherInfo[]>([]);
+    const submaps = ref<Submap[]>([]);
+    const errors = ref<Record<string, string>>({});
     const { showToast } = useToast();
 
     // Bind Types
@@ -35,7 +104,7 @@ export function useKeybindsViewModel() {
     ]);
 
     // Standard modifiers
-    const modifierOptions = [
+    const modifierOptions = ref([
         { label: 'Super', value: 'SUPER' },
         { label: 'Super + Shift', value: 'SUPER SHIFT' },
         { label: 'Super + Ctrl', value: 'SUPER CTRL' },
@@ -43,14 +112,15 @@ export function useKeybindsViewModel() {
         { label: 'Ctrl + Alt', value: 'CTRL ALT' },
         { label: 'Alt', value: 'ALT' },
         { label: 'None', value: '' }
-    ];
+    ]);
 
     const loadKeybinds = async () => {
         loading.value = true;
         try {
-            const result = await invoke<Keybind[]>('get_keybinds');
-            keybinds.value = result.map(kb => {
-                const type = kb.bind_type || 'bind';
+            const result = await invoke<KeybindsResult>('get_keybinds');
+            conflicts.value = result.conflicts;
+            keybinds.value = result.keybinds.map(kb => {
+                const type = flagsToBindType(kb.flags || []);
 
                 if (!bindTypes.value.find(t => t.value === type)) {
                     bindTypes.value.push({
@@ -72,27 +142,161 @@ export function useKeybindsViewModel() {
         }
     };
 
-    const saveKeybinds = async () => {
+    const saveKeybinds = async (force = false) => {
         loading.value = true;
         try {
             await invoke('save_keybinds', {
                 args: {
-                    keybinds: keybinds.value
+                    force,
+                    keybinds: keybinds.value.map(({ bind_type, ...kb }) => ({
+                        ...kb,
+                        flags: bindTypeToFlags(bind_type)
+                    }))
                 }
             });
+            errors.value = {};
+            warnings.value = [];
+            saveBlocked.value = false;
             await loadKeybinds();
             showToast('Keybinds saved successfully', 'success');
         } catch (e: any) {
-            showToast('Failed to save keybinds', 'error');
+            const err = e as SaveKeybindsError;
+            if (err.kind === 'invalid') {
+                errors.value = Object.fromEntries(err.errors.map(x => [x.id, x.message]));
+                showToast(`${err.errors.length} keybind(s) are invalid`, 'error');
+            } else if (err.kind === 'warnings') {
+                warnings.value = err.warnings;
+                saveBlocked.value = true;
+                showToast(`${err.warnings.length} keybind(s) use unknown dispatchers`, 'warning');
+            } else if (err.kind === 'conflicts') {
+                conflicts.value = err.conflicts;
+                saveBlocked.value = true;
+                showToast(`Conflicting keybinds: ${err.conflicts.map(c => c.combo).join(', ')}`, 'error');
+            } else {
+                showToast('Failed to save keybinds', 'error');
+            }
         } finally {
             loading.value = false;
         }
     };
 
+    /**
+     * Saves a single keybind in place: new rows are appended, existing rows updated by ID.
+     */
+    const saveKeybind = async ({ bind_type, ...kb }: EditableKeybind, force = false) => {
+        const payload = { ...kb, flags: bindTypeToFlags(bind_type) };
+        try {
+            if (kb.id.startsWith('new-')) {
+                await invoke<Keybind>('add_keybind', { keybind: payload, force });
+            } else {
+                await invoke<Keybind>('update_keybind', { id: kb.id, keybind: payload, force });
+            }
+            await loadKeybinds();
+        } catch (e: any) {
+            const err = e as SaveKeybindsError;
+            if (err.kind === 'not_found') {
+                showToast('Keybind changed on disk, reloading', 'error');
+                await loadKeybinds();
+            } else if (err.kind === 'invalid') {
+                errors.value = { ...errors.value, [kb.id]: err.errors[0]?.message ?? 'Invalid keybind' };
+            } else if (err.kind === 'warnings') {
+                showToast(err.warnings[0]?.message ?? 'Unknown dispatcher', 'warning');
+            } else if (err.kind === 'conflicts') {
+                showToast(`Conflicting keybinds: ${err.conflicts.map(c => c.combo).join(', ')}`, 'error');
+            } else {
+                showToast('Failed to save keybind', 'error');
+            }
+        }
+    };
+
+    /**
+     * Deletes a single saved keybind from the keybinds file.
+     */
+    const deleteKeybind = async (id: string) => {
+        try {
+            if (!id.startsWith('new-')) {
+                await invoke('delete_keybind', { id });
+            }
+            keybinds.value = keybinds.value.filter(kb => kb.id !== id);
+        } catch (e: any) {
+            showToast('Failed to delete keybind', 'error');
+            await loadKeybinds();
+        }
+    };
+
+    /**
+     * Exports a cheatsheet of all keybinds to a file chosen by the user.
+     */
+    const exportCheatsheet = async (format: 'markdown' | 'html' | 'json') => {
+        const extension = format === 'markdown' ? 'md' : format;
+        const path = await save({
+            defaultPath: `keybinds.${extension}`,
+            filters: [{ name: format.toUpperCase(), extensions: [extension] }]
+        });
+        if (!path) return;
+
+        try {
+            await invoke('export_keybind_cheatsheet', { format, path });
+            showToast('Cheatsheet exported', 'success');
+        } catch (e: any) {
+            showToast(`Failed to export cheatsheet: ${e}`, 'error');
+        }
+    };
+
+    /**
+     * Converts a captured keyboard or mouse event into Hyprland modifiers and key name.
+     */
+    const recordKey = async (event: KeyboardEvent | MouseEvent, submap = '') => {
+        const isKey = event instanceof KeyboardEvent;
+        try {
+            return await invoke<RecordedKey>('resolve_captured_key', {
+                event: {
+                    code: isKey ? event.code : '',
+                    key: isKey ? event.key : '',
+                    mouse_button: isKey ? null : event.button,
+                    super_key: event.metaKey,
+                    ctrl: event.ctrlKey,
+                    alt: event.altKey,
+                    shift: event.shiftKey
+                },
+                submap
+            });
+        } catch (e: any) {
+            showToast(`${e}`, 'error');
+            return null;
+        }
+    };
+
+    /**
+     * Records a key press into a keybind row. Returns false while only modifiers are held,
+     * so the view keeps listening until the combination is complete.
+     */
+    const captureKey = async (event: KeyboardEvent, kb: EditableKeybind) => {
+        if (['Shift', 'Control', 'Alt', 'Meta', 'Super', 'OS', 'CapsLock'].includes(event.key)) {
+            return false;
+        }
+
+        const recorded = await recordKey(event, kb.submap);
+        if (!recorded) return false;
+
+        if (!modifierOptions.value.find(o => o.value === recorded.modifiers)) {
+            modifierOptions.value.push({ label: recorded.modifiers, value: recorded.modifiers });
+        }
+        kb.modifiers = recorded.modifiers;
+        kb.key = recorded.key;
+        if (recorded.conflicts.length > 0) {
+            showToast(`${recorded.modifiers} ${recorded.key} is already bound`, 'warning');
+        }
+        return true;
+    };
+
     const addKeybind = () => {
         keybinds.value.unshift({
             id: `new-${Date.now()}`,
             bind_type: 'bind',
+            flags: [],
+            description: null,
+            submap: '',
             modifiers: 'SUPER',
             key: '',
             dispatcher: 'exec',
@@ -100,22 +304,90 @@ export function useKeybindsViewModel() {
         });
     };
 
+    /**
+     * IDs of keybinds involved in any reported conflict or warning.
+     */
+    const conflictingIds = computed(() => new Set([
+        ...conflicts.value.flatMap(c => c.ids),
+        ...warnings.value.map(w => w.id)
+    ]));
+
     const removeKeybind = (index: number) => {
         keybinds.value.splice(index, 1);
     };
 
+    const loadSubmaps = async () => {
+        try {
+            submaps.value = await invoke<Submap[]>('get_submaps');
+        } catch (e) {
+            console.error('Failed to load submaps:', e);
+        }
+    };
+
+    const createSubmap = async (name: string, modifiers: string, key: string) => {
+        try {
+            await invoke('create_submap', { name, modifiers, key });
+            await Promise.all([loadKeybinds(), loadSubmaps()]);
+        } catch (e: any) {
+            showToast(`Failed to create submap: ${e}`, 'error');
+        }
+    };
+
+    const renameSubmap = async (oldName: string, newName: string) => {
+        try {
+            await invoke('rename_submap', { oldName, newName });
+            await Promise.all([loadKeybinds(), loadSubmaps()]);
+        } catch (e: any) {
+            showToast(`Failed to rename submap: ${e}`, 'error');
+        }
+    };
+
+    const deleteSubmap = async (name: string) => {
+        try {
+            await invoke('delete_submap', { name });
+            await Promise.all([loadKeybinds(), loadSubmaps()]);
+        } catch (e: any) {
+            showToast(`Failed to delete submap: ${e}`, 'error');
+        }
+    };
+
+    const loadDispatchers = async () => {
+        try {
+            dispatchers.value = await invoke<DispatcherInfo[]>('get_dispatchers');
+        } catch (e) {
+            console.error('Failed to load dispatchers:', e);
+        }
+    };
+
     onMounted(() => {
         loadKeybinds();
+        loadDispatchers();
+        loadSubmaps();
     });
 
     return {
         loading,
         keybinds,
+        conflicts,
+        conflictingIds,
+        warnings,
+        saveBlocked,
+        dispatchers,
+        submaps,
+        errors,
         modifierOptions,
         bindTypes,
         loadKeybinds,
         saveKeybinds,
         addKeybind,
-        removeKeybind
+        removeKeybind,
+        saveKeybind,
+        deleteKeybind,
+        exportCheatsheet,
+        recordKey,
+        captureKey,
+        createSubmap,
+        renameSubmap,
+        deleteSubmap
     };
 }
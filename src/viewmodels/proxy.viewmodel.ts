@@ -0,0 +1,116 @@
+import { ref, onMounted } from 'vue';
+import { invoke } from '@tauri-apps/api/core';
+import type { EnvVar } from './env.viewmodel.ts';
+import type {
+    SystemProxy,
+    SystemProxyReport,
+    ConnectionProxy
+} from '../models/proxy.model.ts';
+import { emptyProxyServer } from '../models/proxy.model.ts';
+import type { ProfileError, ProfileUpdateError } from '../models/wifi.model.ts';
+import { useToast } from '../composables/useToast';
+
+/**
+ * ViewModel for the system proxy (GSettings and env.conf) and per-connection proxies.
+ */
+export function useProxyViewModel() {
+    // --- State ---
+    const loading = ref(false);
+    const saving = ref(false);
+    const proxy = ref<SystemProxy>({
+        mode: 'none',
+        http: emptyProxyServer(),
+        https: emptyProxyServer(),
+        socks: emptyProxyServer(),
+        pac_url: '',
+        ignore_hosts: ['localhost', '127.0.0.0/8', '::1']
+    });
+    const envVars = ref<EnvVar[]>([]);
+    const updateEnv = ref(true);
+    const connectionErrors = ref<ProfileError[]>([]);
+
+    const { showToast } = useToast();
+
+    // --- Actions ---
+
+    /**
+     * Loads the desktop proxy and the proxy variables in env.conf.
+     */
+    const loadProxy = async () => {
+        loading.value = true;
+        try {
+            const report = await invoke<SystemProxyReport>('get_system_proxy');
+            proxy.value = report.proxy;
+            envVars.value = report.env;
+        } catch (e) {
+            console.error("Failed to load proxy settings:", e);
+        } finally {
+            loading.value = false;
+        }
+    };
+
+    /**
+     * Saves the desktop proxy, optionally mirroring it into env.conf.
+     */
+    const saveProxy = async () => {
+        saving.value = true;
+        try {
+            await invoke('set_system_proxy', { proxy: proxy.value, updateEnv: updateEnv.value });
+            await loadProxy();
+            showToast('Proxy settings saved', 'success');
+        } catch (e) {
+            showToast(`Failed to save proxy settings: ${e}`, 'error');
+        } finally {
+            saving.value = false;
+        }
+    };
+
+    /**
+     * Reads the proxy settings of a NetworkManager connection.
+     */
+    const loadConnectionProxy = async (id: string) => {
+        try {
+            return await invoke<ConnectionProxy>('get_connection_proxy', { id });
+        } catch (e) {
+            showToast(`Failed to read connection proxy: ${e}`, 'error');
+            return null;
+        }
+    };
+
+    /**
+     * Saves the proxy settings of a connection; they apply on its next activation.
+     */
+    const saveConnectionProxy = async (id: string, connectionProxy: ConnectionProxy) => {
+        connectionErrors.value = [];
+        try {
+            await invoke('set_connection_proxy', { id, proxy: connectionProxy });
+            showToast('Connection proxy saved', 'success');
+            return true;
+        } catch (e) {
+            const err = e as ProfileUpdateError;
+            if (err.kind === 'invalid') {
+                connectionErrors.value = err.errors;
+                showToast('Please check the highlighted fields', 'error');
+            } else {
+                showToast(`Failed to save connection proxy: ${err.message}`, 'error');
+            }
+            return false;
+        }
+    };
+
+    // --- Lifecycle ---
+    onMounted(loadProxy);
+
+    return {
+        loading,
+        saving,
+        proxy,
+        envVars,
+        updateEnv,
+        connectionErrors,
+        loadProxy,
+        saveProxy,
+        loadConnectionProxy,
+        saveConnectionProxy
+    };
+}
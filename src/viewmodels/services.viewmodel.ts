@@ -0,0 +1,85 @@
+/**
+ * Services ViewModel (Composable)
+ * Logic for managing systemd user services
+ */
+
+import { ref, onMounted } from 'vue';
+import { invoke } from '@tauri-apps/api/core';
+import { useToast } from '../composables/useToast';
+
+export interface UserUnit {
+    name: string;
+    description: string;
+    load_state: string;
+    active_state: string;
+    sub_state: string;
+    unit_file_state: string;
+    user_defined: boolean;
+}
+
+export type UnitAction = 'start' | 'stop' | 'restart' | 'enable' | 'disable';
+
+export function useServicesViewModel() {
+    const loading = ref(false);
+    const units = ref<UserUnit[]>([]);
+    const logs = ref<Record<string, string[]>>({});
+    const { showToast } = useToast();
+
+    // Load user units from backend
+    const loadUnits = async () => {
+        loading.value = true;
+        try {
+            units.value = await invoke<UserUnit[]>('get_user_units');
+        } catch (e: any) {
+            showToast(`Failed to load user services: ${e}`, 'error');
+        } finally {
+            loading.value = false;
+        }
+    };
+
+    // Fetch recent journal lines for a unit
+    const loadLogs = async (name: string, lines = 50) => {
+        try {
+            logs.value[name] = await invoke<string[]>('get_user_unit_logs', { name, lines });
+        } catch (e: any) {
+            showToast(`Failed to load logs: ${e}`, 'error');
+        }
+    };
+
+    // Start, stop, restart, enable or disable a unit
+    const manageUnit = async (name: string, action: UnitAction) => {
+        try {
+            await invoke('manage_user_unit', { name, action });
+            await loadUnits();
+        } catch (e: any) {
+            showToast(`Failed to ${action} ${name}: ${e}`, 'error');
+        }
+    };
+
+    // Move an exec-once startup entry into a generated user unit
+    const convertStartupEntry = async (id: string, unitName: string | null = null) => {
+        try {
+            const name = await invoke<string>('convert_startup_to_unit', { id, unitName });
+            await loadUnits();
+            showToast(`Created ${name}`, 'success');
+            return name;
+        } catch (e: any) {
+            showToast(`Failed to create service: ${e}`, 'error');
+            return null;
+        }
+    };
+
+    onMounted(() => {
+        loadUnits();
+    });
+
+    return {
+        loading,
+        units,
+        logs,
+        loadUnits,
+        loadLogs,
+        manageUnit,
+        convertStartupEntry
+    };
+}
@@ -7,30 +7,67 @@ import { ref, onMounted } from 'vue';
 import { invoke } from '@tauri-apps/api/core';
 import { useToast } from '../composables/useToast';
 
+export type StartupKind = 'exec-once' | 'exec' | 'exec-shutdown';
+
 export interface StartupCommand {
     id: string;
+    kind: StartupKind;
     command: string;
+    enabled: boolean;
+    label: string | null;
+}
+
+export interface AutostartEntry {
+    id: string;
+    name: string;
+    comment: string;
+    exec: string;
+    enabled: boolean;
+    source: 'user' | 'system';
+    path: string;
+}
+
+export interface StartupStatus {
+    id: string;
+    executable: string;
+    running: boolean;
+    pids: number[];
+}
+
+export type TestRunMode = 'hyprctl' | 'direct';
+
+export interface TestRunResult {
+    stdout: string;
+    stderr: string;
+    exit_code: number | null;
+    running: boolean;
 }
 
+const newCommand = (): StartupCommand => ({
+    id: `new-${Date.now()}`,
+    kind: 'exec-once',
+    command: '',
+    enabled: true,
+    label: null
+});
+
 export function useStartupViewModel() {
     const loading = ref(false);
     const startupCommands = ref<StartupCommand[]>([]);
+    const autostartEntries = ref<AutostartEntry[]>([]);
+    const statuses = ref<Record<string, StartupStatus>>({});
+    const testRuns = ref<Record<string, TestRunResult>>({});
+    const testingId = ref<string | null>(null);
     const { showToast } = useToast();
 
     // Load commands from backend
     const loadCommands = async () => {
         loading.value = true;
         try {
-            const commands = await invoke<string[]>('get_startup_commands');
-            // Convert strings to objects with IDs
-            if (commands.length === 0) {
-                startupCommands.value = [{ id: `cmd-${Date.now()}`, command: '' }];
-            } else {
-                startupCommands.value = commands.map((cmd, index) => ({
-                    id: `cmd-${Date.now()}-${index}`,
-                    command: cmd
-                }));
-            }
+            const commands = await invoke<StartupCommand[]>('get_startup_commands');
+            startupCommands.value = commands.length === 0 ? [newCommand()] : commands;
+            autostartEntries.value = await invoke<AutostartEntry[]>('get_autostart_entries');
+            await loadStatus();
         } catch (e: any) {
             showToast('Failed to load startup commands', 'error');
         } finally {
@@ -38,16 +75,42 @@ export function useStartupViewModel() {
         }
     };
 
+    // Check which saved commands currently have a running process
+    const loadStatus = async () => {
+        try {
+            const list = await invoke<StartupStatus[]>('get_startup_status');
+            statuses.value = Object.fromEntries(list.map(status => [status.id, status]));
+        } catch (e: any) {
+            statuses.value = {};
+        }
+    };
+
+    // Launch a command and capture its output for a few seconds
+    const testRun = async (item: StartupCommand, mode: TestRunMode = 'hyprctl') => {
+        if (item.command.trim() === '') return;
+        testingId.value = item.id;
+        try {
+            testRuns.value[item.id] = await invoke<TestRunResult>('test_run_startup_command', {
+                command: item.command,
+                mode,
+                seconds: null
+            });
+            await loadStatus();
+        } catch (e: any) {
+            showToast(`Test run failed: ${e}`, 'error');
+        } finally {
+            testingId.value = null;
+        }
+    };
+
     // Save commands to backend
     const saveCommands = async () => {
         loading.value = true;
         try {
-            // Filter out empty lines before saving and extract command string
-            const toSave = startupCommands.value
-                .map(item => item.command)
-                .filter(cmd => cmd.trim() !== '');
+            // Filter out empty lines before saving
+            const toSave = startupCommands.value.filter(item => item.command.trim() !== '');
 
-            await invoke('save_startup_commands', { commands: toSave });
+            await invoke('save_startup_commands', { entries: toSave });
 
             // Reload to reflect clean state (optional, but good practice)
             await loadCommands();
@@ -61,10 +124,7 @@ export function useStartupViewModel() {
 
     // Add a new empty command row
     const addCommand = () => {
-        startupCommands.value.unshift({
-            id: `new-${Date.now()}`,
-            command: ''
-        });
+        startupCommands.value.unshift(newCommand());
     };
 
     // Remove a command row
@@ -72,10 +132,31 @@ export function useStartupViewModel() {
         startupCommands.value.splice(index, 1);
         // If all removed, keep one empty row
         if (startupCommands.value.length === 0) {
-            startupCommands.value.push({
-                id: `new-${Date.now()}`,
-                command: ''
-            });
+            startupCommands.value.push(newCommand());
+        }
+    };
+
+    // Enable or disable a saved command by commenting it in or out
+    const toggleCommand = async (item: StartupCommand) => {
+        if (item.id.startsWith('new-')) {
+            item.enabled = !item.enabled;
+            return;
+        }
+        try {
+            await invoke('set_startup_entry_enabled', { id: item.id, enabled: !item.enabled });
+            item.enabled = !item.enabled;
+        } catch (e: any) {
+            showToast(`Failed to update startup command: ${e}`, 'error');
+        }
+    };
+
+    // Enable or disable an XDG autostart entry via a Hidden= override
+    const toggleAutostart = async (entry: AutostartEntry) => {
+        try {
+            await invoke('set_autostart_enabled', { id: entry.id, enabled: !entry.enabled });
+            autostartEntries.value = await invoke<AutostartEntry[]>('get_autostart_entries');
+        } catch (e: any) {
+            showToast(`Failed to update autostart entry: ${e}`, 'error');
         }
     };
 
@@ -86,9 +167,17 @@ export function useStartupViewModel() {
     return {
         loading,
         startupCommands,
+        autostartEntries,
+        statuses,
+        testRuns,
+        testingId,
         loadCommands,
+        loadStatus,
+        testRun,
         saveCommands,
         addCommand,
-        removeCommand
+        removeCommand,
+        toggleCommand,
+        toggleAutostart
     };
 }
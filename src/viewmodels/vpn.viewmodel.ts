@@ -1,7 +1,13 @@
 import { ref, computed, onMounted, onUnmounted } from 'vue';
 import { invoke } from '@tauri-apps/api/core';
-import { open } from '@tauri-apps/plugin-dialog';
-import type { VpnConnection } from '../models/vpn.model';
+import { open, save } from '@tauri-apps/plugin-dialog';
+import type {
+    VpnConnection,
+    VpnAutoConnectSettings,
+    VpnPlugin,
+    VpnCreateRequest
+} from '../models/vpn.model';
+import type { ProfileError, ProfileUpdateError } from '../models/wifi.model';
 import { useToast } from '../composables/useToast';
 
 /**
@@ -13,6 +19,13 @@ export function useVpnViewModel() {
     const connections = ref<VpnConnection[]>([]);
     const loading = ref(false);
     const connectingUuid = ref<string | null>(null);
+    const plugins = ref<VpnPlugin[]>([]);
+    const createErrors = ref<ProfileError[]>([]);
+    const autoConnect = ref<VpnAutoConnectSettings>({
+        enabled: false,
+        vpn_uuid: '',
+        trusted_ssids: []
+    });
 
     // Modal and form state for adding new connections
     const showAddModal = ref(false);
@@ -27,6 +40,19 @@ export function useVpnViewModel() {
 
     // --- Computed ---
 
+    /**
+     * Import types offered in the add modal: WireGuard plus the installed plugins.
+     */
+    const importTypes = computed(() => [
+        { name: 'wireguard', label: 'WireGuard' },
+        ...plugins.value.map((p) => ({ name: p.name, label: p.label }))
+    ]);
+
+    /**
+     * Installed plugins that have a creation form.
+     */
+    const creatablePlugins = computed(() => plugins.value.filter((p) => p.can_create));
+
     /**
      * Connections sorted by status (Active > Connecting > Inactive) and then by name.
      */
@@ -175,12 +201,118 @@ export function useVpnViewModel() {
         }
     };
 
+    /**
+     * Deletes a VPN connection.
+     */
+    const deleteConnection = async (conn: VpnConnection) => {
+        try {
+            await invoke('delete_vpn', { uuid: conn.uuid });
+            showToast(`Deleted ${conn.name}`, 'success');
+            await fetchConnections();
+        } catch (e) {
+            showToast(`Failed to delete ${conn.name}: ${e}`, 'error');
+        }
+    };
+
+    const renameConnection = async (conn: VpnConnection, name: string) => {
+        try {
+            await invoke('rename_vpn', { uuid: conn.uuid, name });
+            await fetchConnections();
+        } catch (e) {
+            showToast(`Failed to rename ${conn.name}: ${e}`, 'error');
+        }
+    };
+
+    const setAutoconnect = async (conn: VpnConnection, enabled: boolean) => {
+        try {
+            await invoke('set_vpn_autoconnect', { uuid: conn.uuid, enabled });
+            await fetchConnections();
+        } catch (e) {
+            showToast(`Failed to update autoconnect: ${e}`, 'error');
+        }
+    };
+
+    /**
+     * Exports a connection: .conf for WireGuard, the plugin's format (e.g. .ovpn) otherwise.
+     */
+    const exportConnection = async (conn: VpnConnection) => {
+        const extension = conn.type_name === 'wireguard' ? 'conf' : 'ovpn';
+        try {
+            const filePath = await save({
+                defaultPath: `${conn.name}.${extension}`,
+                filters: [{ name: 'VPN Configuration', extensions: [extension] }]
+            });
+            if (!filePath) return;
+
+            await invoke('export_vpn', { uuid: conn.uuid, filePath });
+            showToast(`Exported ${conn.name}`, 'success');
+        } catch (e) {
+            showToast(`Failed to export ${conn.name}: ${e}`, 'error');
+        }
+    };
+
+    /**
+     * Discovers the installed NetworkManager VPN plugins.
+     */
+    const loadPlugins = async () => {
+        try {
+            plugins.value = await invoke<VpnPlugin[]>('get_vpn_plugins');
+        } catch (e) {
+            console.error("Failed to load VPN plugins:", e);
+        }
+    };
+
+    /**
+     * Creates an OpenConnect, L2TP/IPsec, strongSwan or PPTP connection.
+     */
+    const createConnection = async (request: VpnCreateRequest) => {
+        createErrors.value = [];
+        try {
+            await invoke<string>('create_vpn_connection', { request });
+            showToast(`Created ${request.name}`, 'success');
+            await fetchConnections();
+            return true;
+        } catch (e) {
+            const err = e as ProfileUpdateError;
+            if (err.kind === 'invalid') {
+                createErrors.value = err.errors;
+                showToast('Please check the highlighted fields', 'error');
+            } else {
+                showToast(`Failed to create VPN: ${err.message}`, 'error');
+            }
+            return false;
+        }
+    };
+
+    /**
+     * Loads the setting that brings a VPN up on untrusted Wi-Fi networks.
+     */
+    const loadAutoConnect = async () => {
+        try {
+            autoConnect.value = await invoke<VpnAutoConnectSettings>('get_vpn_auto_connect');
+        } catch (e) {
+            console.error("Failed to load VPN auto-connect settings:", e);
+        }
+    };
+
+    const saveAutoConnect = async () => {
+        try {
+            await invoke('set_vpn_auto_connect', { settings: autoConnect.value });
+            await loadAutoConnect();
+            showToast('Untrusted network settings saved', 'success');
+        } catch (e) {
+            showToast(`Failed to save settings: ${e}`, 'error');
+        }
+    };
+
     // --- Lifecycle ---
     let refreshInterval: ReturnType<typeof setInterval> | null = null;
 
     onMounted(async () => {
         loading.value = true;
         await fetchConnections();
+        await loadAutoConnect();
+        await loadPlugins();
         // Periodically refresh connection status
         refreshInterval = setInterval(fetchConnections, 5000);
     });
@@ -202,6 +334,17 @@ export function useVpnViewModel() {
         formData,
         openAddModal,
         pickFile,
-        saveConnection
+        saveConnection,
+        deleteConnection,
+        renameConnection,
+        setAutoconnect,
+        exportConnection,
+        autoConnect,
+        saveAutoConnect,
+        plugins,
+        importTypes,
+        creatablePlugins,
+        createErrors,
+        createConnection
     };
 }
@@ -1,6 +1,28 @@
 import { ref, onMounted, onUnmounted } from 'vue';
 import { invoke } from '@tauri-apps/api/core';
-import type { WifiNetwork, WifiConfig } from '../models/wifi.model.ts';
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
+import type {
+    WifiNetwork,
+    WifiConfig,
+    SavedNetwork,
+    Metered,
+    MacRandomization,
+    WifiConnectRequest,
+    WifiConnectError,
+    ProfileError,
+    IpConfigReport,
+    ProfileUpdateError,
+    ActiveIp,
+    HotspotConfig,
+    HotspotStatus,
+    WifiBand,
+    WifiNetworkDetails,
+    WifiLinkInfo,
+    RadioChanged,
+    NetworksChanged,
+    ConnectionChanged
+} from '../models/wifi.model.ts';
+import { emptyIpSettings } from '../models/wifi.model.ts';
 import { useToast } from '../composables/useToast';
 
 /**
@@ -13,6 +35,13 @@ export function useWifiViewModel() {
     const networks = ref<WifiNetwork[]>([]);
     const loading = ref(false);
     const connectingSsid = ref<string | null>(null);
+    const savedNetworks = ref<SavedNetwork[]>([]);
+    const profileErrors = ref<ProfileError[]>([]);
+    const hotspot = ref<HotspotStatus | null>(null);
+    const hotspotErrors = ref<ProfileError[]>([]);
+    const shareQrSvg = ref<string | null>(null);
+    const networkDetails = ref<WifiNetworkDetails[]>([]);
+    const activeLink = ref<WifiLinkInfo | null>(null);
 
     const { showToast } = useToast();
 
@@ -21,37 +50,34 @@ export function useWifiViewModel() {
     const savingConfig = ref(false);
     const selectedSsid = ref('');
     const config = ref<WifiConfig>({
-        method: 'auto',
-        ip_address: '',
-        prefix: 24,
-        gateway: '',
-        dns: ''
+        ipv4: emptyIpSettings(),
+        ipv6: emptyIpSettings(),
+        ipv6_privacy: 'default'
     });
+    const activeConfig = ref<{ ipv4: ActiveIp; ipv6: ActiveIp } | null>(null);
+    const configErrors = ref<ProfileError[]>([]);
 
-    let scanInterval: ReturnType<typeof setInterval> | null = null;
+    let unlisteners: UnlistenFn[] = [];
 
     // --- Actions ---
 
     /**
-     * Starts periodic background scanning for networks.
+     * Subscribes to the Wi-Fi events pushed by the backend monitor.
      */
-    const startScanInterval = () => {
-        if (scanInterval) clearInterval(scanInterval);
-        scanInterval = setInterval(() => {
-            if (isEnabled.value && !showConfigModal.value) {
-                scan(true);
-            }
-        }, 10000);
-    };
-
-    /**
-     * Stops the periodic scan interval.
-     */
-    const stopScanInterval = () => {
-        if (scanInterval) {
-            clearInterval(scanInterval);
-            scanInterval = null;
-        }
+    const subscribe = async () => {
+        unlisteners = await Promise.all([
+            listen<RadioChanged>('wifi-radio-changed', (event) => {
+                isEnabled.value = event.payload.enabled;
+                if (!event.payload.enabled) networks.value = [];
+            }),
+            listen<NetworksChanged>('wifi-networks-changed', (event) => {
+                networks.value = event.payload.networks;
+            }),
+            listen<ConnectionChanged>('wifi-connection-changed', async (event) => {
+                activeLink.value = event.payload.link;
+                await loadSavedNetworks();
+            })
+        ]);
     };
 
     /**
@@ -91,10 +117,8 @@ export function useWifiViewModel() {
             await invoke('toggle_wifi', { enable: isEnabled.value });
             if (isEnabled.value) {
                 await scan(false);
-                startScanInterval();
             } else {
                 networks.value = [];
-                stopScanInterval();
             }
         } catch (e) {
             isEnabled.value = !isEnabled.value;
@@ -120,17 +144,56 @@ export function useWifiViewModel() {
         }
     };
 
+    /**
+     * Joins a network from a full profile: hidden SSID, WPA3-SAE or 802.1X.
+     * Field errors are kept in `profileErrors` so the form can highlight them.
+     */
+    const connectNetwork = async (request: WifiConnectRequest) => {
+        if (connectingSsid.value) return false;
+
+        connectingSsid.value = request.ssid;
+        profileErrors.value = [];
+        try {
+            await invoke<string>('connect_wifi_network', { request });
+            await scan(true);
+            await loadSavedNetworks();
+            showToast(`Connected to ${request.ssid}`, 'success');
+            return true;
+        } catch (e) {
+            const err = e as WifiConnectError;
+            switch (err.kind) {
+                case 'invalid':
+                    profileErrors.value = err.errors;
+                    showToast('Please check the highlighted fields', 'error');
+                    break;
+                case 'not_found':
+                    showToast(`Network ${err.ssid} was not found`, 'error');
+                    break;
+                case 'auth_failed':
+                    showToast('Authentication failed, check your credentials', 'error');
+                    break;
+                default:
+                    showToast('Connection failed: ' + err.message, 'error');
+            }
+            return false;
+        } finally {
+            connectingSsid.value = null;
+        }
+    };
+
     /**
      * Opens the configuration modal for a specific network.
      */
     const openConfig = async (net: WifiNetwork) => {
         selectedSsid.value = net.ssid;
         try {
-            const conf = await invoke<WifiConfig>('get_wifi_config', { ssid: net.ssid });
-            config.value = conf;
+            const report = await invoke<IpConfigReport>('get_wifi_config', { ssid: net.ssid });
+            config.value = report.configured;
+            activeConfig.value = report.active;
+            configErrors.value = [];
             showConfigModal.value = true;
         } catch (e) {
-            showToast('Failed to retrieve configuration', 'error');
+            showToast(`Failed to retrieve configuration: ${e}`, 'error');
         }
     };
 
@@ -146,8 +209,8 @@ export function useWifiViewModel() {
      */
     const saveConfig = async () => {
         savingConfig.value = true;
+        configErrors.value = [];
         try {
-            config.value.prefix = Number(config.value.prefix);
             await invoke('set_wifi_config', {
                 ssid: selectedSsid.value,
                 config: config.value
@@ -156,23 +219,174 @@ export function useWifiViewModel() {
             await scan(true);
             showToast('Network settings saved successfully', 'success');
         } catch (e) {
-            showToast(`Failed to apply settings: ${e}`, 'error');
+            const err = e as ProfileUpdateError;
+            if (err.kind === 'invalid') {
+                configErrors.value = err.errors;
+                showToast('Please check the highlighted fields', 'error');
+            } else {
+                showToast(`Failed to apply settings: ${err.message}`, 'error');
+            }
         } finally {
             savingConfig.value = false;
         }
     };
 
+    /**
+     * Loads saved Wi-Fi profiles with their connection preferences.
+     */
+    const loadSavedNetworks = async () => {
+        try {
+            savedNetworks.value = await invoke<SavedNetwork[]>('get_saved_networks');
+        } catch (e) {
+            console.error("Failed to load saved networks:", e);
+        }
+    };
+
+    /**
+     * Runs a saved-network command and refreshes the list afterwards.
+     */
+    const updateSaved = async (command: string, args: Record<string, unknown>, failure: string) => {
+        try {
+            await invoke(command, args);
+            await loadSavedNetworks();
+        } catch (e) {
+            showToast(`${failure}: ${e}`, 'error');
+        }
+    };
+
+    /**
+     * Deletes a saved network and its stored password.
+     */
+    const forgetNetwork = async (net: SavedNetwork) => {
+        await updateSaved('forget_wifi_network', { uuid: net.uuid }, 'Failed to forget network');
+        await scan(true);
+    };
+
+    const setAutoconnect = (net: SavedNetwork, enabled: boolean) =>
+        updateSaved('set_wifi_autoconnect', { uuid: net.uuid, enabled }, 'Failed to update autoconnect');
+
+    const setPriority = (net: SavedNetwork, priority: number) =>
+        updateSaved('set_wifi_priority', { uuid: net.uuid, priority: Number(priority) }, 'Failed to update priority');
+
+    const setMetered = (net: SavedNetwork, metered: Metered) =>
+        updateSaved('set_wifi_metered', { uuid: net.uuid, metered }, 'Failed to update metered setting');
+
+    const setMacRandomization = (net: SavedNetwork, mode: MacRandomization) =>
+        updateSaved('set_wifi_mac_randomization', { uuid: net.uuid, mode }, 'Failed to update MAC address setting');
+
+    /**
+     * Loads every access point per network, with band, channel and security flags.
+     */
+    const loadNetworkDetails = async () => {
+        try {
+            networkDetails.value = await invoke<WifiNetworkDetails[]>('get_wifi_access_points');
+        } catch (e) {
+            console.error("Failed to load access points:", e);
+        }
+    };
+
+    /**
+     * Loads bitrate, addressing and access point of the current connection.
+     */
+    const loadActiveLink = async () => {
+        try {
+            activeLink.value = await invoke<WifiLinkInfo | null>('get_active_wifi_link');
+        } catch (e) {
+            console.error("Failed to load active Wi-Fi link:", e);
+        }
+    };
+
+    /**
+     * Pins a saved network to an access point and/or band; null and 'auto' unpin.
+     */
+    const setPin = (net: SavedNetwork, bssid: string | null, band: WifiBand) =>
+        updateSaved('set_wifi_pin', { uuid: net.uuid, bssid, band }, 'Failed to pin network');
+
+    /**
+     * Renders a saved network's credentials as a QR code (SVG markup).
+     */
+    const shareNetwork = async (net: SavedNetwork) => {
+        try {
+            shareQrSvg.value = await invoke<string>('get_wifi_share_qr', { uuid: net.uuid });
+        } catch (e) {
+            showToast(`Cannot share network: ${e}`, 'error');
+        }
+    };
+
+    const closeShare = () => {
+        shareQrSvg.value = null;
+    };
+
+    /**
+     * Loads the saved hotspot settings and whether it is running.
+     */
+    const loadHotspot = async () => {
+        try {
+            hotspot.value = await invoke<HotspotStatus>('get_hotspot_status');
+        } catch (e) {
+            console.error("Failed to load hotspot status:", e);
+        }
+    };
+
+    /**
+     * Starts the hotspot; field errors are kept in `hotspotErrors`.
+     */
+    const startHotspot = async (hotspotConfig: HotspotConfig) => {
+        hotspotErrors.value = [];
+        try {
+            await invoke<string>('start_hotspot', { config: hotspotConfig });
+            await loadHotspot();
+            showToast(`Hotspot ${hotspotConfig.ssid} started`, 'success');
+            return true;
+        } catch (e) {
+            const err = e as ProfileUpdateError;
+            if (err.kind === 'invalid') {
+                hotspotErrors.value = err.errors;
+                showToast('Please check the highlighted fields', 'error');
+            } else {
+                showToast(`Failed to start hotspot: ${err.message}`, 'error');
+            }
+            return false;
+        }
+    };
+
+    const stopHotspot = async () => {
+        try {
+            await invoke('stop_hotspot');
+            await loadHotspot();
+        } catch (e) {
+            showToast(`Failed to stop hotspot: ${e}`, 'error');
+        }
+    };
+
+    /**
+     * Shows the hotspot credentials as a QR code.
+     */
+    const shareHotspot = async () => {
+        try {
+            shareQrSvg.value = await invoke<string>('get_hotspot_qr');
+        } catch (e) {
+            showToast(`Cannot share hotspot: ${e}`, 'error');
+        }
+    };
+
     // --- Lifecycle ---
     onMounted(async () => {
+        await subscribe();
+        // Rescans run in the backend only while this page is open
+        await invoke('set_wifi_page_active', { active: true });
         await checkStatus();
         if (isEnabled.value) {
             await scan(false);
-            startScanInterval();
         }
+        await loadSavedNetworks();
+        await loadHotspot();
+        await loadActiveLink();
     });
 
     onUnmounted(() => {
-        stopScanInterval();
+        unlisteners.forEach((unlisten) => unlisten());
+        invoke('set_wifi_page_active', { active: false });
     });
 
     return {
@@ -184,11 +398,36 @@ export function useWifiViewModel() {
         savingConfig,
         selectedSsid,
         config,
+        activeConfig,
+        configErrors,
         toggleWifi,
         scan,
         connect,
+        connectNetwork,
+        profileErrors,
         openConfig,
         closeConfig,
-        saveConfig
+        saveConfig,
+        savedNetworks,
+        loadSavedNetworks,
+        forgetNetwork,
+        setAutoconnect,
+        setPriority,
+        setMetered,
+        setMacRandomization,
+        shareQrSvg,
+        shareNetwork,
+        closeShare,
+        hotspot,
+        hotspotErrors,
+        loadHotspot,
+        startHotspot,
+        stopHotspot,
+        shareHotspot,
+        networkDetails,
+        loadNetworkDetails,
+        activeLink,
+        loadActiveLink,
+        setPin
     };
 }
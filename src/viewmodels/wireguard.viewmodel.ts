@@ -0,0 +1,157 @@
+import { ref, watch } from 'vue';
+import { invoke } from '@tauri-apps/api/core';
+import { save } from '@tauri-apps/plugin-dialog';
+import type { WireguardConfig, WireguardKeypair, WireguardPeer } from '../models/vpn.model';
+import { emptyWireguardPeer } from '../models/vpn.model';
+import type { ProfileError, ProfileUpdateError } from '../models/wifi.model';
+import { useToast } from '../composables/useToast';
+
+/**
+ * ViewModel for the WireGuard tunnel editor.
+ * Generates keys, edits the interface and its peers, saves to NetworkManager and exports wg-quick files.
+ */
+export function useWireguardViewModel() {
+    // --- State ---
+    const editingUuid = ref<string | null>(null);
+    const config = ref<WireguardConfig>(emptyConfig());
+    const publicKey = ref('');
+    const errors = ref<ProfileError[]>([]);
+    const saving = ref(false);
+
+    const { showToast } = useToast();
+
+    function emptyConfig(): WireguardConfig {
+        return {
+            name: '',
+            interface: '',
+            private_key: '',
+            addresses: [],
+            dns: [],
+            mtu: null,
+            listen_port: null,
+            peers: [emptyWireguardPeer()]
+        };
+    }
+
+    // Keep the shown public key in sync with the private key
+    watch(() => config.value.private_key, async (privateKey) => {
+        try {
+            publicKey.value = privateKey
+                ? await invoke<string>('get_wireguard_public_key', { privateKey })
+                : '';
+        } catch {
+            publicKey.value = '';
+        }
+    });
+
+    // --- Actions ---
+
+    /**
+     * Starts a new tunnel with a freshly generated keypair.
+     */
+    const newTunnel = async () => {
+        editingUuid.value = null;
+        errors.value = [];
+        config.value = emptyConfig();
+        await generateKeypair();
+    };
+
+    /**
+     * Loads an existing NetworkManager WireGuard connection into the editor.
+     */
+    const editTunnel = async (uuid: string) => {
+        try {
+            config.value = await invoke<WireguardConfig>('get_wireguard_connection', { uuid });
+            editingUuid.value = uuid;
+            errors.value = [];
+            return true;
+        } catch (e) {
+            showToast(`Failed to load tunnel: ${e}`, 'error');
+            return false;
+        }
+    };
+
+    const generateKeypair = async () => {
+        const keypair = await invoke<WireguardKeypair>('generate_wireguard_keypair');
+        config.value.private_key = keypair.private_key;
+    };
+
+    const generatePresharedKey = async (peer: WireguardPeer) => {
+        peer.preshared_key = await invoke<string>('generate_wireguard_preshared_key');
+    };
+
+    const addPeer = () => {
+        config.value.peers.push(emptyWireguardPeer());
+    };
+
+    const removePeer = (index: number) => {
+        config.value.peers.splice(index, 1);
+    };
+
+    /**
+     * Returns the validation message for a field, e.g. `peers[0].endpoint`.
+     */
+    const fieldError = (field: string) => errors.value.find((e) => e.field === field)?.message;
+
+    /**
+     * Saves the tunnel as a NetworkManager connection.
+     */
+    const saveTunnel = async () => {
+        saving.value = true;
+        errors.value = [];
+        try {
+            editingUuid.value = await invoke<string>('save_wireguard_connection', {
+                uuid: editingUuid.value,
+                config: config.value
+            });
+            showToast(`Tunnel ${config.value.name} saved`, 'success');
+            return true;
+        } catch (e) {
+            const err = e as ProfileUpdateError;
+            if (err.kind === 'invalid') {
+                errors.value = err.errors;
+                showToast('Please check the highlighted fields', 'error');
+            } else {
+                showToast(`Failed to save tunnel: ${err.message}`, 'error');
+            }
+            return false;
+        } finally {
+            saving.value = false;
+        }
+    };
+
+    /**
+     * Writes the tunnel to a wg-quick .conf file chosen by the user.
+     */
+    const exportTunnel = async () => {
+        try {
+            const filePath = await save({
+                defaultPath: `${config.value.interface || 'wg0'}.conf`,
+                filters: [{ name: 'WireGuard Configuration', extensions: ['conf'] }]
+            });
+            if (!filePath) return;
+
+            await invoke('export_wireguard_config', { config: config.value, filePath });
+            showToast('Configuration exported', 'success');
+        } catch (e) {
+            showToast(`Failed to export configuration: ${e}`, 'error');
+        }
+    };
+
+    return {
+        editingUuid,
+        config,
+        publicKey,
+        errors,
+        saving,
+        newTunnel,
+        editTunnel,
+        generateKeypair,
+        generatePresharedKey,
+        addPeer,
+        removePeer,
+        fieldError,
+        saveTunnel,
+        exportTunnel
+    };
+}